@@ -0,0 +1,295 @@
+extern crate pigeon_parrot as parrot;
+
+use std::{ops::Deref};
+use parrot::{
+    pipeline::{Plumber,
+        PipelineDescription,
+        PipelineCore,
+        Pipeline, Blending, BlendFactor, BlendOp, Set,
+    },
+    buffers::{UniformBuffer, VertexBuffer, IndexBuffer, FrameBuffer},
+    binding::{Binding, BindingType},
+    vertex::VertexFormat,
+    shader::ShaderFile,
+    painter::PassOp, RenderPassExtention, Painter, Sampler,
+};
+use wgpu::ShaderStages;
+use winit::event::{Event, WindowEvent};
+use winit::event_loop::ControlFlow;
+use euclid::Size2D;
+
+// =======================================================================
+//  I recommend reading [learn wgpu](https://sotrh.github.io/learn-wgpu/)
+//  before using parrot and reading this example
+// 
+// This example is intended to show rendering to a frame buffer and then
+// sampling it in a second pass. The first pass draws a triangle into the
+// frame buffer and the second blurs it onto the screen
+// =======================================================================
+
+/// The struct that will represent our verticies. As we are only drawing 2D shapes, we have a position composed of 2 floats
+/// The vertices must implement [`bytemuck::Pod`], [`bytemuck::Zeroable`] and [`Copy`]
+/// They also must be #[repr(C)]
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Vertex {
+    position: [f32; 2],
+    color: [f32; 3],
+}
+
+impl Vertex {
+    // Create a new vertex
+    pub fn new(x: f32, y: f32, r: f32, g: f32, b: f32) -> Self {
+        Self {
+            position: [x,y],
+            color: [r,g,b],
+        }
+    }
+}
+
+/// This is our pipeline, it should contain everything we need as so we can deref to [`PipelineCore`] when we render.
+pub struct ScenePipe {
+    /// This is the pipeline core that we will return. Normally you would instead store your [`parrot::binding::BindingGroup`] and [`parrot::buffers::UniformBuffer`] and create it from those, but as this is a simple pipeline with no textures or uniforms, I won't bother
+    pipeline: PipelineCore,
+    /// The vertex buffer for our pipeline
+    vertices: VertexBuffer,
+}
+
+/// This is what allows our rendering functions to render with our pipeline
+impl Deref for ScenePipe {
+    type Target = PipelineCore;
+
+    /// This function returns our [`PipelineCore`]. Normally we would create this, but as this is a simple triangle pipeline, I won't bother
+    fn deref(&self) -> &Self::Target {
+        &self.pipeline
+    }
+}
+
+impl<'a> Plumber<'a> for ScenePipe {
+    /// Typically contains the information required to update our uniform buffer. We don't have one so we use ()
+    type PrepareContext = ();
+    /// Once again we have no uniforms, so we use ()
+    type Uniforms = ();
+
+    /// This is the function that will be used to create our pipeline
+    fn setup(pipe: Pipeline, painter: &Painter) -> Self {
+        let pipeline = PipelineCore {
+            // The actual pipeline
+            pipeline: pipe,
+            // Our bindings (we have none)
+            bindings: vec![],
+            // Our uniforms (we have none)
+            uniforms: vec![]
+        };
+
+        // The three default verticies that will make up our triangle. If you want, this can be blank.
+        let vertices = [Vertex::new(0.0, 0.5, 1.0, 0.0, 0.0), Vertex::new(-0.5, -0.5, 0.0, 1.0, 0.0), Vertex::new(0.5, -0.5, 0.0, 0.0, 1.0)];
+        // Create a vertex buffer, ours contains three verticies
+        let vertex = painter.vertex_buffer::<Vertex>(&vertices, Some("Triangle vertex buffer"));
+
+        Self {
+            pipeline,
+            vertices: vertex
+        }
+    }
+
+    // This function is used to update our uniform buffer. As we don't have one, we return a blank vector
+    fn prepare(&'a mut self, _context: Self::PrepareContext, _: &mut Painter) -> Vec<(&'a mut UniformBuffer, Vec<Self::Uniforms>)> {
+        vec![]
+    }
+
+    // This function is used to describe the layout of our pipeline
+    fn description() -> PipelineDescription<'a> {
+        PipelineDescription::new(
+            &[VertexFormat::Floatx2, VertexFormat::Floatx3], // Layout of 2 floats for position, 3 floats for color
+            None, // Has no bindings, so left empty
+            ShaderFile::Wgsl(include_str!("./shaders/triangle.wgsl")), // Takes in triangle shader
+            Some("Triangle pipeline") // Name of pipeline
+        )
+    }
+}
+
+
+/// The vertices of the quad that covers the screen in the blur pass
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct QuadVertex {
+    position: [f32; 2],
+    tex_coords: [f32; 2],
+}
+
+impl QuadVertex {
+    // Create a new vertex
+    pub fn new(x: f32, y: f32, tx: f32, ty: f32) -> Self {
+        Self {
+            position: [x,y],
+            tex_coords: [tx,ty],
+        }
+    }
+}
+
+/// The pipeline that samples the frame buffer and blurs it onto the screen
+pub struct BlurPipe {
+    /// The pipeline core. Its binding is created once we have a frame buffer to sample
+    pipeline: PipelineCore,
+    /// The quad covering the screen
+    vertices: VertexBuffer,
+    /// The indicies of the quad
+    index: IndexBuffer,
+    /// The sampler used to read the frame buffer
+    sampler: Sampler,
+}
+
+impl Deref for BlurPipe {
+    type Target = PipelineCore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.pipeline
+    }
+}
+
+impl BlurPipe {
+    /// Sample a new frame buffer. This needs to be called whenever the frame buffer is recreated
+    fn bind_frame_buffer(&mut self, painter: &Painter, fb: &FrameBuffer) {
+        let layout = &self.pipeline.pipeline.layout.b_layouts[0];
+        let binding = painter.binding_group(layout, &[fb, &self.sampler], Some("Frame buffer bind group")).unwrap();
+        self.pipeline.bindings = vec![binding];
+    }
+}
+
+impl<'a> Plumber<'a> for BlurPipe {
+    type PrepareContext = ();
+    type Uniforms = ();
+
+    fn setup(pipe: Pipeline, painter: &Painter) -> Self {
+        // A quad covering the whole screen
+        let vertices = [QuadVertex::new(-1.0, 1.0, 0.0, 0.0), QuadVertex::new(1.0, 1.0, 1.0, 0.0), QuadVertex::new(1.0, -1.0, 1.0, 1.0), QuadVertex::new(-1.0, -1.0, 0.0, 1.0)];
+        let vertices = painter.vertex_buffer::<QuadVertex>(&vertices, Some("Blur vertex buffer"));
+        let index = painter.index_buffer(&[0, 1, 2, 2, 3, 0], Some("Blur index buffer"));
+        // Linear filtering makes the blur a little smoother
+        let sampler = painter.sampler(wgpu::FilterMode::Linear, wgpu::FilterMode::Linear, Some("Frame buffer sampler"));
+
+        Self {
+            pipeline: PipelineCore {
+                pipeline: pipe,
+                bindings: vec![],
+                uniforms: vec![],
+            },
+            vertices,
+            index,
+            sampler,
+        }
+    }
+
+    fn prepare(&'a mut self, _context: Self::PrepareContext, _: &mut Painter) -> Vec<(&'a mut UniformBuffer, Vec<Self::Uniforms>)> {
+        vec![]
+    }
+
+    fn description() -> PipelineDescription<'a> {
+        PipelineDescription::new(
+            &[VertexFormat::Floatx2, VertexFormat::Floatx2], // Layout of 2 floats for position, 2 floats for texture coords
+            Some(&[
+                Set(&[
+                    // The frame buffer we drew the scene to
+                    Binding {
+                        binding: BindingType::Texture { multisampled: false, view_dimension: wgpu::TextureViewDimension::D2 },
+                        stage: ShaderStages::FRAGMENT,
+                    },
+                    // A sampler for the frame buffer
+                    Binding {
+                        binding: BindingType::Sampler,
+                        stage: ShaderStages::FRAGMENT,
+                    }
+                    ], Some("Blur bind group")
+                )
+            ]),
+            ShaderFile::Wgsl(include_str!("./shaders/blur.wgsl")), // Takes in the blur shader
+            Some("Blur pipeline") // Name of pipeline
+        )
+    }
+}
+
+fn main() {
+    // Initialise the logging output at info level only from parrot
+    env_logger::builder().filter_module("pigeon_parrot", log::LevelFilter::Info).init();
+
+    // Create an event loop
+    let event_loop = winit::event_loop::EventLoop::new();
+    // Create a window to draw to
+    let window = winit::window::WindowBuilder::new().with_title("Blur :D").build(&event_loop).unwrap();
+
+    // Create a wgpu instance
+    let instance = wgpu::Instance::new(wgpu::Backends::all());
+    let surface = unsafe { instance.create_surface(&window) };
+
+    // Create the painter
+    let mut painter = pollster::block_on(parrot::Painter::for_surface(surface, &instance, 1)).unwrap();
+
+    // Get the size of the window
+    let winsize = window.inner_size();
+    let size = Size2D::new(winsize.width, winsize.height);
+
+    // Get the preferred texture format for the surface
+    let pref_format = painter.preferred_format();
+
+    // Configure the surface
+    painter.configure(size, wgpu::PresentMode::Fifo, pref_format).unwrap();
+
+    // Create our pipelines. Both draw to textures with the surface's format, so the frame buffer must use it too
+    let blending = Blending::new(BlendFactor::One, BlendFactor::Zero, BlendOp::Add);
+    let scene = painter.pipeline_no_depth::<ScenePipe>(blending, pref_format, Some("Scene shader"));
+    let mut blur = painter.pipeline_no_depth::<BlurPipe>(Blending::default(), pref_format, Some("Blur shader"));
+
+    // Create the frame buffer the scene is drawn to and let the blur pipeline sample it
+    let mut frame_buffer = painter.create_frame_buffer_no_depth(size, pref_format, Some("Scene frame buffer"));
+    blur.bind_frame_buffer(&painter, &frame_buffer);
+
+    // Initiate the event loop
+    event_loop.run(move |event, _, control_flow| {
+        // Only update the event loop if input is recieved
+        *control_flow = ControlFlow::Wait;
+
+        match event {
+            // Window event
+            Event::WindowEvent { event: win_event, .. } => {
+                match win_event {
+                    // Close if a close request is detected
+                    WindowEvent::CloseRequested => {
+                        println!("The close button was pressed; stopping");
+                        *control_flow = ControlFlow::Exit
+                    },
+                    // Update the surface and frame buffer if resized
+                    WindowEvent::Resized(size) => {
+                        let size = Size2D::new(size.width, size.height);
+                        painter.configure(size, wgpu::PresentMode::Fifo, pref_format).unwrap();
+                        frame_buffer = painter.create_frame_buffer_no_depth(size, pref_format, Some("Scene frame buffer"));
+                        blur.bind_frame_buffer(&painter, &frame_buffer);
+                    }
+                    _ => ()
+                }
+            },
+            Event::RedrawRequested(_) => {
+                // First pass: draw the triangle into the frame buffer
+                let mut frame = painter.frame();
+                painter.render_to(&mut frame, &frame_buffer, PassOp::Clear(parrot::color::Rgba::new(0.1, 0.2, 0.3, 1.0)), |pass| {
+                    pass.set_parrot_pipeline(&scene);
+                    pass.draw_buffer_range(&scene.vertices, 0..3);
+                });
+
+                // Second pass: blur the frame buffer onto the screen
+                let current_surface = painter.current_frame_no_depth().unwrap();
+                {
+                    let mut pass = frame.pass(PassOp::Clear(parrot::color::Rgba::new(0.0, 0.0, 0.0, 1.0)), &current_surface, None);
+                    pass.set_parrot_pipeline(&blur);
+                    pass.set_parrot_vertex_buffer(&blur.vertices);
+                    pass.set_parrot_index_buffer(&blur.index);
+                    pass.draw_parrot_indexed(0..blur.index.size, 0..1);
+                }
+
+                // Present our frame
+                painter.present(frame);
+            }
+            _ => ()
+        }
+    });
+}
@@ -0,0 +1,69 @@
+extern crate pigeon_parrot as parrot;
+
+use parrot::{
+    binding::{Binding, BindingType},
+    pipeline::Set,
+    shader::ShaderFile,
+    ComputePassExtention,
+};
+
+// =======================================================================
+//  I recommend reading [learn wgpu](https://sotrh.github.io/learn-wgpu/)
+//  before using parrot and reading this example
+//
+// This example is intended to show a compute shader doubling some numbers
+// in a storage buffer which are then read back to the cpu
+// =======================================================================
+
+fn main() {
+    // Initialise the logging output at info level only from parrot
+    env_logger::builder().filter_module("pigeon_parrot", log::LevelFilter::Info).init();
+
+    // Create an event loop
+    let event_loop = winit::event_loop::EventLoop::new();
+    // Create a window. We don't draw anything to it, but the painter needs a surface
+    let window = winit::window::WindowBuilder::new().with_title("Compute :D").with_visible(false).build(&event_loop).unwrap();
+
+    // Create a wgpu instance
+    let instance = wgpu::Instance::new(wgpu::Backends::all());
+    let surface = unsafe { instance.create_surface(&window) };
+
+    // Create the painter
+    let mut painter = pollster::block_on(parrot::Painter::for_surface(surface, &instance, 1)).unwrap();
+
+    // The numbers we want doubled
+    let numbers: Vec<u32> = (1..=8).collect();
+    let storage = painter.storage_buffer(&numbers, Some("Numbers buffer"));
+
+    // Create our compute pipeline. It has one set containing our storage buffer and uses the default entry point of main
+    let pipeline = painter.compute_pipeline(
+        Some(&[Set(&[Binding {
+            binding: BindingType::StorageBuffer { read_only: false },
+            stage: wgpu::ShaderStages::COMPUTE,
+        }], Some("Numbers bind group"))]),
+        ShaderFile::Wgsl(include_str!("./shaders/compute.wgsl")),
+        None,
+        Some("Double pipeline"),
+    );
+    let binding = painter.binding_group(&pipeline.layout.b_layouts[0], &[&storage], Some("Numbers binding")).unwrap();
+
+    // A buffer we can read our results from
+    let size = (numbers.len() * std::mem::size_of::<u32>()) as u64;
+    let staging = painter.device.create_staging_buffer(size, Some("Numbers staging buffer"));
+
+    let mut frame = painter.frame();
+    {
+        // Run our compute shader once for each number
+        let mut pass = frame.compute_pass();
+        pass.set_parrot_compute_pipeline(&pipeline);
+        pass.set_binding(&binding, &[]);
+        pass.dispatch(numbers.len() as u32, 1, 1);
+    }
+    // Copy the results into our staging buffer
+    frame.encoder.copy_buffer_to_buffer(&storage.wgpu, 0, &staging, 0, size);
+    painter.present(frame);
+
+    // Read back the results
+    let doubled: Vec<u32> = painter.device.map_and_read_buffer(&staging, numbers.len()).unwrap();
+    println!("{:?} doubled is {:?}", numbers, doubled);
+}
@@ -0,0 +1,221 @@
+extern crate pigeon_parrot as parrot;
+
+use std::{ops::Deref};
+use parrot::{
+    pipeline::{Plumber,
+        PipelineDescription,
+        PipelineCore,
+        Pipeline, Blending, BlendFactor, BlendOp,
+    },
+    buffers::{UniformBuffer, VertexBuffer, IndexBuffer},
+    vertex::VertexFormat,
+    shader::ShaderFile,
+    painter::PassOp, RenderPassExtention, Painter,
+};
+use winit::event::{Event, WindowEvent};
+use winit::event_loop::ControlFlow;
+
+// =======================================================================
+//  I recommend reading [learn wgpu](https://sotrh.github.io/learn-wgpu/)
+//  before using parrot and reading this example
+// 
+// This example is intended to show a grid of quads drawn with a single
+// draw call by giving each quad its own offset and color per instance
+// =======================================================================
+
+/// The struct that will represent the corners of our quad. As we are only drawing 2D shapes, we have a position composed of 2 floats
+/// The vertices must implement [`bytemuck::Pod`], [`bytemuck::Zeroable`] and [`Copy`]
+/// They also must be #[repr(C)]
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Vertex {
+    position: [f32; 2],
+}
+
+impl Vertex {
+    // Create a new vertex
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            position: [x,y],
+        }
+    }
+}
+
+/// The struct that will represent each quad in our grid. Each instance moves the quad by an offset and gives it a color
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Instance {
+    offset: [f32; 2],
+    color: [f32; 3],
+}
+
+/// How many quads are along each side of our grid
+const GRID: u32 = 10;
+
+/// This is our pipeline, it should contain everything we need as so we can deref to [`PipelineCore`] when we render.
+pub struct InstancedPipe {
+    /// This is the pipeline core that we will return. As this is a simple pipeline with no textures or uniforms, we don't need anything else
+    pipeline: PipelineCore,
+    /// The corners of a single quad
+    vertices: VertexBuffer,
+    /// The indicies of a single quad
+    indicies: IndexBuffer,
+    /// The offset and color of every quad in the grid
+    instances: VertexBuffer,
+}
+
+/// This is what allows our rendering functions to render with our pipeline
+impl Deref for InstancedPipe {
+    type Target = PipelineCore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.pipeline
+    }
+}
+
+impl<'a> Plumber<'a> for InstancedPipe {
+    /// Typically contains the information required to update our uniform buffer. We don't have one so we use ()
+    type PrepareContext = ();
+    /// Once again we have no uniforms, so we use ()
+    type Uniforms = ();
+
+    /// This is the function that will be used to create our pipeline
+    fn setup(pipe: Pipeline, painter: &Painter) -> Self {
+        let pipeline = PipelineCore {
+            // The actual pipeline
+            pipeline: pipe,
+            // Our bindings (we have none)
+            bindings: vec![],
+            // Our uniforms (we have none)
+            uniforms: vec![]
+        };
+
+        // A single small quad, every instance will reuse it
+        let size = 1.0 / GRID as f32;
+        let vertices = [Vertex::new(0.0, 0.0), Vertex::new(0.0, size), Vertex::new(size, size), Vertex::new(size, 0.0)];
+        let vertex = painter.vertex_buffer::<Vertex>(&vertices, Some("Quad vertex buffer"));
+        let indicies = painter.index_buffer(&[0, 1, 2, 2, 3, 0], Some("Quad index buffer"));
+
+        // Lay the quads out in a grid, leaving a gap between each one
+        let mut instances = vec![];
+        for y in 0..GRID {
+            for x in 0..GRID {
+                instances.push(Instance {
+                    offset: [-1.0 + (x as f32 * 2.0 + 0.5) * size, -1.0 + (y as f32 * 2.0 + 0.5) * size],
+                    color: [x as f32 / GRID as f32, y as f32 / GRID as f32, 1.0],
+                });
+            }
+        }
+        // Instance buffers are just vertex buffers that are bound at slot 1
+        let instances = painter.vertex_buffer::<Instance>(&instances, Some("Quad instance buffer"));
+
+        Self {
+            pipeline,
+            vertices: vertex,
+            indicies,
+            instances,
+        }
+    }
+
+    // This function is used to update our uniform buffer. As we don't have one, we return a blank vector
+    fn prepare(&'a mut self, _context: Self::PrepareContext, _: &mut Painter) -> Vec<(&'a mut UniformBuffer, Vec<Self::Uniforms>)> {
+        vec![]
+    }
+
+    // This function is used to describe the layout of our pipeline
+    fn description() -> PipelineDescription<'a> {
+        PipelineDescription {
+            instance_layout: Some(&[VertexFormat::Floatx2, VertexFormat::Floatx3]), // Layout of 2 floats for offset, 3 floats for color. These start at location 1
+            ..PipelineDescription::new(
+                &[VertexFormat::Floatx2], // Layout of 2 floats for position
+                None, // Has no bindings, so left empty
+                ShaderFile::Wgsl(include_str!("./shaders/instanced.wgsl")), // Takes in instanced shader
+                Some("Instanced pipeline") // Name of pipeline
+            )
+        }
+    }
+}
+
+fn main() {
+    // Initialise the logging output at info level only from parrot
+    env_logger::builder().filter_module("pigeon_parrot", log::LevelFilter::Info).init();
+    
+    // Create an event loop
+    let event_loop = winit::event_loop::EventLoop::new();
+    // Create a window to draw to
+    let window = winit::window::WindowBuilder::new().with_title("Instanced quads :D").build(&event_loop).unwrap();
+
+    // Create a wgpu instance
+    let instance = wgpu::Instance::new(wgpu::Backends::GL);
+    let surface = unsafe { instance.create_surface(&window) };
+
+    // Create the painter
+    let mut painter = pollster::block_on(parrot::Painter::for_surface(surface, &instance, 1)).unwrap();
+
+    // Get the size of the window
+    let winsize = window.inner_size();
+
+    // Get the preferred texture format for the surface
+    let pref_format = painter.preferred_format();
+
+    // Configure the surface
+    painter.configure(euclid::Size2D::new(winsize.width, winsize.height), wgpu::PresentMode::Fifo, pref_format).unwrap();
+
+    // Create our pipeline with no depth buffer :D
+    let blending = Blending::new(BlendFactor::One, BlendFactor::Zero, BlendOp::Add);
+    let pipeline = painter.pipeline_no_depth::<InstancedPipe>(blending, pref_format, Some("Instanced shader"));
+
+    // Initiate the event loop
+    event_loop.run(move |event, _, control_flow| {
+        // Only update the event loop if input is recieved
+        *control_flow = ControlFlow::Wait;
+
+        match event {
+            // Window event
+            Event::WindowEvent { event: win_event, .. } => {
+                match win_event {
+                    // Close if a close request is detected
+                    WindowEvent::CloseRequested => {
+                        println!("The close button was pressed; stopping");
+                        *control_flow = ControlFlow::Exit
+                    },
+                    // Update the surface if resized
+                    WindowEvent::Resized(size) => {
+                        let size = euclid::Size2D::new(size.width, size.height);
+                        painter.configure(size, wgpu::PresentMode::Fifo, pref_format).unwrap()
+                    }
+                    _ => ()
+                }
+            },
+            Event::RedrawRequested(_) => {
+                // Time to draw our shape :D
+
+                // Create a frame. This represents our, well, frame
+                let mut frame = painter.frame();
+
+                // Grab the current surface, we grab the one with no depth buffer attached
+                let current_surface = painter.current_frame_no_depth().unwrap();
+
+                {
+                    // Initiate a render pass
+                    let mut pass = frame.pass(PassOp::Clear(parrot::color::Rgba::new(0.1, 0.2, 0.3, 1.0)), &current_surface, None);
+
+                    // Set our pipeline
+                    pass.set_parrot_pipeline(&pipeline);
+
+                    // Set our vertex, instance and index buffers
+                    pass.set_parrot_vertex_buffer(&pipeline.vertices);
+                    pass.set_parrot_instance_buffer(&pipeline.instances);
+                    pass.set_parrot_index_buffer(&pipeline.indicies);
+
+                    // Draw every quad in the grid at once
+                    pass.draw_parrot_indexed(0..pipeline.indicies.size, 0..GRID * GRID);
+                }
+
+                // Present our frame
+                painter.present(frame);
+            }
+            _ => ()
+        }
+    });
+}
\ No newline at end of file
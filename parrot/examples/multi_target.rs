@@ -0,0 +1,146 @@
+extern crate pigeon_parrot as parrot;
+
+use std::{ops::Deref};
+use parrot::{
+    pipeline::{Plumber,
+        PipelineDescription,
+        PipelineCore,
+        Pipeline, Blending, BlendFactor, BlendOp,
+    },
+    buffers::{UniformBuffer, VertexBuffer},
+    vertex::VertexFormat,
+    shader::ShaderFile,
+    painter::PassOp, RenderPassExtention, Painter,
+};
+use euclid::Size2D;
+
+// =======================================================================
+//  I recommend reading [learn wgpu](https://sotrh.github.io/learn-wgpu/)
+//  before using parrot and reading this example
+// 
+// This example is intended to show rendering to multiple targets in one
+// pass. It draws the triangle from the triangle example into two frame
+// buffers at once, the second with inverted colors, and reads both back
+// =======================================================================
+
+/// The struct that will represent our verticies. As we are only drawing 2D shapes, we have a position composed of 2 floats
+/// The vertices must implement [`bytemuck::Pod`], [`bytemuck::Zeroable`] and [`Copy`]
+/// They also must be #[repr(C)]
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Vertex {
+    position: [f32; 2],
+    color: [f32; 3],
+}
+
+impl Vertex {
+    // Create a new vertex
+    pub fn new(x: f32, y: f32, r: f32, g: f32, b: f32) -> Self {
+        Self {
+            position: [x,y],
+            color: [r,g,b],
+        }
+    }
+}
+
+/// This is our pipeline, it should contain everything we need as so we can deref to [`PipelineCore`] when we render.
+pub struct TrianglePipe {
+    /// This is the pipeline core that we will return. Normally you would instead store your [`parrot::binding::BindingGroup`] and [`parrot::buffers::UniformBuffer`] and create it from those, but as this is a simple pipeline with no textures or uniforms, I won't bother
+    pipeline: PipelineCore,
+    /// The vertex buffer for our pipeline
+    vertices: VertexBuffer,
+}
+
+/// This is what allows our rendering functions to render with our pipeline
+impl Deref for TrianglePipe {
+    type Target = PipelineCore;
+
+    /// This function returns our [`PipelineCore`]. Normally we would create this, but as this is a simple triangle pipeline, I won't bother
+    fn deref(&self) -> &Self::Target {
+        &self.pipeline
+    }
+}
+
+impl<'a> Plumber<'a> for TrianglePipe {
+    /// Typically contains the information required to update our uniform buffer. We don't have one so we use ()
+    type PrepareContext = ();
+    /// Once again we have no uniforms, so we use ()
+    type Uniforms = ();
+
+    /// This is the function that will be used to create our pipeline
+    fn setup(pipe: Pipeline, painter: &Painter) -> Self {
+        let pipeline = PipelineCore {
+            // The actual pipeline
+            pipeline: pipe,
+            // Our bindings (we have none)
+            bindings: vec![],
+            // Our uniforms (we have none)
+            uniforms: vec![]
+        };
+
+        // The three default verticies that will make up our triangle. If you want, this can be blank.
+        let vertices = [Vertex::new(0.0, 0.5, 1.0, 0.0, 0.0), Vertex::new(-0.5, -0.5, 0.0, 1.0, 0.0), Vertex::new(0.5, -0.5, 0.0, 0.0, 1.0)];
+        // Create a vertex buffer, ours contains three verticies
+        let vertex = painter.vertex_buffer::<Vertex>(&vertices, Some("Triangle vertex buffer"));
+
+        Self {
+            pipeline,
+            vertices: vertex
+        }
+    }
+
+    // This function is used to update our uniform buffer. As we don't have one, we return a blank vector
+    fn prepare(&'a mut self, _context: Self::PrepareContext, _: &mut Painter) -> Vec<(&'a mut UniformBuffer, Vec<Self::Uniforms>)> {
+        vec![]
+    }
+
+    // This function is used to describe the layout of our pipeline
+    fn description() -> PipelineDescription<'a> {
+        PipelineDescription {
+            targets: &[wgpu::TextureFormat::Rgba8Unorm], // Also write to a second target at location 1
+            ..PipelineDescription::new(
+                &[VertexFormat::Floatx2, VertexFormat::Floatx3], // Layout of 2 floats for position, 3 floats for color
+                None, // Has no bindings, so left empty
+                ShaderFile::Wgsl(include_str!("./shaders/multi_target.wgsl")), // Takes in multi target shader
+                Some("Multi target pipeline") // Name of pipeline
+            )
+        }
+    }
+}
+
+fn main() {
+    // Initialise the logging output at info level only from parrot
+    env_logger::builder().filter_module("pigeon_parrot", log::LevelFilter::Info).init();
+
+    // Create a wgpu instance. We don't need a window or surface
+    let instance = wgpu::Instance::new(wgpu::Backends::all());
+
+    // Create the painter without a surface
+    let mut painter = pollster::block_on(parrot::Painter::headless(&instance, 1)).unwrap();
+    let format = painter.preferred_format();
+
+    // Create our pipeline with no depth buffer :D
+    let blending = Blending::new(BlendFactor::One, BlendFactor::Zero, BlendOp::Add);
+    let pipeline = painter.pipeline_no_depth::<TrianglePipe>(blending, format, Some("Multi target shader"));
+
+    // Create a frame buffer for each target. Their formats must match the pipeline
+    let size = Size2D::new(64, 64);
+    let color = painter.create_frame_buffer_no_depth(size, format, Some("Color frame buffer"));
+    let inverted = painter.create_frame_buffer_no_depth(size, wgpu::TextureFormat::Rgba8Unorm, Some("Inverted frame buffer"));
+
+    // Draw our triangle to both frame buffers
+    let mut frame = painter.frame();
+    {
+        let mut pass = frame.pass_multi(PassOp::Clear(parrot::color::Rgba::new(0.0, 0.0, 0.0, 1.0)), &[&color, &inverted], None);
+        pass.set_parrot_pipeline(&pipeline);
+        pass.draw_buffer_range(&pipeline.vertices, 0..3);
+    }
+    painter.present(frame);
+
+    // Read the pixels back. Each pixel is 4 bytes
+    let centre = ((size.height / 2 * size.width + size.width / 2) * 4) as usize;
+    let color_pixels = color.texture.read(&painter.device).unwrap();
+    let inverted_pixels = inverted.texture.read(&painter.device).unwrap();
+    println!("Centre pixel: {:?}", &color_pixels[centre..centre + 4]);
+    println!("Inverted centre pixel: {:?}", &inverted_pixels[centre..centre + 4]);
+}
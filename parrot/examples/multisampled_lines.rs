@@ -2,7 +2,7 @@ extern crate pigeon_parrot as parrot;
 use std::{ops::Deref};
 use parrot::{
     pipeline::{Plumber,
-        PipelineDescription,
+        PipelineDescription, PrimitiveTopology,
         PipelineCore,
         Pipeline, Blending, PipelineLayout,
     },
@@ -104,10 +104,13 @@ impl<'a> Plumber<'a> for LinePipe {
     // This function is used to describe the layout of our pipeline
     fn description() -> PipelineDescription<'a> {
         PipelineDescription {
-            vertex_layout: &[VertexFormat::Floatx2, VertexFormat::Floatx3], // Layout of 2 floats for position, 3 floats for color
-            pipeline_layout: None, // Has no bindings, so left empty
-            shader: ShaderFile::Wgsl(include_str!("./shaders/multisampled_line.wgsl")), // Takes in line shader
-            name: Some("Line pipeline") // Name of pipeline
+            topology: PrimitiveTopology::LineList, // How our verticies are put together
+            ..PipelineDescription::new(
+                &[VertexFormat::Floatx2, VertexFormat::Floatx3], // Layout of 2 floats for position, 3 floats for color
+                None, // Has no bindings, so left empty
+                ShaderFile::Wgsl(include_str!("./shaders/multisampled_line.wgsl")), // Takes in line shader
+                Some("Line pipeline") // Name of pipeline
+            )
         }
     }
 }
@@ -135,7 +138,7 @@ fn main() {
     let winsize = window.inner_size();
 
     // Configure the surface
-    painter.configure(Size2D::new(winsize.width, winsize.height), wgpu::PresentMode::Fifo, wgpu::TextureFormat::Bgra8UnormSrgb);
+    painter.configure(Size2D::new(winsize.width, winsize.height), wgpu::PresentMode::Fifo, wgpu::TextureFormat::Bgra8UnormSrgb).unwrap();
 
     // Create our pipeline. As we are using lines instead of triangles as our primative geometry, we have to create a custom pipeline.
     // As we are passing in a function and not a closure, we must provide both the function type and pipeline type. The function type we need is already in parrot
@@ -164,7 +167,7 @@ fn main() {
                     WindowEvent::Resized(size) => {
                         let size = euclid::Size2D::new(size.width, size.height);
                         multisample = painter.texture(size, wgpu::TextureFormat::Bgra8UnormSrgb, TextureUsages::RENDER_ATTACHMENT, Some("Multisampled framebuffer"), true);
-                        painter.configure(size, wgpu::PresentMode::Fifo, wgpu::TextureFormat::Bgra8UnormSrgb)
+                        painter.configure(size, wgpu::PresentMode::Fifo, wgpu::TextureFormat::Bgra8UnormSrgb).unwrap()
                     },
                     WindowEvent::KeyboardInput { input: KeyboardInput { state: ElementState::Pressed, virtual_keycode: Some(VirtualKeyCode::Space), .. }, .. } => {
                         // Switch multisampling
@@ -175,7 +178,7 @@ fn main() {
                         }
 
                         // Update the painters multisample variable
-                        painter.update_sample_count(samples);
+                        painter.update_sample_count(samples).unwrap();
                         // Update the pipeline
                         pipeline = painter.custom_pipeline::<LinePipe, parrot::painter::PipelineFunction>(Some("Line shader"), create_pipeline);
                         // Update the multisample texture
@@ -238,20 +241,12 @@ fn create_pipeline(device: &Device, pipeline_layout: PipelineLayout, vertex_layo
     });
     
     // Blending
-    let (src_factor, dst_factor, operation) = Blending::default().as_wgpu();
+    let (color, alpha) = Blending::default().as_wgpu();
     let targets = [Some(wgpu::ColorTargetState {
         format: wgpu::TextureFormat::Bgra8UnormSrgb,
         blend: Some(wgpu::BlendState {
-            color: wgpu::BlendComponent {
-                src_factor,
-                dst_factor,
-                operation
-            },
-            alpha: wgpu::BlendComponent {
-                src_factor,
-                dst_factor,
-                operation,
-            },
+            color,
+            alpha,
         }),
         write_mask: wgpu::ColorWrites::ALL,
     })];
@@ -275,5 +270,5 @@ fn create_pipeline(device: &Device, pipeline_layout: PipelineLayout, vertex_layo
     let wgpu = device.wgpu.create_render_pipeline(&desc);
 
     // Our pipeline
-    Pipeline { wgpu, layout: pipeline_layout, vertex_layout: vertex_layout }
+    Pipeline { wgpu, layout: pipeline_layout, vertex_layout, instance_layout: None, depth: None }
 }
\ No newline at end of file
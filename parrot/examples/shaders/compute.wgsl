@@ -0,0 +1,9 @@
+// Compute shader
+
+@group(0) @binding(0)
+var<storage, read_write> numbers: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    numbers[id.x] = numbers[id.x] * 2u;
+}
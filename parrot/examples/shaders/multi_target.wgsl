@@ -0,0 +1,37 @@
+// Vertex shader
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) color: vec3<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec3<f32>,
+}
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.color = model.color;
+    out.clip_position = vec4<f32>(model.position, 1.0, 1.0);
+    return out;
+}
+
+// Fragment shader
+
+// Each location is written to a different target
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    @location(1) inverted: vec4<f32>,
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> FragmentOutput {
+    var out: FragmentOutput;
+    out.color = vec4<f32>(in.color, 1.0);
+    out.inverted = vec4<f32>(1.0 - in.color, 1.0);
+    return out;
+}
@@ -103,7 +103,7 @@ impl<'a> Plumber<'a> for TrianglePipe {
         // Create relevant bindings
         let texture_layout = &pipe.layout.b_layouts[0];
 
-        let texture_bind = painter.binding_group(texture_layout, &[&texture, &sampler], Some("Texture bind group"));
+        let texture_bind = painter.binding_group(texture_layout, &[&texture, &sampler], Some("Texture bind group")).unwrap();
 
         let pipeline = PipelineCore {
             // The actuall pipeline
@@ -128,14 +128,14 @@ impl<'a> Plumber<'a> for TrianglePipe {
 
     // This function is used to describe the layout of our pipeline
     fn description() -> PipelineDescription<'a> {
-        PipelineDescription {
-            vertex_layout: &[VertexFormat::Floatx2, VertexFormat::Floatx2], // Layout of 2 floats for position, 2 floats for texture coords
-            pipeline_layout: Some(&[
+        PipelineDescription::new(
+            &[VertexFormat::Floatx2, VertexFormat::Floatx2], // Layout of 2 floats for position, 2 floats for texture coords
+            Some(&[
                 // Add a set of bindings
                 Set(&[
                     // Our texture binding
                     Binding {
-                        binding: BindingType::Texture { multisampled: false },
+                        binding: BindingType::Texture { multisampled: false, view_dimension: wgpu::TextureViewDimension::D2 },
                         stage: ShaderStages::FRAGMENT, // We'll use this in the vertex stage
                     },
                     Binding {
@@ -145,9 +145,9 @@ impl<'a> Plumber<'a> for TrianglePipe {
                     ], Some("Triangle texture bind group") // A name, not neccessary but usefull for debugging
                 )
             ]),
-            shader: ShaderFile::Wgsl(include_str!("./shaders/textured_square.wgsl")), // Takes in triangle shader
-            name: Some("Triangle pipeline") // Name of pipeline
-        }
+            ShaderFile::Wgsl(include_str!("./shaders/textured_square.wgsl")), // Takes in triangle shader
+            Some("Triangle pipeline") // Name of pipeline
+        )
     }
 }
 
@@ -174,7 +174,7 @@ fn main() {
     let winsize = window.inner_size();
 
     // Configure the surface
-    painter.configure(euclid::Size2D::new(winsize.width, winsize.height), wgpu::PresentMode::Fifo, pref_format);
+    painter.configure(euclid::Size2D::new(winsize.width, winsize.height), wgpu::PresentMode::Fifo, pref_format).unwrap();
 
     // Create our pipeline with no depth buffer :D
     let blending = Blending::new(BlendFactor::One, BlendFactor::Zero, BlendOp::Add);
@@ -197,7 +197,7 @@ fn main() {
                     // Update the surface if resized
                     WindowEvent::Resized(size) => {
                         let size = euclid::Size2D::new(size.width, size.height);
-                        painter.configure(size, wgpu::PresentMode::Fifo, pref_format)
+                        painter.configure(size, wgpu::PresentMode::Fifo, pref_format).unwrap()
                     }
                     _ => ()
                 }
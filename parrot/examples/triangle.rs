@@ -3,7 +3,7 @@ extern crate pigeon_parrot as parrot;
 use std::{ops::Deref};
 use parrot::{
     pipeline::{Plumber,
-        PipelineDescription,
+        PipelineDescription, PrimitiveTopology, FrontFace, PolygonMode, DepthConfig,
         PipelineCore,
         Pipeline, Blending, BlendFactor, BlendOp,
     },
@@ -98,9 +98,21 @@ impl<'a> Plumber<'a> for TrianglePipe {
     fn description() -> PipelineDescription<'a> {
         PipelineDescription {
             vertex_layout: &[VertexFormat::Floatx2, VertexFormat::Floatx3], // Layout of 2 floats for position, 3 floats for color
+            instance_layout: None, // No per instance data
             pipeline_layout: None, // Has no bindings, so left empty
             shader: ShaderFile::Wgsl(include_str!("./shaders/triangle.wgsl")), // Takes in triangle shader
-            name: Some("Triangle pipeline") // Name of pipeline
+            vertex_entry: "vs_main", // Name of the vertex shader function
+            fragment_entry: "fs_main", // Name of the fragment shader function
+            name: Some("Triangle pipeline"), // Name of pipeline
+            topology: PrimitiveTopology::TriangleList, // How our verticies are put together
+            index_format: wgpu::IndexFormat::Uint16, // Only used by strips, which need to know the index buffer's format
+            cull_mode: None, // Draw both sides of our shapes
+            front_face: FrontFace::Ccw, // Counter clockwise shapes face forwards
+            polygon_mode: PolygonMode::Fill, // Fill in our shapes
+            depth: DepthConfig::default(), // Use the depth buffer normally
+            write_mask: wgpu::ColorWrites::ALL, // Write to every color channel
+            targets: &[], // Only render to one target
+            push_constants: &[], // No push constants
         }
     }
 }
@@ -128,7 +140,7 @@ fn main() {
     let pref_format = painter.preferred_format();
 
     // Configure the surface
-    painter.configure(euclid::Size2D::new(winsize.width, winsize.height), wgpu::PresentMode::Fifo, pref_format);
+    painter.configure(euclid::Size2D::new(winsize.width, winsize.height), wgpu::PresentMode::Fifo, pref_format).unwrap();
 
     // Create our pipeline with no depth buffer :D
     let blending = Blending::new(BlendFactor::One, BlendFactor::Zero, BlendOp::Add);
@@ -151,7 +163,7 @@ fn main() {
                     // Update the surface if resized
                     WindowEvent::Resized(size) => {
                         let size = euclid::Size2D::new(size.width, size.height);
-                        painter.configure(size, wgpu::PresentMode::Fifo, pref_format)
+                        painter.configure(size, wgpu::PresentMode::Fifo, pref_format).unwrap()
                     }
                     _ => ()
                 }
@@ -99,12 +99,12 @@ impl<'a> Plumber<'a> for TrianglePipe {
 
     // This function is used to describe the layout of our pipeline
     fn description() -> PipelineDescription<'a> {
-        PipelineDescription {
-            vertex_layout: &[VertexFormat::Floatx2], // Layout of 2 floats
-            pipeline_layout: None, // Has no bindings, so left empty
-            shader: ShaderFile::Wgsl(include_str!("./shaders/triangle_square.wgsl")), // Takes in triangle shader
-            name: Some("Triangle pipeline") // Name of pipeline
-        }
+        PipelineDescription::new(
+            &[VertexFormat::Floatx2], // Layout of 2 floats
+            None, // Has no bindings, so left empty
+            ShaderFile::Wgsl(include_str!("./shaders/triangle_square.wgsl")), // Takes in triangle shader
+            Some("Triangle pipeline") // Name of pipeline
+        )
     }
 }
 
@@ -131,7 +131,7 @@ fn main() {
     let pref_format = painter.preferred_format();
 
     // Configure the surface
-    painter.configure(euclid::Size2D::new(winsize.width, winsize.height), wgpu::PresentMode::Fifo, pref_format);
+    painter.configure(euclid::Size2D::new(winsize.width, winsize.height), wgpu::PresentMode::Fifo, pref_format).unwrap();
 
     // A simple switch to keep track of whether it's a pentagon or a triangle
     let mut switch = false;
@@ -227,7 +227,7 @@ fn main() {
                     pass.set_parrot_pipeline(&pipeline);
 
                     // Perform the render pass on the entire vertex buffer
-                    pass.draw_parrot_indexed(0..pipeline.indicies.size, 0..1)
+                    pass.draw_parrot_indexed(pipeline.indicies.full_range(), 0..1)
                 }
 
                 // Present our frame
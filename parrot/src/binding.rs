@@ -44,8 +44,20 @@ pub trait Bind {
 #[derive(Debug)]
 pub enum BindingType {
     UniformBuffer,
+    /// A uniform buffer selected with a dynamic offset when the binding group is set. See [`crate::buffers::UniformBuffer::offset`]
+    DynamicUniformBuffer,
+    /// A storage buffer. Read only storage buffers can be used in the vertex stage
+    StorageBuffer {read_only: bool},
     Sampler,
-    Texture {multisampled: bool},
+    /// A sampler created with [`crate::sampler::SamplerDesc::with_compare`]. Use with `textureSampleCompare` in the shader
+    ComparisonSampler,
+    /// A texture with a depth format, such as [`crate::buffers::DepthBuffer::FORMAT`]. Read as a `texture_depth_2d` with a comparison sampler
+    DepthTexture {view_dimension: wgpu::TextureViewDimension},
+    /// A texture binding. `view_dimension` should match the texture being bound, usually [`wgpu::TextureViewDimension::D2`].
+    /// Multisampled textures are bound as non filterable and must be read with `textureLoad`
+    Texture {multisampled: bool, view_dimension: wgpu::TextureViewDimension},
+    /// A 2D texture array, such as one created with [`crate::Painter::texture_array`]. Read as a `texture_2d_array<f32>`
+    TextureArray,
 }
 
 impl BindingType {
@@ -56,14 +68,38 @@ impl BindingType {
                 has_dynamic_offset: false,
                 min_binding_size: None,
             },
+            BindingType::DynamicUniformBuffer => wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: true,
+                min_binding_size: None,
+            },
+            BindingType::StorageBuffer { read_only } => wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: *read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
             BindingType::Sampler => wgpu::BindingType::Sampler(
                 wgpu::SamplerBindingType::Filtering
             ),
-            BindingType::Texture{ multisampled } => wgpu::BindingType::Texture {
-                sample_type: wgpu::TextureSampleType::Float{ filterable: true },
-                view_dimension: wgpu::TextureViewDimension::D2,
+            BindingType::ComparisonSampler => wgpu::BindingType::Sampler(
+                wgpu::SamplerBindingType::Comparison
+            ),
+            BindingType::DepthTexture{ view_dimension } => wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Depth,
+                view_dimension: *view_dimension,
+                multisampled: false,
+            },
+            BindingType::Texture{ multisampled, view_dimension } => wgpu::BindingType::Texture {
+                // Multisampled textures can't be filtered, only loaded
+                sample_type: wgpu::TextureSampleType::Float{ filterable: !*multisampled },
+                view_dimension: *view_dimension,
                 multisampled: *multisampled
-            }
+            },
+            BindingType::TextureArray => wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float{ filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2Array,
+                multisampled: false,
+            },
         }
     }
 }
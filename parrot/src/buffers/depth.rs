@@ -1,11 +1,44 @@
-use crate::texture::Texture;
+use crate::{device::Device, error::ParrotError, texture::Texture};
 
 /// Depth buffer
 #[derive(Debug)]
 pub struct DepthBuffer {
     pub texture: Texture,
+    /// Sample count of the depth texture
+    pub sample_count: u32,
 }
 
 impl DepthBuffer {
+    /// The default depth format. It has no stencil
     pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
-}
\ No newline at end of file
+
+    /// Returns true if the depth format also has a stencil aspect
+    pub const fn has_stencil(format: wgpu::TextureFormat) -> bool {
+        matches!(
+            format,
+            wgpu::TextureFormat::Depth24PlusStencil8 | wgpu::TextureFormat::Depth24UnormStencil8 | wgpu::TextureFormat::Depth32FloatStencil8
+        )
+    }
+
+    /// Read the depth values back to the cpu, row by row. Useful for debugging. This blocks until the gpu is finished.
+    ///
+    /// Only [`DepthBuffer::FORMAT`] can be copied, so other formats return [`ParrotError::WrongTextureFormat`].
+    /// Multisampled depth buffers return [`ParrotError::MultisampledReadback`], and adapters that can't copy depth textures
+    /// (such as GL) return [`ParrotError::UnsupportedDownlevel`]
+    pub fn read(&self, device: &Device) -> Result<Vec<f32>, ParrotError> {
+        if self.texture.format != Self::FORMAT {
+            return Err(ParrotError::WrongTextureFormat { expected: Self::FORMAT, got: self.texture.format });
+        }
+        if self.sample_count > 1 {
+            return Err(ParrotError::MultisampledReadback(self.sample_count));
+        }
+        if !device.downlevel_flags().contains(wgpu::DownlevelFlags::DEPTH_TEXTURE_AND_BUFFER_COPIES) {
+            return Err(ParrotError::UnsupportedDownlevel(wgpu::DownlevelFlags::DEPTH_TEXTURE_AND_BUFFER_COPIES));
+        }
+        let bytes = self.texture.read(device)?;
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|d| f32::from_le_bytes([d[0], d[1], d[2], d[3]]))
+            .collect())
+    }
+}
@@ -12,6 +12,9 @@ pub struct FrameBuffer {
 }
 
 impl FrameBuffer {
+    /// Format used by [`crate::Painter::create_hdr_frame_buffer`]. Holds values above 1.0 and supports blending
+    pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
     /// Amount of pixels in the frame buffer
     pub fn size(&self) -> u32 {
         self.texture.size.area()
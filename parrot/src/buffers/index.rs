@@ -1,35 +1,56 @@
-/// Represents the index buffer
-#[derive(Debug)]
-pub struct IndexBuffer {
-    /// Wrapped wgpu type
-    pub wgpu: wgpu::Buffer,
-    /// Size of the buffer in indicies
-    pub size: u32,
-    /// Name
-    pub name: Option<String>
+use std::marker::PhantomData;
+
+/// A type that can be stored in an [`IndexBuffer`]. Implemented for `u16` and `u32`
+pub trait Index: bytemuck::Pod + std::fmt::Debug {
+    /// Format of the indicies
+    const FORMAT: wgpu::IndexFormat;
+    /// Size of an index in bytes
+    const SIZE: usize = std::mem::size_of::<Self>();
 }
 
-impl IndexBuffer {
-    pub fn slice(&self) -> wgpu::BufferSlice {
-        self.wgpu
-            .slice(0..(self.size as usize * std::mem::size_of::<u16>()) as u64)
-    }
+impl Index for u16 {
+    const FORMAT: wgpu::IndexFormat = wgpu::IndexFormat::Uint16;
 }
 
-/// 32-bit index buffer
+impl Index for u32 {
+    const FORMAT: wgpu::IndexFormat = wgpu::IndexFormat::Uint32;
+}
+
+/// Represents the index buffer. Holds 16 bit indicies unless another [`Index`] type is given
 #[derive(Debug)]
-pub struct IndexBuffer32 {
+pub struct IndexBuffer<I: Index = u16> {
     /// Wrapped wgpu type
     pub wgpu: wgpu::Buffer,
     /// Size of the buffer in indicies
     pub size: u32,
     /// Name
-    pub name: Option<String>
+    pub name: Option<String>,
+    /// Type of the indicies
+    pub(crate) index: PhantomData<I>,
 }
 
-impl IndexBuffer32 {
-    pub fn slice(&self) -> wgpu::BufferSlice {
+/// 32-bit index buffer
+pub type IndexBuffer32 = IndexBuffer<u32>;
+
+impl<I: Index> IndexBuffer<I> {
+    /// Format of the indicies
+    pub const FORMAT: wgpu::IndexFormat = I::FORMAT;
+
+    /// Returns a slice of the indicies in the buffer. wgpu can't slice nothing, so the buffer must not be empty
+    pub fn slice(&self) -> wgpu::BufferSlice<'_> {
+        debug_assert!(self.size > 0, "Cannot slice empty index buffer {:?}", self.name);
         self.wgpu
-            .slice(0..(self.size as usize * std::mem::size_of::<u32>()) as u64)
+            .slice(0..(self.size as usize * I::SIZE) as u64)
     }
-}
\ No newline at end of file
+
+    /// The range covering every index in the buffer
+    pub const fn full_range(&self) -> std::ops::Range<u32> {
+        0..self.size
+    }
+
+    /// Free the buffer's gpu memory now instead of waiting for it to be dropped
+    pub fn destroy(self) {
+        log::info!("Destroying index buffer >> Name: {:?} || Format: {:?}", self.name, I::FORMAT);
+        self.wgpu.destroy();
+    }
+}
@@ -3,5 +3,6 @@ pub mod index;
 pub mod uniform;
 pub mod depth;
 pub mod frame;
+pub mod storage;
 
-pub use {vertex::VertexBuffer, index::IndexBuffer, uniform::UniformBuffer, depth::DepthBuffer, frame::FrameBuffer};
\ No newline at end of file
+pub use {vertex::VertexBuffer, index::{IndexBuffer, IndexBuffer32, Index}, uniform::{UniformBuffer, TypedUniform}, depth::DepthBuffer, frame::FrameBuffer, storage::StorageBuffer};
\ No newline at end of file
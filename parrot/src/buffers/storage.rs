@@ -0,0 +1,33 @@
+use std::num::NonZeroU64;
+
+use crate::binding::Bind;
+
+/// A buffer that can be read from, and written to, by shaders
+#[derive(Debug)]
+pub struct StorageBuffer {
+    pub wgpu: wgpu::Buffer,
+    pub size: usize,
+    pub count: usize,
+    pub name: Option<String>
+}
+
+impl StorageBuffer {
+    /// Free the buffer's gpu memory now instead of waiting for it to be dropped
+    pub fn destroy(self) {
+        log::info!("Destroying storage buffer >> Name: {:?}", self.name);
+        self.wgpu.destroy();
+    }
+}
+
+impl Bind for StorageBuffer {
+    fn binding(&self, index: u32) -> wgpu::BindGroupEntry<'_> {
+        wgpu::BindGroupEntry {
+            binding: index,
+            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                buffer: &self.wgpu,
+                offset: 0,
+                size: NonZeroU64::new((self.size * self.count) as u64)
+            })
+        }
+    }
+}
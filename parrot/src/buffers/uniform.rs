@@ -1,24 +1,132 @@
-use std::num::NonZeroU64;
+use std::{any::TypeId, marker::PhantomData, num::NonZeroU64};
 
-use crate::binding::Bind;
+use crate::{binding::Bind, error::ParrotError};
 
 #[derive(Debug)]
 pub struct UniformBuffer {
     pub wgpu: wgpu::Buffer,
     pub size: usize,
     pub count: usize,
-    pub name: Option<String>
+    pub name: Option<String>,
+    /// Whether each element is placed at an aligned offset so it can be selected with a dynamic offset
+    pub dynamic: bool,
+}
+
+impl UniformBuffer {
+    /// Free the buffer's gpu memory now instead of waiting for it to be dropped
+    pub fn destroy(self) {
+        log::info!("Destroying uniform buffer >> Name: {:?}", self.name);
+        self.wgpu.destroy();
+    }
+
+    /// The alignment required between elements selected with dynamic offsets. This is wgpu's default `min_uniform_buffer_offset_alignment`
+    pub const ALIGNMENT: usize = 256;
+
+    /// Returns the distance between elements of the given size in a dynamic uniform buffer
+    pub const fn aligned_stride(size: usize) -> usize {
+        size.div_ceil(Self::ALIGNMENT) * Self::ALIGNMENT
+    }
+
+    /// Returns the dynamic offset of the element at `index`
+    pub const fn offset(&self, index: usize) -> u32 {
+        (Self::aligned_stride(self.size) * index) as u32
+    }
+
+    /// Returns the amount of bytes the buffer holds
+    pub const fn byte_size(&self) -> usize {
+        if self.dynamic {
+            Self::aligned_stride(self.size) * self.count
+        } else {
+            self.size * self.count
+        }
+    }
+
+    /// Places each element at an aligned offset
+    pub(crate) fn pack_dynamic<T: bytemuck::Pod>(data: &[T]) -> Vec<u8> {
+        let stride = Self::aligned_stride(std::mem::size_of::<T>());
+        let mut bytes = vec![0; stride * data.len()];
+        for (chunk, item) in bytes.chunks_mut(stride).zip(data) {
+            let item: &[u8] = bytemuck::bytes_of(item);
+            chunk[..item.len()].copy_from_slice(item);
+        }
+        bytes
+    }
 }
 
 impl Bind for UniformBuffer {
     fn binding(&self, index: u32) -> wgpu::BindGroupEntry {
+        // Dynamic buffers only bind a single element, which is picked by the offset
+        let size = if self.dynamic { self.size } else { self.size * self.count };
         wgpu::BindGroupEntry {
             binding: index as u32,
             resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
                 buffer: &self.wgpu,
                 offset: 0,
-                size: NonZeroU64::new((self.size * self.count) as u64)
+                size: NonZeroU64::new(size as u64)
+            })
+        }
+    }
+}
+
+/// A [`UniformBuffer`] that remembers the type it was created with. Updating it with any other type is an error
+/// instead of the bytes being silently reinterpreted. Create with [`crate::Painter::typed_uniform_buffer`]
+#[derive(Debug)]
+pub struct TypedUniform<T> {
+    pub buffer: UniformBuffer,
+    _marker: PhantomData<T>,
+}
+
+impl<T: bytemuck::Pod + 'static> TypedUniform<T> {
+    pub(crate) fn new(buffer: UniformBuffer) -> Self {
+        Self {
+            buffer,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns [`ParrotError::UniformTypeMismatch`] if `U` isn't the type the buffer was created with
+    pub fn check<U: 'static>(&self) -> Result<(), ParrotError> {
+        if TypeId::of::<U>() == TypeId::of::<T>() {
+            Ok(())
+        } else {
+            Err(ParrotError::UniformTypeMismatch {
+                expected: std::any::type_name::<T>(),
+                expected_size: std::mem::size_of::<T>(),
+                got: std::any::type_name::<U>(),
+                got_size: std::mem::size_of::<U>(),
             })
         }
     }
-}
\ No newline at end of file
+}
+
+impl<T> Bind for TypedUniform<T> {
+    fn binding(&self, index: u32) -> wgpu::BindGroupEntry<'_> {
+        self.buffer.binding(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::ParrotError, painter::tests::headless};
+
+    #[test]
+    fn typed_uniform_rejects_other_types() {
+        let mut paint = match headless() {
+            Some(paint) => paint,
+            None => return,
+        };
+        let mut uniform = paint.typed_uniform_buffer(&[[0.0f32; 4]], None);
+
+        // Same size, different type
+        assert!(matches!(
+            paint.update_typed_uniform(&[[0u32; 4]], &mut uniform),
+            Err(ParrotError::UniformTypeMismatch { expected_size: 16, got_size: 16, .. })
+        ));
+        assert!(matches!(
+            paint.update_typed_uniform(&[0.0f32], &mut uniform),
+            Err(ParrotError::UniformTypeMismatch { expected_size: 16, got_size: 4, .. })
+        ));
+        assert!(paint.update_typed_uniform(&[[1.0f32; 4]], &mut uniform).is_ok());
+        assert!(uniform.check::<[f32; 4]>().is_ok());
+    }
+}
@@ -3,6 +3,8 @@
 pub struct VertexBuffer {
     /// Size of the buffer in bytes
     pub size: u32,
+    /// Bytes of the buffer holding vertices from the last update. Never more than [`VertexBuffer::size`]
+    pub len: u32,
     /// Wrapped wgpu buffer
     pub wgpu: wgpu::Buffer,
     /// Name of the vertex buffer
@@ -10,7 +12,21 @@ pub struct VertexBuffer {
 }
 
 impl VertexBuffer {
-    pub fn slice(&self) -> wgpu::BufferSlice {
-        self.wgpu.slice(0..self.size as u64)
+    /// Returns a slice of the vertices written by the last update, rather than the whole buffer.
+    /// wgpu can't slice nothing, so the buffer must not be empty
+    pub fn slice(&self) -> wgpu::BufferSlice<'_> {
+        debug_assert!(self.len > 0, "Cannot slice empty vertex buffer {:?}", self.name);
+        self.wgpu.slice(0..self.len as u64)
+    }
+
+    /// Returns the amount of vertices written by the last update, given the size of a vertex in bytes
+    pub fn vertex_count(&self, stride: u32) -> u32 {
+        self.len / stride
+    }
+
+    /// Free the buffer's gpu memory now instead of waiting for it to be dropped
+    pub fn destroy(self) {
+        log::info!("Destroying vertex buffer >> Name: {:?}", self.name);
+        self.wgpu.destroy();
     }
 }
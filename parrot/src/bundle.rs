@@ -0,0 +1,13 @@
+/// A finished render bundle. Record one with [`crate::Painter::record_render_bundle`] and draw it with
+/// [`crate::RenderPassExtention::execute_parrot_bundle`]. It can only be executed in passes with the same format, depth format and sample count
+#[derive(Debug)]
+pub struct RenderBundle {
+    /// Wrapped wgpu value
+    pub wgpu: wgpu::RenderBundle,
+    /// Format of the color target the bundle was recorded for
+    pub format: wgpu::TextureFormat,
+    /// Format of the depth target the bundle was recorded for, or none for passes without a depth buffer
+    pub depth_format: Option<wgpu::TextureFormat>,
+    /// Sample count the bundle was recorded for
+    pub sample_count: u32,
+}
@@ -17,10 +17,21 @@ pub struct Rgba8 {
 }
 
 impl Rgba8 {
+    pub const TRANSPARENT: Self = Self::new(0, 0, 0, 0);
+
+    pub const WHITE: Self = Self::new(255, 255, 255, 255);
+
+    pub const BLACK: Self = Self::new(0, 0, 0, 255);
+
     pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
         Self {r, g, b, a}
     }
 
+    /// Convert to a linear [`Rgba`], treating the colour channels as sRGB
+    pub fn to_linear_rgba(self) -> Rgba {
+        Rgba::from(self).to_linear()
+    }
+
     /// Given a slice of bytes, return a slice of [`Rgba8`] values
     pub fn align<'a, S: 'a, T: AsRef<[S]> + ?Sized> (bytes: &'a T) -> &'a [Rgba8] {
         let bytes = bytes.as_ref();
@@ -69,6 +80,38 @@ impl Bgra8 {
 
 impl Color for Bgra8 {}
 
+/// A single channel colour with an 8-bit channel. Used to fill [`wgpu::TextureFormat::R8Unorm`] textures, such as masks and heightmaps
+#[repr(transparent)]
+#[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Debug, Default, Pod, Zeroable)]
+pub struct R8 {
+    /// Red
+    pub r: u8,
+}
+
+impl R8 {
+    pub const BLACK: Self = Self::new(0);
+
+    pub const WHITE: Self = Self::new(255);
+
+    pub const fn new(r: u8) -> Self {
+        Self { r }
+    }
+
+    /// Given a slice of bytes, return a slice of [`R8`] values
+    pub fn align<'a, S: 'a, T: AsRef<[S]> + ?Sized>(bytes: &'a T) -> &'a [Self] {
+        let bytes = bytes.as_ref();
+        let (head, body, tail) = unsafe { bytes.align_to::<Self>() };
+
+        // Panic if the bytes weren't correctly alligned
+        if !(head.is_empty() && tail.is_empty()) {
+            panic!("R8::align: input is not a valid R8 buffer");
+        }
+        body
+    }
+}
+
+impl Color for R8 {}
+
 /// A RGBA colour represented as a float between 0 and 1
 #[derive(Clone, Copy, PartialEq, Debug, Default)]
 pub struct Rgba {
@@ -104,6 +147,84 @@ impl Rgba {
             a
         }
     }
+
+    /// Create a colour from hue (in degrees), saturation and value. Saturation, value and alpha are between 0 and 1
+    pub fn from_hsv(h: f32, s: f32, v: f32, a: f32) -> Self {
+        let c = v * s;
+        Self::from_hue(h, c, v - c, a)
+    }
+
+    /// Create a colour from hue (in degrees), saturation and lightness. Saturation, lightness and alpha are between 0 and 1
+    pub fn from_hsl(h: f32, s: f32, l: f32, a: f32) -> Self {
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        Self::from_hue(h, c, l - c / 2.0, a)
+    }
+
+    // Shared by hsv and hsl. `c` is the chroma and `m` is added to every channel
+    fn from_hue(h: f32, c: f32, m: f32, a: f32) -> Self {
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+
+        Self::new(r + m, g + m, b + m, a)
+    }
+
+    /// Linearly interpolate between two colours. `t` is clamped between 0 and 1
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        Self::new(
+            self.r + (other.r - self.r) * t,
+            self.g + (other.g - self.g) * t,
+            self.b + (other.b - self.b) * t,
+            self.a + (other.a - self.a) * t,
+        )
+    }
+
+    /// Returns the same colour with a different alpha
+    pub const fn with_alpha(self, a: f32) -> Self {
+        Self::new(self.r, self.g, self.b, a)
+    }
+
+    /// Convert from sRGB to linear space using the standard sRGB transfer function. Alpha is left as is
+    pub fn to_linear(self) -> Self {
+        Self::new(srgb_to_linear(self.r), srgb_to_linear(self.g), srgb_to_linear(self.b), self.a)
+    }
+
+    /// Convert from linear to sRGB space using the standard sRGB transfer function. Alpha is left as is
+    pub fn to_srgb(self) -> Self {
+        Self::new(linear_to_srgb(self.r), linear_to_srgb(self.g), linear_to_srgb(self.b), self.a)
+    }
+
+    /// Convert to [`Rgba8`], clamping each channel and rounding to the nearest value
+    pub fn to_rgba8(self) -> Rgba8 {
+        let conv = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Rgba8::new(conv(self.r), conv(self.g), conv(self.b), conv(self.a))
+    }
+}
+
+// sRGB transfer functions for a single channel
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
 }
 
 impl From<Bgra8> for Rgba8 {
@@ -117,6 +238,7 @@ impl From<Bgra8> for Rgba8 {
     }
 }
 
+/// Converts the channels as-is with no gamma correction. Use [`Rgba8::to_linear_rgba`] if the colour is sRGB
 impl From<Rgba8> for Rgba {
     fn from(c: Rgba8) -> Self {
         Self {
@@ -137,4 +259,67 @@ impl From<Rgba> for wgpu::Color {
             a: rgba.a as f64,
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Rgba, b: Rgba) {
+        let diff = [a.r - b.r, a.g - b.g, a.b - b.b, a.a - b.a];
+        assert!(diff.iter().all(|d| d.abs() < 1e-5), "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn hue_to_rgb() {
+        let hues = [
+            (0.0, Rgba::RED),
+            (60.0, Rgba::new(1.0, 1.0, 0.0, 1.0)),
+            (120.0, Rgba::GREEN),
+            (180.0, Rgba::new(0.0, 1.0, 1.0, 1.0)),
+            (240.0, Rgba::BLUE),
+            (300.0, Rgba::new(1.0, 0.0, 1.0, 1.0)),
+            // Hues wrap around
+            (360.0, Rgba::RED),
+            (-120.0, Rgba::BLUE),
+        ];
+        for (hue, rgb) in hues {
+            assert_close(Rgba::from_hsv(hue, 1.0, 1.0, 1.0), rgb);
+            assert_close(Rgba::from_hsl(hue, 1.0, 0.5, 1.0), rgb);
+        }
+    }
+
+    #[test]
+    fn saturation_value_and_lightness() {
+        assert_close(Rgba::from_hsv(200.0, 0.0, 0.5, 0.25), Rgba::new(0.5, 0.5, 0.5, 0.25));
+        assert_close(Rgba::from_hsv(0.0, 0.5, 1.0, 1.0), Rgba::new(1.0, 0.5, 0.5, 1.0));
+        assert_close(Rgba::from_hsl(120.0, 1.0, 0.25, 1.0), Rgba::new(0.0, 0.5, 0.0, 1.0));
+        assert_close(Rgba::from_hsl(0.0, 1.0, 1.0, 1.0), Rgba::WHITE);
+    }
+
+    #[test]
+    fn lerp_is_clamped() {
+        assert_close(Rgba::BLACK.lerp(Rgba::WHITE, 0.5), Rgba::new(0.5, 0.5, 0.5, 1.0));
+        assert_close(Rgba::RED.lerp(Rgba::BLUE, 2.0), Rgba::BLUE);
+        assert_close(Rgba::RED.lerp(Rgba::BLUE, -1.0), Rgba::RED);
+    }
+
+    #[test]
+    fn srgb_linear_points() {
+        // sRGB and its linear value
+        let points = [(0.0, 0.0), (0.04045, 0.0031308), (0.5, 0.214041), (0.735357, 0.5), (1.0, 1.0)];
+        for (srgb, linear) in points {
+            assert!((srgb_to_linear(srgb) - linear).abs() < 1e-5, "{} to linear", srgb);
+            assert!((linear_to_srgb(linear) - srgb).abs() < 1e-5, "{} to srgb", linear);
+        }
+        let color = Rgba::new(0.5, 0.735357, 1.0, 0.5);
+        assert_close(color.to_linear(), Rgba::new(0.214041, 0.5, 1.0, 0.5));
+        assert_close(color.to_linear().to_srgb(), color);
+    }
+
+    #[test]
+    fn rgba8_conversions() {
+        assert_close(Rgba8::new(255, 0, 188, 128).to_linear_rgba(), Rgba::new(1.0, 0.0, 0.502886, 128.0 / 255.0));
+        assert_eq!(Rgba::new(1.5, -0.5, 0.5, 1.0).to_rgba8(), Rgba8::new(255, 0, 128, 255));
+    }
+}
+
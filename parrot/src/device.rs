@@ -1,7 +1,8 @@
+use std::marker::PhantomData;
 use euclid::Size2D;
 use wgpu::{util::DeviceExt, TextureFormat, TextureUsages};
 use crate::{
-    vertex::VertexLayout,
+    vertex::{self, VertexLayout},
     transform::ScreenSpace,
     shader::{
         Shader,
@@ -9,13 +10,14 @@ use crate::{
     },
     buffers::{
         vertex::VertexBuffer,
-        index::{IndexBuffer, IndexBuffer32},
-        uniform::UniformBuffer, DepthBuffer, FrameBuffer
+        index::{IndexBuffer, IndexBuffer32, Index},
+        uniform::UniformBuffer, DepthBuffer, FrameBuffer, StorageBuffer,
     },
     texture::Texture,
-    sampler::Sampler,
+    sampler::{Sampler, SamplerDesc},
     binding::{Binding, BindingGroupLayout, Bind, BindingGroup},
-    pipeline::{PipelineLayout, Pipeline, Blending, Set},
+    pipeline::{PipelineLayout, Pipeline, PipelineConfig, ComputePipeline, Set, PushConstantRange},
+    error::ParrotError,
 };
 
 /// Parrot wrapper around [wgpu::Device]
@@ -29,19 +31,69 @@ pub struct Device {
     pub surface: Option<wgpu::Surface>,
     /// Size of the surface
     size: euclid::Size2D<u32, ScreenSpace>,
+    /// The last configuration applied to the surface
+    config: Option<wgpu::SurfaceConfiguration>,
+    /// Downlevel flags of the adapter the device was created from
+    downlevel: wgpu::DownlevelFlags,
 }
 
 impl Device {
-    /// Create a device for a given surface
+    /// Returns the limits used when none are given. This is everything the adapter supports, so large render targets work on
+    /// capable hardware. On the web it's the WebGL2 defaults with the adapter's texture sizes, as browsers are more restrictive
+    pub fn default_limits(adapter: &wgpu::Adapter) -> wgpu::Limits {
+        #[cfg(target_arch = "wasm32")]
+        let limits = wgpu::Limits::downlevel_webgl2_defaults().using_resolution(adapter.limits());
+        #[cfg(not(target_arch = "wasm32"))]
+        let limits = adapter.limits();
+        limits
+    }
+
+    /// Create a device for a given surface with the adapter's limits, see [`Device::default_limits`]
     pub async fn for_surface(
         surface: wgpu::Surface,
         adapter: &wgpu::Adapter,
     ) -> Result<Self, wgpu::RequestDeviceError> {
+        Self::for_surface_with(surface, adapter, wgpu::Features::empty(), Self::default_limits(adapter)).await
+    }
+
+    /// Create a device for a given surface with the given features and limits
+    pub async fn for_surface_with(
+        surface: wgpu::Surface,
+        adapter: &wgpu::Adapter,
+        features: wgpu::Features,
+        limits: wgpu::Limits,
+    ) -> Result<Self, wgpu::RequestDeviceError> {
+        let mut device = Self::headless_with(adapter, features, limits).await?;
+        device.surface = Some(surface);
+        Ok(device)
+    }
+
+    /// Create a device with no surface with the adapter's limits. Useful for offscreen rendering
+    pub async fn headless(
+        adapter: &wgpu::Adapter,
+    ) -> Result<Self, wgpu::RequestDeviceError> {
+        Self::headless_with(adapter, wgpu::Features::empty(), Self::default_limits(adapter)).await
+    }
+
+    /// Create a device with no surface with the given features and limits.
+    /// Push constants are also requested when the adapter supports them
+    pub async fn headless_with(
+        adapter: &wgpu::Adapter,
+        features: wgpu::Features,
+        limits: wgpu::Limits,
+    ) -> Result<Self, wgpu::RequestDeviceError> {
+        log::info!("Creating device >> Features: {:?}", features);
+        let features = features | (adapter.features() & wgpu::Features::PUSH_CONSTANTS);
+        let limits = wgpu::Limits {
+            max_push_constant_size: limits.max_push_constant_size.max(adapter.limits().max_push_constant_size),
+            ..limits
+        };
+
         let (device, queue) = adapter.request_device(
             &wgpu::DeviceDescriptor {
                 label: Some("parrot device"),
-                limits: wgpu::Limits::default(),
-                features: wgpu::Features::empty(),
+                limits,
+                features,
             },
             None
         ).await?;
@@ -49,11 +101,18 @@ impl Device {
         Ok(Self {
             wgpu: device,
             queue,
-            surface: Some(surface),
+            surface: None,
             size: Size2D::default(),
+            config: None,
+            downlevel: adapter.get_downlevel_capabilities().flags,
         })
     }
 
+    /// Downlevel flags of the adapter. Lists what the backend supports beyond the WebGPU baseline
+    pub const fn downlevel_flags(&self) -> wgpu::DownlevelFlags {
+        self.downlevel
+    }
+
     pub const fn device(&self) -> &wgpu::Device {
         &self.wgpu
     }
@@ -68,7 +127,8 @@ impl Device {
         size: Size2D<u32, ScreenSpace>,
         mode: T,
         format: wgpu::TextureFormat,
-    ) {
+    ) -> Result<(), ParrotError> {
+        let surface = self.surface.as_ref().ok_or(ParrotError::NoSurface)?;
         let desc = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format,
@@ -76,8 +136,37 @@ impl Device {
             width: size.width,
             height: size.height,
         };
-        self.surface.as_ref().expect("no surface found").configure(&self.wgpu, &desc);
+        surface.configure(&self.wgpu, &desc);
         self.size = size;
+        self.config = Some(desc);
+        Ok(())
+    }
+
+    /// Get the last configuration applied to the surface
+    pub const fn config(&self) -> Option<&wgpu::SurfaceConfiguration> {
+        self.config.as_ref()
+    }
+
+    /// Get the present mode the surface was last configured with
+    pub fn present_mode(&self) -> Option<wgpu::PresentMode> {
+        self.config.as_ref().map(|c| c.present_mode)
+    }
+
+    /// Get the texture format the surface was last configured with
+    pub fn format(&self) -> Option<wgpu::TextureFormat> {
+        self.config.as_ref().map(|c| c.format)
+    }
+
+    /// Reapply the last surface configuration. Returns false if the surface was never configured
+    pub fn reconfigure(&mut self) -> bool {
+        match (&self.surface, &self.config) {
+            (Some(surface), Some(config)) => {
+                log::info!("Reconfiguring surface >> Width: {} || Height: {}", config.width, config.height);
+                surface.configure(&self.wgpu, config);
+                true
+            }
+            _ => false,
+        }
     }
 
     pub fn create_command_encoder(&self) -> wgpu::CommandEncoder {
@@ -90,23 +179,34 @@ impl Device {
         self.queue.submit(cmds);
     }
 
-    /// Create a shader given a [`crate::shader::ShaderFile`]
-    pub fn create_shader(&self, source: ShaderFile, name: Option<&str>) -> Shader {
+    /// Poll the device, running any callbacks for work the gpu has finished. [`wgpu::Maintain::Wait`] blocks until all submitted work is done.
+    /// Returns true if the queue is empty
+    pub fn poll(&self, maintain: wgpu::Maintain) -> bool {
+        self.wgpu.poll(maintain)
+    }
+
+    /// Create a shader given a [`crate::shader::ShaderFile`]. Wgsl shaders are validated first, see [`Device::create_wgsl_shader`]
+    pub fn create_shader(&self, source: ShaderFile, name: Option<&str>) -> Result<Shader, ParrotError> {
         log::info!("Creating shader >> Name: {:?}", name);
         match source {
-            ShaderFile::Spirv(bytes) => self.create_sprv_shader(bytes, name),
+            ShaderFile::Spirv(bytes) => Ok(self.create_sprv_shader(bytes, name)),
             ShaderFile::Wgsl(s) => self.create_wgsl_shader(s, name),
+            ShaderFile::WgslOwned(s) => self.create_wgsl_shader(&s, name),
         }
     }
 
-    /// Create a shader given the wgsl source code
-    pub fn create_wgsl_shader(&self, source: &str, name: Option<&str>) -> Shader {
-        Shader {
+    /// Create a shader given the wgsl source code. Returns [`ParrotError::ShaderCompile`] if the shader doesn't parse or validate
+    pub fn create_wgsl_shader(&self, source: &str, name: Option<&str>) -> Result<Shader, ParrotError> {
+        crate::shader::validate_wgsl(source).map_err(|e| {
+            log::error!("Shader failed to compile >> Name: {:?} || Error: {}", name, e);
+            e
+        })?;
+        Ok(Shader {
             wgpu: self.wgpu.create_shader_module(wgpu::ShaderModuleDescriptor {
                 label: name,
                 source: wgpu::ShaderSource::Wgsl(source.into())
             })
-        }
+        })
     }
 
     /// Create a shader given the bytes of a spirv bindary.
@@ -126,31 +226,32 @@ impl Device {
         VertexBuffer {
             wgpu: self.create_buffer_from_slice(vertices, wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST, 
                 name),
-            size: (vertices.len() * std::mem::size_of::<T>()) as u32,
+            size: std::mem::size_of_val(vertices) as u32,
+            len: std::mem::size_of_val(vertices) as u32,
             name: name.map(|s| s.to_string()),
         }
     }
 
-    /// Create 32 bit index buffer
-    pub fn create_index_buffer_32(&self, indicies: &[u32], name: Option<&str>) -> IndexBuffer32 {
-        log::info!("Created index buffer 32 >> Name: {:?}", name);
+    /// Create an index buffer holding any [`Index`] type
+    pub fn create_typed_index_buffer<I: Index>(&self, indicies: &[I], name: Option<&str>) -> IndexBuffer<I> {
+        log::info!("Created index buffer >> Name: {:?} || Format: {:?}", name, I::FORMAT);
         let index_buf = self.create_buffer_from_slice(indicies, wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST, name);
-        IndexBuffer32 {
+        IndexBuffer {
             wgpu: index_buf,
             size: indicies.len() as u32,
-            name: name.map(|s| s.to_string())
+            name: name.map(|s| s.to_string()),
+            index: PhantomData,
         }
     }
 
+    /// Create 32 bit index buffer
+    pub fn create_index_buffer_32(&self, indicies: &[u32], name: Option<&str>) -> IndexBuffer32 {
+        self.create_typed_index_buffer(indicies, name)
+    }
+
     /// Crate 16 bit index buffer
     pub fn create_index_buffer(&self, indicies: &[u16], name: Option<&str>) -> IndexBuffer {
-        log::info!("Created index buffer >> Name: {:?}", name);
-        let index_buf = self.create_buffer_from_slice(indicies, wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST, name);
-        IndexBuffer {
-            wgpu: index_buf,
-            size: indicies.len() as u32,
-            name: name.map(|s| s.to_string())
-        }
+        self.create_typed_index_buffer(indicies, name)
     }
 
     pub fn create_uniform_buffer<T>(&self, buf: &[T], name: Option<&str>) -> UniformBuffer
@@ -166,17 +267,57 @@ impl Device {
                 contents: bytemuck::cast_slice(buf),
                 usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             }),
+            name: name.map(|s| s.to_string()),
+            dynamic: false,
+        }
+    }
+
+    /// Create a uniform buffer where each element is aligned so it can be selected with a dynamic offset
+    pub fn create_dynamic_uniform_buffer<T>(&self, buf: &[T], name: Option<&str>) -> UniformBuffer
+    where
+        T: bytemuck::Pod + 'static + Copy
+    {
+        log::info!("Created dynamic uniform buffer >> Name: {:?} || Stride: {}", name, UniformBuffer::aligned_stride(std::mem::size_of::<T>()));
+        UniformBuffer {
+            size: std::mem::size_of::<T>(),
+            count: buf.len(),
+            wgpu: self.create_buffer_from_slice(UniformBuffer::pack_dynamic(buf).as_slice(), wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST, name),
+            name: name.map(|s| s.to_string()),
+            dynamic: true,
+        }
+    }
+
+    /// Create a storage buffer. The buffer can also be copied from so its contents can be read back.
+    pub fn create_storage_buffer<T>(&self, buf: &[T], name: Option<&str>) -> StorageBuffer
+    where
+        T: bytemuck::Pod + 'static + Copy
+    {
+        log::info!("Created storage buffer >> Name: {:?}", name);
+        StorageBuffer {
+            size: std::mem::size_of::<T>(),
+            count: buf.len(),
+            wgpu: self.create_buffer_from_slice(buf, wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC, name),
             name: name.map(|s| s.to_string())
         }
     }
 
     /// Create a depth buffer
     pub fn create_depth_buffer(&self, sample_count: u32, name: Option<&str>) -> DepthBuffer {
-        log::info!("Created depth buffer");
-        let format = DepthBuffer::FORMAT;
+        self.create_depth_buffer_with_format(sample_count, DepthBuffer::FORMAT, name)
+    }
+
+    /// Create a depth buffer with the given depth (or depth stencil) format
+    pub fn create_depth_buffer_with_format(&self, sample_count: u32, format: wgpu::TextureFormat, name: Option<&str>) -> DepthBuffer {
+        self.create_depth_buffer_with_size(self.size, sample_count, format, name)
+    }
+
+    /// Create a depth buffer of the given size instead of the surface's size. Used for frame buffers
+    pub fn create_depth_buffer_with_size(&self, size: Size2D<u32, ScreenSpace>, sample_count: u32, format: wgpu::TextureFormat, name: Option<&str>) -> DepthBuffer {
+        log::info!("Created depth buffer >> Format: {:?} || Size: {:?}", format, size);
+        let usage = wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::RENDER_ATTACHMENT;
         let extent = wgpu::Extent3d {
-            width: self.size.width,
-            height: self.size.height,
+            width: size.width,
+            height: size.height,
             depth_or_array_layers: 1,
         };
 
@@ -187,7 +328,7 @@ impl Device {
             sample_count,
             dimension: wgpu::TextureDimension::D2,
             format,
-            usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage,
         });
         let view = wgpu.create_view(&wgpu::TextureViewDescriptor::default());
 
@@ -196,16 +337,29 @@ impl Device {
             view,
             extent,
             format,
-            size: self.size,
-        }}
+            size,
+            mip_level_count: 1,
+            usage,
+        }, sample_count }
     }
 
+    /// Create a buffer from a slice. wgpu doesn't allow zero sized buffers, so an empty slice will create a minimum sized buffer instead.
     pub fn create_buffer_from_slice<T: bytemuck::Pod> (
         &self,
         slice: &[T],
         usage: wgpu::BufferUsages,
         name: Option<&str>
     ) -> wgpu::Buffer {
+        if slice.is_empty() {
+            log::debug!("Empty slice given, creating minimum sized buffer >> Name: {:?}", name);
+            return self.wgpu.create_buffer(&wgpu::BufferDescriptor {
+                label: name,
+                size: wgpu::COPY_BUFFER_ALIGNMENT,
+                usage: usage | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+        }
+
         self.wgpu.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: name,
             contents: bytemuck::cast_slice(slice),
@@ -213,6 +367,38 @@ impl Device {
         })
     }
 
+    /// Create a staging buffer. Used to read data from the gpu back to the cpu.
+    pub fn create_staging_buffer(&self, size_bytes: u64, name: Option<&str>) -> wgpu::Buffer {
+        log::info!("Creating staging buffer >> Name: {:?} || Size: {}", name, size_bytes);
+        self.wgpu.create_buffer(&wgpu::BufferDescriptor {
+            label: name,
+            size: size_bytes,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Maps a staging buffer and reads `len` elements out of it. This blocks until the gpu is finished with the buffer.
+    /// Returns [`ParrotError::BufferMap`] if the buffer couldn't be mapped
+    pub fn map_and_read_buffer<T: bytemuck::Pod>(&self, buffer: &wgpu::Buffer, len: usize) -> Result<Vec<T>, ParrotError> {
+        log::info!("Reading staging buffer >> Length: {}", len);
+        let size = (len * std::mem::size_of::<T>()) as wgpu::BufferAddress;
+        let slice = buffer.slice(0..size);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |res| {
+            sender.send(res).ok();
+        });
+        self.wgpu.poll(wgpu::Maintain::Wait);
+
+        if let Err(e) = receiver.recv().unwrap_or(Err(wgpu::BufferAsyncError)) {
+            log::error!("Failed to map staging buffer >> {:?}", e);
+            return Err(ParrotError::BufferMap(e));
+        }
+        let data = bytemuck::cast_slice(&slice.get_mapped_range()).to_vec();
+        buffer.unmap();
+        Ok(data)
+    }
+
     /// Create a texture
     pub fn create_texture(
         &self,
@@ -222,7 +408,32 @@ impl Device {
         name: Option<&str>,
         sample_count: u32,
     ) -> Texture {
-        log::info!("Creating texture >> Name: {:?}", name);
+        self.create_texture_with_mips(size, format, usage, name, sample_count, 1)
+    }
+
+    /// Create a texture with a full mip chain. The mip levels can be filled using [`Texture::generate_mipmaps`],
+    /// which requires the texture to have the [`wgpu::TextureUsages::TEXTURE_BINDING`] and [`wgpu::TextureUsages::RENDER_ATTACHMENT`] usages.
+    pub fn create_texture_mipmapped(
+        &self,
+        size: euclid::Size2D<u32, ScreenSpace>,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+        name: Option<&str>,
+    ) -> Texture {
+        self.create_texture_with_mips(size, format, usage, name, 1, Texture::mip_levels_for(size))
+    }
+
+    /// Create a texture with the given amount of mip levels
+    pub fn create_texture_with_mips(
+        &self,
+        size: euclid::Size2D<u32, ScreenSpace>,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+        name: Option<&str>,
+        sample_count: u32,
+        mip_level_count: u32,
+    ) -> Texture {
+        log::info!("Creating texture >> Name: {:?} || Mip levels: {}", name, mip_level_count);
         let texture_extent = wgpu::Extent3d {
             width: size.width,
             height: size.height,
@@ -232,7 +443,7 @@ impl Device {
         let texture = self.wgpu.create_texture( &wgpu::TextureDescriptor {
             label: name,
             size: texture_extent,
-            mip_level_count: 1,
+            mip_level_count,
             sample_count,
             dimension: wgpu::TextureDimension::D2,
             format,
@@ -247,31 +458,92 @@ impl Device {
             extent: texture_extent,
             format,
             size,
+            mip_level_count,
+            usage,
+        }
+    }
+
+    /// Create a 2D texture array with `layers` layers. The view covers every layer, so it must be bound with [`crate::binding::BindingType::TextureArray`].
+    /// Fill each layer with [`Texture::fill_layer`]
+    pub fn create_texture_array(
+        &self,
+        size: euclid::Size2D<u32, ScreenSpace>,
+        layers: u32,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+        name: Option<&str>,
+    ) -> Texture {
+        log::info!("Creating texture array >> Name: {:?} || Layers: {}", name, layers);
+        let texture_extent = wgpu::Extent3d {
+            width: size.width,
+            height: size.height,
+            depth_or_array_layers: layers,
+        };
+
+        let texture = self.wgpu.create_texture( &wgpu::TextureDescriptor {
+            label: name,
+            size: texture_extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage,
+        });
+
+        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: name,
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            ..Default::default()
+        });
+
+        Texture {
+            wgpu: texture,
+            view: texture_view,
+            extent: texture_extent,
+            format,
+            size,
+            mip_level_count: 1,
+            usage,
         }
     }
 
     pub fn create_sampler(&self, mag_filter: wgpu::FilterMode, min_filter: wgpu::FilterMode, name: Option<&str>) -> Sampler {
+        self.create_sampler_with_desc(&SamplerDesc::new(mag_filter, min_filter), name)
+    }
+
+    /// Create a sampler from a [`SamplerDesc`]
+    pub fn create_sampler_with_desc(&self, desc: &SamplerDesc, name: Option<&str>) -> Sampler {
         log::info!("Creating sampler >> Name: {:?}", name);
+        // wgpu panics on an invalid anisotropy so drop it instead
+        let anisotropy_clamp = match desc.validate() {
+            Ok(()) => desc.anisotropy,
+            Err(e) => {
+                log::error!("Ignoring sampler anisotropy >> Name: {:?} || Error: {}", name, e);
+                None
+            }
+        };
+        let (lod_min_clamp, lod_max_clamp) = desc.wgpu_lod_clamp();
         Sampler {
             wgpu: self.wgpu.create_sampler( &wgpu::SamplerDescriptor{
                 label: name,
-                address_mode_u: wgpu::AddressMode::ClampToEdge,
-                address_mode_v: wgpu::AddressMode::ClampToEdge,
-                address_mode_w: wgpu::AddressMode::ClampToEdge,
-                mag_filter,
-                min_filter,
-                mipmap_filter: wgpu::FilterMode::Nearest,
-                lod_max_clamp: 100.0,
-                lod_min_clamp: -100.0,
-                compare: None,
-                border_color: None,
-                anisotropy_clamp: None,
+                address_mode_u: desc.address_mode_u.into(),
+                address_mode_v: desc.address_mode_v.into(),
+                address_mode_w: desc.address_mode_w.into(),
+                mag_filter: desc.mag_filter,
+                min_filter: desc.min_filter,
+                mipmap_filter: desc.mipmap_filter,
+                lod_max_clamp,
+                lod_min_clamp,
+                compare: desc.compare.map(Into::into),
+                border_color: desc.wgpu_border_color(),
+                anisotropy_clamp,
             })
         }
     }
 
     pub fn create_frame_buffer(&self, size: Size2D<u32, ScreenSpace>, format: TextureFormat, sample_count: u32, name: Option<&str>, depth: bool) -> FrameBuffer {
         log::info!("Creating frame buffer >> Name: {:?} || Depth: {}", name, depth);
+        let usage = TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::COPY_SRC | TextureUsages::RENDER_ATTACHMENT;
         let extent = wgpu::Extent3d {
             width: size.width,
             height: size.height,
@@ -283,7 +555,7 @@ impl Device {
             sample_count,
             dimension: wgpu::TextureDimension::D2,
             format,
-            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::COPY_SRC | TextureUsages::RENDER_ATTACHMENT,
+            usage,
             label: name
         });
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
@@ -295,9 +567,11 @@ impl Device {
                     view,
                     extent,
                     format,
-                    size
+                    size,
+                    mip_level_count: 1,
+                    usage,
                 },
-                depth: Some(self.create_depth_buffer(sample_count, name))
+                depth: Some(self.create_depth_buffer_with_size(size, sample_count, DepthBuffer::FORMAT, name))
             }
         } else {
             FrameBuffer {
@@ -306,7 +580,9 @@ impl Device {
                     view,
                     extent,
                     format,
-                    size
+                    size,
+                    mip_level_count: 1,
+                    usage,
                 },
                 depth: None
             }
@@ -333,9 +609,11 @@ impl Device {
         BindingGroupLayout::new(index, layout, bindings.len())
     }
 
-    pub fn create_binding_group(&self, layout: &BindingGroupLayout, binds: &[&dyn Bind], name: Option<&str>) -> BindingGroup {
+    pub fn create_binding_group(&self, layout: &BindingGroupLayout, binds: &[&dyn Bind], name: Option<&str>) -> Result<BindingGroup, ParrotError> {
         log::info!("Creating binding >> Name: {:?}", name);
-        assert_eq!(binds.len(), layout.size, "Layout slot doesn't match bindings");
+        if binds.len() != layout.size {
+            return Err(ParrotError::BindingCountMismatch { expected: layout.size, got: binds.len() });
+        }
 
         let mut bindings = Vec::new();
 
@@ -343,18 +621,19 @@ impl Device {
             bindings.push(b.binding(i as u32));
         }
 
-        BindingGroup::new(
+        Ok(BindingGroup::new(
             layout.set_index,
             self.wgpu.create_bind_group(&wgpu::BindGroupDescriptor {
                 layout: &layout.wgpu,
                 label: name,
                 entries: bindings.as_slice()
             }),
-        )
+        ))
     }
 
-    /// Create a pipeline layout from a set of bindings
-    pub fn create_pipeline_layout(&self, sets: Option<&[Set<'_>]>) -> PipelineLayout {
+    /// Create a pipeline layout from a set of bindings and push constant ranges.
+    /// Push constants are dropped with an error if the device doesn't support them
+    pub fn create_pipeline_layout(&self, sets: Option<&[Set<'_>]>, push_constants: &[PushConstantRange]) -> PipelineLayout {
         let mut b_layouts = Vec::new();
         if let Some(ss) = sets {
             for (index, bindings) in ss.iter().enumerate() {
@@ -362,15 +641,27 @@ impl Device {
                 b_layouts.push(self.create_binding_group_layout(index as u32, bindings.0, bindings.1))
             }
         }
-        
+
+        let push_constants = if push_constants.is_empty() || self.wgpu.features().contains(wgpu::Features::PUSH_CONSTANTS) {
+            push_constants.iter().map(PushConstantRange::as_wgpu).collect()
+        } else {
+            log::error!("Push constants are not supported by this device. The push constant ranges will be ignored");
+            vec![]
+        };
+
         PipelineLayout {
             b_layouts,
+            push_constants,
         }
     }
 
     /// Updates a uniform buffer
     pub fn update_buffer<T: bytemuck::Pod + Copy + 'static>(&self, slice: &[T], buf: &mut UniformBuffer) {
-        self.queue.write_buffer(&buf.wgpu, 0, bytemuck::cast_slice(slice));
+        if buf.dynamic {
+            self.queue.write_buffer(&buf.wgpu, 0, UniformBuffer::pack_dynamic(slice).as_slice());
+        } else {
+            self.queue.write_buffer(&buf.wgpu, 0, bytemuck::cast_slice(slice));
+        }
         buf.size = std::mem::size_of::<T>();
         buf.count = slice.len();
     }
@@ -378,48 +669,54 @@ impl Device {
     /// Updates a vertex buffer
     pub fn update_vertex_buffer<T: bytemuck::Pod + Copy + 'static>(&self, vertices: &[T], buf: &mut VertexBuffer) {
         self.queue.write_buffer(&buf.wgpu, 0, bytemuck::cast_slice(vertices));
+        buf.len = std::mem::size_of_val(vertices) as u32;
     }
 
-    /// Update a 32 bit index buffer
-    pub fn update_index_buffer_32(&self, mut indicies: Vec<u32>, buf: &mut IndexBuffer32) {
-        // Get the alignment
-        let alignment = wgpu::COPY_BUFFER_ALIGNMENT as usize / std::mem::size_of::<u32>();
-        let fraction = indicies.len() % alignment;
-        // Extend the index buffer so its aligned
-        if fraction > 0 {
-            indicies.extend(std::iter::repeat(0).take(alignment - fraction));
+    /// Updates part of a vertex buffer, starting `offset` bytes in. The buffer isn't resized, so the data must fit.
+    /// Returns [`ParrotError::BufferOutOfBounds`] if it doesn't, or [`ParrotError::UnalignedBufferWrite`] if the offset or length aren't multiples of 4
+    pub fn update_vertex_buffer_at<T: bytemuck::Pod + Copy + 'static>(&self, offset: u64, vertices: &[T], buf: &mut VertexBuffer) -> Result<(), ParrotError> {
+        let bytes: &[u8] = bytemuck::cast_slice(vertices);
+        let len = bytes.len() as u64;
+        if offset + len > buf.size as u64 {
+            return Err(ParrotError::BufferOutOfBounds { offset, len, size: buf.size as u64 });
         }
+        if offset % wgpu::COPY_BUFFER_ALIGNMENT != 0 || len % wgpu::COPY_BUFFER_ALIGNMENT != 0 {
+            return Err(ParrotError::UnalignedBufferWrite { offset, len });
+        }
+        self.queue.write_buffer(&buf.wgpu, offset, bytes);
+        buf.len = buf.len.max((offset + len) as u32);
+        Ok(())
+    }
+
+    /// Update an index buffer holding any [`Index`] type
+    pub fn update_typed_index_buffer<I: Index>(&self, mut indicies: Vec<I>, buf: &mut IndexBuffer<I>) {
+        pad_indicies(&mut indicies);
 
         // Update the buffer
         self.queue.write_buffer(&buf.wgpu, 0, bytemuck::cast_slice(indicies.as_slice()));
     }
 
-    /// Update a 16 bit index buffer
-    pub fn update_index_buffer(&self, mut indicies: Vec<u16>, buf: &mut IndexBuffer) {
-        // Get the alignment
-        let alignment = wgpu::COPY_BUFFER_ALIGNMENT as usize / std::mem::size_of::<u16>();
-        let fraction = indicies.len() % alignment;
-        // Extend the index buffer so its aligned
-        if fraction > 0 {
-            indicies.extend(std::iter::repeat(0).take(alignment - fraction));
-        }
+    /// Update a 32 bit index buffer
+    pub fn update_index_buffer_32(&self, indicies: Vec<u32>, buf: &mut IndexBuffer32) {
+        self.update_typed_index_buffer(indicies, buf)
+    }
 
-        // Update the buffer
-        self.queue.write_buffer(&buf.wgpu, 0, bytemuck::cast_slice(indicies.as_slice()));
+    /// Update a 16 bit index buffer
+    pub fn update_index_buffer(&self, indicies: Vec<u16>, buf: &mut IndexBuffer) {
+        self.update_typed_index_buffer(indicies, buf)
     }
 
-    /// Create a pipeline
+    /// Create a pipeline. There is a color target for each of the config's formats, at the same location as its position in the slice.
+    /// The pipeline only has a depth buffer if the config has a [`DepthConfig`]
     pub fn create_pipeline(
         &self,
         pipeline_layout: PipelineLayout,
         vertex_layout: VertexLayout,
-        blending: Blending,
+        instance_layout: Option<VertexLayout>,
         shader: Shader,
-        tex_format: wgpu::TextureFormat,
-        multisample: wgpu::MultisampleState,
-        name: Option<&str>
+        config: &PipelineConfig,
     ) -> Pipeline {
-        let vertex_attrs = vertex_layout.to_wgpu();
+        let vertex_attrs = vertex::buffer_layouts(&vertex_layout, instance_layout.as_ref());
         let mut b_layouts = Vec::new();
 
         for s in pipeline_layout.b_layouts.iter() {
@@ -427,68 +724,42 @@ impl Device {
         }
 
         let layout = &self.wgpu.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: name,
+            label: config.name,
             bind_group_layouts: b_layouts.as_slice(),
-            push_constant_ranges: &[],
+            push_constant_ranges: pipeline_layout.push_constants.as_slice(),
         });
 
-        let (src_factor, dst_factor, operation) = blending.as_wgpu();
-
         // I like your funny words magic man
-        let targets = [Some(wgpu::ColorTargetState {
-            format: tex_format,
-            blend: Some(wgpu::BlendState {
-                color: wgpu::BlendComponent {
-                    src_factor,
-                    dst_factor,
-                    operation
-                },
-                alpha: wgpu::BlendComponent {
-                    src_factor,
-                    dst_factor,
-                    operation
-                },
-            }),
-            write_mask: wgpu::ColorWrites::ALL,
-        })];
+        let targets: Vec<_> = config.formats.iter().map(|format| Some(wgpu::ColorTargetState {
+            format: *format,
+            blend: Some(config.blending.as_blend_state()),
+            write_mask: config.write_mask,
+        })).collect();
 
         let desc = wgpu::RenderPipelineDescriptor {
-            label: name,
+            label: config.name,
             layout: Some(layout),
             vertex: wgpu::VertexState {
                 module: &shader.wgpu,
-                entry_point: "vs_main",
-                buffers: &[vertex_attrs],
+                entry_point: config.vertex_entry,
+                buffers: &vertex_attrs,
             },
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: DepthBuffer::FORMAT,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::LessEqual,
-                stencil: wgpu::StencilState {
-                    front: wgpu::StencilFaceState::IGNORE,
-                    back: wgpu::StencilFaceState::IGNORE,
-                    read_mask: 0,
-                    write_mask: 0,
-                },
+            primitive: config.primitive,
+            depth_stencil: config.depth.map(|depth| wgpu::DepthStencilState {
+                format: depth.format,
+                depth_write_enabled: depth.write_enabled,
+                depth_compare: depth.compare.into(),
+                stencil: depth.stencil.into(),
                 bias: wgpu::DepthBiasState {
                     constant: 0,
                     slope_scale: 0.,
                     clamp: 0.,
                 }
             }),
-            multisample,
+            multisample: config.multisample,
             fragment: Some(wgpu::FragmentState {
                 module: &shader.wgpu,
-                entry_point: "fs_main",
+                entry_point: config.fragment_entry,
                 targets: &targets,
             }),
             multiview: None,
@@ -499,22 +770,36 @@ impl Device {
         Pipeline {
             layout: pipeline_layout,
             vertex_layout,
+            instance_layout,
+            depth: config.depth,
             wgpu,
         }
     }
 
-    /// Create a pipeline without a depth buffer
+    /// Create a pipeline without a depth buffer, ignoring the config's [`DepthConfig`]
     pub fn create_pipeline_no_depth(
         &self,
         pipeline_layout: PipelineLayout,
         vertex_layout: VertexLayout,
-        blending: Blending,
+        instance_layout: Option<VertexLayout>,
         shader: Shader,
-        tex_format: wgpu::TextureFormat,
-        multisample: wgpu::MultisampleState,
-        name: Option<&str>
+        config: &PipelineConfig,
     ) -> Pipeline {
-        let vertex_attrs = vertex_layout.to_wgpu();
+        self.create_pipeline(pipeline_layout, vertex_layout, instance_layout, shader, &PipelineConfig {
+            depth: None,
+            ..config.clone()
+        })
+    }
+
+    /// Create a compute pipeline
+    pub fn create_compute_pipeline(
+        &self,
+        pipeline_layout: PipelineLayout,
+        shader: Shader,
+        entry_point: &str,
+        name: Option<&str>
+    ) -> ComputePipeline {
+        log::info!("Creating compute pipeline >> Name: {:?} || Entry point: {}", name, entry_point);
         let mut b_layouts = Vec::new();
 
         for s in pipeline_layout.b_layouts.iter() {
@@ -524,77 +809,130 @@ impl Device {
         let layout = &self.wgpu.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: name,
             bind_group_layouts: b_layouts.as_slice(),
-            push_constant_ranges: &[],
+            push_constant_ranges: pipeline_layout.push_constants.as_slice(),
         });
 
-        let (src_factor, dst_factor, operation) = blending.as_wgpu();
-
-        // I like your funny words magic man
-        let targets = [Some(wgpu::ColorTargetState {
-            format: tex_format,
-            blend: Some(wgpu::BlendState {
-                color: wgpu::BlendComponent {
-                    src_factor,
-                    dst_factor,
-                    operation
-                },
-                alpha: wgpu::BlendComponent {
-                    src_factor,
-                    dst_factor,
-                    operation
-                },
-            }),
-            write_mask: wgpu::ColorWrites::ALL,
-        })];
-
-        let desc = wgpu::RenderPipelineDescriptor {
+        let wgpu = self.wgpu.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
             label: name,
             layout: Some(layout),
-            vertex: wgpu::VertexState {
-                module: &shader.wgpu,
-                entry_point: "vs_main",
-                buffers: &[vertex_attrs],
-            },
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample,
-            fragment: Some(wgpu::FragmentState {
-                module: &shader.wgpu,
-                entry_point: "fs_main",
-                targets: &targets,
-            }),
-            multiview: None,
-        };
-
-        let wgpu = self.wgpu.create_render_pipeline(&desc);
+            module: &shader.wgpu,
+            entry_point,
+        });
 
-        Pipeline {
+        ComputePipeline {
             layout: pipeline_layout,
-            vertex_layout,
             wgpu,
         }
     }
 
-    pub fn create_render_bundle_encoder(&self, format: wgpu::TextureFormat, name: Option<&str>, sample_count: u32) -> wgpu::RenderBundleEncoder {
-        log::info!("Creating render bundle encoder >> Name: {:?}", name);
+    /// Create a render bundle encoder for passes with the given color format and depth format (if they have a depth buffer)
+    pub fn create_render_bundle_encoder(&self, format: wgpu::TextureFormat, depth_format: Option<wgpu::TextureFormat>, name: Option<&str>, sample_count: u32) -> wgpu::RenderBundleEncoder {
+        log::info!("Creating render bundle encoder >> Name: {:?} || Depth format: {:?}", name, depth_format);
         self.wgpu.create_render_bundle_encoder(&wgpu::RenderBundleEncoderDescriptor {
             label: name,
-            depth_stencil: Some(wgpu::RenderBundleDepthStencil {
-                format: DepthBuffer::FORMAT,
+            depth_stencil: depth_format.map(|format| wgpu::RenderBundleDepthStencil {
+                format,
                 depth_read_only: false,
-                stencil_read_only: false,
+                // Depth only formats have no stencil to write to
+                stencil_read_only: !DepthBuffer::has_stencil(format),
             }),
             sample_count,
             color_formats: &[Some(format)],
             multiview: None,
         })
     }
-}
\ No newline at end of file
+}
+/// Extend the indicies with zeros so their size in bytes is a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`]
+fn pad_indicies<I: Index>(indicies: &mut Vec<I>) {
+    // Get the alignment
+    let alignment = (wgpu::COPY_BUFFER_ALIGNMENT as usize / I::SIZE).max(1);
+    let fraction = indicies.len() % alignment;
+    if fraction > 0 {
+        indicies.extend(std::iter::repeat_n(I::zeroed(), alignment - fraction));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{binding::BindingType, painter::tests::headless};
+    use super::*;
+
+    #[test]
+    fn binding_count_mismatch() {
+        let paint = match headless() {
+            Some(paint) => paint,
+            None => return,
+        };
+        let layout = paint.device.create_binding_group_layout(0, &[Binding {
+            binding: BindingType::UniformBuffer,
+            stage: wgpu::ShaderStages::VERTEX,
+        }], None);
+        let uniform = paint.uniform_buffer(&[[0.0f32; 4]], None);
+
+        assert!(matches!(
+            paint.binding_group(&layout, &[], None),
+            Err(ParrotError::BindingCountMismatch { expected: 1, got: 0 })
+        ));
+        assert!(matches!(
+            paint.binding_group(&layout, &[&uniform, &uniform], None),
+            Err(ParrotError::BindingCountMismatch { expected: 1, got: 2 })
+        ));
+        assert!(paint.binding_group(&layout, &[&uniform], None).is_ok());
+    }
+
+    #[test]
+    #[ignore = "needs a gpu"]
+    fn reconfigure_without_surface() {
+        let mut paint = headless().expect("No adapter");
+        assert!(matches!(paint.device.configure(Size2D::new(8, 8), wgpu::PresentMode::Fifo, TextureFormat::Bgra8UnormSrgb), Err(ParrotError::NoSurface)));
+        assert!(paint.device.config().is_none());
+        assert!(!paint.device.reconfigure());
+    }
+
+    #[test]
+    #[ignore = "needs a window"]
+    #[cfg(target_os = "linux")]
+    fn reconfigure_uses_stored_config() {
+        let (_event_loop, _window, mut paint) = crate::painter::tests::windowed();
+
+        // Nothing to reapply before the first configure
+        assert!(!paint.device.reconfigure());
+
+        let format = paint.preferred_format();
+        paint.configure(Size2D::new(64, 48), wgpu::PresentMode::Fifo, format).unwrap();
+        let stored = paint.device.config().cloned().unwrap();
+        assert_eq!((stored.width, stored.height, stored.format), (64, 48, format));
+        assert_eq!(paint.device.present_mode(), Some(wgpu::PresentMode::Fifo));
+
+        assert!(paint.device.reconfigure());
+        assert_eq!(paint.device.config(), Some(&stored));
+        assert_eq!(paint.device.size(), Size2D::new(64, 48));
+        assert!(paint.current_frame().is_ok());
+    }
+
+    #[test]
+    fn index_padding() {
+        // A u32 is already 4 bytes so odd lengths are left alone
+        let mut wide = vec![1u32, 2, 3];
+        pad_indicies(&mut wide);
+        assert_eq!(wide, vec![1, 2, 3]);
+
+        let mut narrow = vec![1u16, 2, 3];
+        pad_indicies(&mut narrow);
+        assert_eq!(narrow, vec![1, 2, 3, 0]);
+        pad_indicies(&mut narrow);
+        assert_eq!(narrow.len(), 4);
+    }
+
+    #[test]
+    #[ignore = "needs a gpu"]
+    fn update_odd_length_index_buffers() {
+        let paint = headless().expect("No adapter");
+        let mut wide = paint.device.create_typed_index_buffer(&[0u32; 4], None);
+        paint.device.update_typed_index_buffer(vec![1u32, 2, 3], &mut wide);
+        let mut narrow = paint.device.create_typed_index_buffer(&[0u16; 4], None);
+        paint.device.update_typed_index_buffer(vec![1u16, 2, 3], &mut narrow);
+        // An unaligned write would be a validation error
+        paint.device.poll(wgpu::Maintain::Wait);
+    }
+}
@@ -6,6 +6,44 @@ pub enum ParrotError {
     NoAdaptersFound,
     #[error("Device creation error")]
     RequestDeviceError(#[from] wgpu::RequestDeviceError),
+    #[error("Sample count {0} is not supported")]
+    InvalidSampleCount(u32),
+    #[error("Sample count {requested} is not supported for {format:?}. Supported sample counts: {supported:?}")]
+    UnsupportedSampleCount { requested: u32, format: wgpu::TextureFormat, supported: Vec<u32> },
+    #[error("There is no surface. The painter was created headless")]
+    NoSurface,
+    #[error("The surface isn't compatible with the adapter and has no supported formats")]
+    NoSurfaceFormats,
+    #[error("Surface error: {0}")]
+    SurfaceError(#[from] wgpu::SurfaceError),
+    #[error("Texture is missing the {0:?} usage")]
+    MissingTextureUsage(wgpu::TextureUsages),
+    #[error("Anisotropy of {0} is invalid. It must be 1, 2, 4, 8 or 16 and the mag, min and mipmap filters must all be linear")]
+    InvalidAnisotropy(u8),
+    #[error("The adapter doesn't support the requested features: {0:?}")]
+    UnsupportedFeatures(wgpu::Features),
+    #[error("The adapter doesn't support the requested limits")]
+    UnsupportedLimits,
+    #[error("Binding group layout expects {expected} bindings but {got} were given")]
+    BindingCountMismatch { expected: usize, got: usize },
+    #[error("Uniform buffer holds {expected} ({expected_size} bytes) but was updated with {got} ({got_size} bytes)")]
+    UniformTypeMismatch { expected: &'static str, expected_size: usize, got: &'static str, got_size: usize },
+    #[error("Failed to decode image: {0}")]
+    ImageDecode(String),
+    #[error("Shader failed to compile:\n{message}")]
+    ShaderCompile { message: String },
+    #[error("Writing {len} bytes at offset {offset} overruns a buffer of {size} bytes")]
+    BufferOutOfBounds { offset: u64, len: u64, size: u64 },
+    #[error("Buffer writes must be aligned to 4 bytes. Offset: {offset} || Length: {len}")]
+    UnalignedBufferWrite { offset: u64, len: u64 },
+    #[error("Expected a {expected:?} texture but the texture is {got:?}")]
+    WrongTextureFormat { expected: wgpu::TextureFormat, got: wgpu::TextureFormat },
+    #[error("Texture has a sample count of {0}. Multisampled textures can't be read back")]
+    MultisampledReadback(u32),
+    #[error("The adapter doesn't support the {0:?} downlevel flags")]
+    UnsupportedDownlevel(wgpu::DownlevelFlags),
+    #[error("Failed to map buffer: {0}")]
+    BufferMap(#[from] wgpu::BufferAsyncError),
 }
 
 impl From<ParrotError> for io::Error {
@@ -1,18 +1,21 @@
 use wgpu::TextureView;
 
 use crate::{
-    painter::{RenderTarget, PassOp, RenderPassExtention}
+    painter::{RenderTarget, PassOp, DepthOp, RenderPassExtention}
 };
 
 #[derive(Debug)]
 pub struct Frame {
     pub encoder: wgpu::CommandEncoder,
+    /// Number of render passes started on this frame
+    pub pass_count: u32,
 }
 
 impl Frame {
     pub fn new(encoder: wgpu::CommandEncoder) -> Self {
         Self {
-            encoder
+            encoder,
+            pass_count: 0,
         }
     }
 
@@ -23,25 +26,61 @@ impl Frame {
         view: &'a impl RenderTarget,
         frame_buffer: Option<&'a TextureView>,
     ) -> wgpu::RenderPass<'a> {
+        self.pass_with_depth(op, DepthOp::default(), view, frame_buffer)
+    }
+
+    /// Same as [`Frame::pass`] but `depth_op` controls whether the depth buffer is cleared or loaded. Use [`DepthOp::Load`]
+    /// to keep the depth written by an earlier pass.
+    pub fn pass_with_depth<'a>(
+        &'a mut self,
+        op: PassOp,
+        depth_op: DepthOp,
+        view: &'a impl RenderTarget,
+        frame_buffer: Option<&'a TextureView>,
+    ) -> wgpu::RenderPass<'a> {
+        self.pass_count += 1;
         let (pass_view, resolve_target) = match frame_buffer {
             Some(buffer) => (buffer, Some(view.color_target())),
             None => (view.color_target(), None),
         };
 
-        wgpu::RenderPass::begin(
+        wgpu::RenderPass::begin_with_ops(
             &mut self.encoder,
             pass_view,
             resolve_target,
             view.depth_target(),
             op,
+            depth_op,
+            true,
         )
     }
 
+    /// Start a render pass that draws to every target at once, for pipelines with extra [`crate::pipeline::PipelineDescription::targets`].
+    /// Targets are bound to color locations in the order they're given. Their depth targets are ignored, `depth` is used instead
+    pub fn pass_multi<'a>(
+        &'a mut self,
+        op: PassOp,
+        targets: &[&'a dyn RenderTarget],
+        depth: Option<&'a TextureView>,
+    ) -> wgpu::RenderPass<'a> {
+        self.pass_count += 1;
+        let views: Vec<&TextureView> = targets.iter().map(|t| t.color_target()).collect();
+        wgpu::RenderPass::begin_multi(&mut self.encoder, &views, depth, op, DepthOp::default())
+    }
+
+    /// Start a compute pass on the frame
+    pub fn compute_pass(&mut self) -> wgpu::ComputePass<'_> {
+        log::debug!("Began compute pass");
+        self.encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: None,
+        })
+    }
+
     pub fn encoder(&self) -> &wgpu::CommandEncoder {
         &self.encoder
     }
 
-    pub fn encoder_mut(&mut self) -> &wgpu::CommandEncoder {
+    pub fn encoder_mut(&mut self) -> &mut wgpu::CommandEncoder {
         &mut self.encoder
     }
 }
\ No newline at end of file
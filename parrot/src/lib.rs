@@ -24,7 +24,7 @@
 //!     // Get the preferred texture format for the surface
 //!    let pref_format = painter.preferred_format();
 //!     // Configure the surface
-//!     painter.configure(euclid::Size2D::new(winsize.width, winsize.height), wgpu::PresentMode::Fifo, pref_format);
+//!     painter.configure(euclid::Size2D::new(winsize.width, winsize.height), wgpu::PresentMode::Fifo, pref_format).unwrap();
 //!     // ...
 //! }
 //! ```
@@ -43,11 +43,17 @@ pub mod sampler;
 pub mod color;
 pub mod error;
 pub mod frame;
+pub mod bundle;
 
-pub use pipeline::{Plumber, PipelineCore, PipelineDescription};
-pub use painter::{RenderPassExtention, Painter};
+pub use pipeline::{Plumber, PipelineCore, PipelineDescription, ComputePipeline};
+pub use painter::{RenderPassExtention, ComputePassExtention, Painter};
 pub use texture::Texture;
-pub use sampler::Sampler;
+pub use bundle::RenderBundle;
+pub use sampler::{Sampler, SamplerDesc, AddressMode};
 pub use buffers::*;
 pub use color::*;
 pub use device::Device;
+pub use transform::{WorldSpace, ScreenSpace, ObjectSpace};
+
+/// The version of parrot
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
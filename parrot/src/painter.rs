@@ -1,23 +1,26 @@
-use euclid::Size2D;
+use euclid::{Rect, Size2D};
 use wgpu::{TextureViewDescriptor, FilterMode, TextureFormat, RenderBundleEncoder};
-use std::ops::Range;
+use std::ops::{DerefMut, Range};
+use std::sync::{Arc, OnceLock};
 
 use crate::{
     device::Device,
-    vertex::VertexLayout,
+    vertex::{VertexLayout, INSTANCE_BUFFER_SLOT},
     error::ParrotError,
-    color::Rgba,
+    color::{Rgba, Rgba8},
     transform::ScreenSpace,
     texture::Texture,
     frame::Frame,
-    pipeline::{Blending, Plumber, Pipeline, PipelineLayout},
-    sampler::Sampler,
+    bundle::RenderBundle,
+    pipeline::{Blending, Plumber, Pipeline, PipelineConfig, PipelineLayout, PipelineDescription, ComputePipeline, DepthConfig, Set},
+    shader::ShaderFile,
+    sampler::{Sampler, SamplerDesc},
     binding::{BindingGroupLayout, Bind, BindingGroup},
     buffers::{
         vertex::VertexBuffer,
-        uniform::UniformBuffer,
-        index::IndexBuffer, DepthBuffer, FrameBuffer,
-    }, index::IndexBuffer32, 
+        uniform::{UniformBuffer, TypedUniform},
+        index::{IndexBuffer, IndexBuffer32, Index}, DepthBuffer, FrameBuffer, StorageBuffer,
+    },
 };
 
 /// The main interface for parrot. *Handles the rendering shenanigans so YOU don't have to*
@@ -42,38 +45,254 @@ pub struct Painter {
     pub(crate) sample_count: u32,
     /// The preferred texture format
     pref_format: wgpu::TextureFormat,
+    /// Texture formats the surface supports. Empty when headless
+    formats: Vec<wgpu::TextureFormat>,
+    /// Present modes the surface supports. Empty when headless
+    present_modes: Vec<wgpu::PresentMode>,
+    /// Cached depth buffer used by [`Painter::current_frame`]. Created lazily
+    depth: Option<Arc<DepthBuffer>>,
+    /// Cached 1x1 white texture. Created lazily by [`Painter::white_texture`]
+    white: OnceLock<Arc<Texture>>,
+    /// Cached linear sampler. Created lazily by [`Painter::default_sampler`]
+    sampler: OnceLock<Arc<Sampler>>,
+    /// Information about the adapter the device was created from
+    adapter_info: wgpu::AdapterInfo,
+    /// The adapter the device was created from. Used to check format features
+    adapter: wgpu::Adapter,
 }
 
 pub type PipelineFunction = fn (&Device, PipelineLayout, VertexLayout, wgpu::ShaderModule, wgpu::MultisampleState, Option<&str>) -> Pipeline;
 
 impl Painter {
-    /// Setup painter for a surface.
+    /// Setup painter for a surface. The device gets the adapter's limits, see [`Device::default_limits`]
     pub async fn for_surface(
         surface: wgpu::Surface,
         instance: &wgpu::Instance,
         sample_count: u32,
     ) -> Result<Self, ParrotError> {
-        log::info!("Creating for surface");
+        Self::create_for_surface(surface, instance, sample_count, wgpu::PowerPreference::default(), wgpu::Features::empty(), None).await
+    }
+
+    /// Setup painter for a surface, preferring an adapter with the given power preference.
+    /// Use [`wgpu::PowerPreference::HighPerformance`] to pick the discrete gpu on laptops with two gpus
+    pub async fn for_surface_with_power(
+        surface: wgpu::Surface,
+        instance: &wgpu::Instance,
+        sample_count: u32,
+        power: wgpu::PowerPreference,
+    ) -> Result<Self, ParrotError> {
+        Self::create_for_surface(surface, instance, sample_count, power, wgpu::Features::empty(), None).await
+    }
+
+    /// Setup painter for a surface, requesting the given features and limits from the device.
+    /// Returns [`ParrotError::UnsupportedFeatures`] or [`ParrotError::UnsupportedLimits`] if the adapter can't provide them.
+    pub async fn for_surface_with(
+        surface: wgpu::Surface,
+        instance: &wgpu::Instance,
+        sample_count: u32,
+        features: wgpu::Features,
+        limits: wgpu::Limits,
+    ) -> Result<Self, ParrotError> {
+        Self::create_for_surface(surface, instance, sample_count, wgpu::PowerPreference::default(), features, Some(limits)).await
+    }
+
+    /// Uses [`Device::default_limits`] if no limits are given
+    async fn create_for_surface(
+        surface: wgpu::Surface,
+        instance: &wgpu::Instance,
+        sample_count: u32,
+        power: wgpu::PowerPreference,
+        features: wgpu::Features,
+        limits: Option<wgpu::Limits>,
+    ) -> Result<Self, ParrotError> {
+        log::info!("Creating for surface >> Power preference: {:?}", power);
+        if sample_count == 0 {
+            return Err(ParrotError::InvalidSampleCount(sample_count));
+        }
         let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::default(),
+            power_preference: power,
             compatible_surface: Some(&surface),
             force_fallback_adapter: false,
         }).await.ok_or(ParrotError::NoAdaptersFound)?;
 
-        let preferred_format = surface.get_supported_formats(&adapter)[0];
+        let info = adapter.get_info();
+        log::info!("Selected adapter >> Backend: {:?} || Name: {} || Vendor: {} || Device type: {:?}", info.backend, info.name, info.vendor, info.device_type);
+
+        let limits = limits.unwrap_or_else(|| Device::default_limits(&adapter));
+        Self::check_adapter(&adapter, features, &limits)?;
+
+        let formats = surface.get_supported_formats(&adapter);
+        let present_modes = surface.get_supported_modes(&adapter);
+        log::info!("Surface capabilities >> Formats: {:?} || Present modes: {:?}", formats, present_modes);
+        // Prefer an sRGB format so colors are gamma corrected on output
+        let preferred_format = formats
+            .iter()
+            .copied()
+            .find(|f| f.describe().srgb)
+            .or_else(|| formats.first().copied())
+            .ok_or(ParrotError::NoSurfaceFormats)?;
+
+        let device = Device::for_surface_with(surface, &adapter, features, limits).await?;
+        Self::validate_sample_count(&adapter, &device, preferred_format, sample_count)?;
+
+        Ok(Self {
+            device,
+            sample_count,
+            pref_format: preferred_format,
+            formats,
+            present_modes,
+            depth: None,
+            white: OnceLock::new(),
+            sampler: OnceLock::new(),
+            adapter_info: info,
+            adapter,
+        })
+    }
+
+    /// Setup painter without a surface for offscreen rendering. Render to a [`FrameBuffer`] and read it back with [`Texture::read`].
+    /// The preferred format is [`wgpu::TextureFormat::Rgba8UnormSrgb`]. The device gets the adapter's limits, see [`Device::default_limits`]
+    pub async fn headless(
+        instance: &wgpu::Instance,
+        sample_count: u32,
+    ) -> Result<Self, ParrotError> {
+        Self::create_headless(instance, sample_count, wgpu::Features::empty(), None).await
+    }
+
+    /// Same as [`Painter::headless`] but requests the given features and limits from the device.
+    pub async fn headless_with(
+        instance: &wgpu::Instance,
+        sample_count: u32,
+        features: wgpu::Features,
+        limits: wgpu::Limits,
+    ) -> Result<Self, ParrotError> {
+        Self::create_headless(instance, sample_count, features, Some(limits)).await
+    }
+
+    /// Uses [`Device::default_limits`] if no limits are given
+    async fn create_headless(
+        instance: &wgpu::Instance,
+        sample_count: u32,
+        features: wgpu::Features,
+        limits: Option<wgpu::Limits>,
+    ) -> Result<Self, ParrotError> {
+        log::info!("Creating headless");
+        if sample_count == 0 {
+            return Err(ParrotError::InvalidSampleCount(sample_count));
+        }
+        let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        }).await.ok_or(ParrotError::NoAdaptersFound)?;
+
+        let info = adapter.get_info();
+        log::info!("Selected adapter >> Backend: {:?} || Name: {} || Vendor: {} || Device type: {:?}", info.backend, info.name, info.vendor, info.device_type);
+
+        let limits = limits.unwrap_or_else(|| Device::default_limits(&adapter));
+        Self::check_adapter(&adapter, features, &limits)?;
+
+        let device = Device::headless_with(&adapter, features, limits).await?;
+        Self::validate_sample_count(&adapter, &device, wgpu::TextureFormat::Rgba8UnormSrgb, sample_count)?;
 
         Ok(Self {
-            device: Device::for_surface(surface, &adapter).await?,
+            device,
             sample_count,
-            pref_format: preferred_format
+            pref_format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            formats: vec![],
+            present_modes: vec![],
+            depth: None,
+            white: OnceLock::new(),
+            sampler: OnceLock::new(),
+            adapter_info: info,
+            adapter,
         })
     }
 
-    /// Returns the preferred texture format of the surface
+    /// Returns the primitive state of a pipeline description. Falls back to [`crate::pipeline::PolygonMode::Fill`] if the device doesn't support the polygon mode
+    fn primitive_state(&self, desc: &PipelineDescription) -> wgpu::PrimitiveState {
+        let mut primitive = desc.primitive_state();
+        let feature = desc.polygon_mode.required_feature();
+        if !self.device.wgpu.features().contains(feature) {
+            log::error!("Polygon mode {:?} needs the {:?} feature, which the device doesn't have. Request it with Painter::for_surface_with. Falling back to fill", desc.polygon_mode, feature);
+            primitive.polygon_mode = wgpu::PolygonMode::Fill;
+        }
+        primitive
+    }
+
+    /// Returns the format the pipeline is created with followed by the formats of its extra targets
+    fn target_formats(format: TextureFormat, targets: &[TextureFormat]) -> Vec<TextureFormat> {
+        std::iter::once(format).chain(targets.iter().copied()).collect()
+    }
+
+    /// Checks the adapter supports the requested features and limits
+    fn check_adapter(adapter: &wgpu::Adapter, features: wgpu::Features, limits: &wgpu::Limits) -> Result<(), ParrotError> {
+        let missing = features - adapter.features();
+        if !missing.is_empty() {
+            return Err(ParrotError::UnsupportedFeatures(missing));
+        }
+        if !limits.check_limits(&adapter.limits()) {
+            return Err(ParrotError::UnsupportedLimits);
+        }
+        Ok(())
+    }
+
+    /// Returns the sample counts the format can be rendered with. Without [`wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`] only 1 and 4 are supported.
+    /// With it, formats the adapter can't multisample only support 1
+    fn sample_counts_for(adapter: &wgpu::Adapter, device: &Device, format: wgpu::TextureFormat) -> Vec<u32> {
+        if !device.wgpu.features().contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+            vec![1, 4]
+        } else if adapter.get_texture_format_features(format).flags.contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE) {
+            vec![1, 2, 4, 8, 16]
+        } else {
+            vec![1]
+        }
+    }
+
+    /// Checks the sample count is supported for the format. Returns [`ParrotError::UnsupportedSampleCount`] if it isn't
+    fn validate_sample_count(adapter: &wgpu::Adapter, device: &Device, format: wgpu::TextureFormat, sample_count: u32) -> Result<(), ParrotError> {
+        let supported = Self::sample_counts_for(adapter, device, format);
+        if supported.contains(&sample_count) {
+            Ok(())
+        } else {
+            Err(ParrotError::UnsupportedSampleCount { requested: sample_count, format, supported })
+        }
+    }
+
+    /// Returns the preferred texture format of the surface. This is the first sRGB format the surface supports, if there is one
     pub const fn preferred_format(&self) -> wgpu::TextureFormat {
         self.pref_format
     }
 
+    /// Returns the texture formats the surface supports. Empty for a headless painter
+    pub fn supported_formats(&self) -> Vec<wgpu::TextureFormat> {
+        self.formats.clone()
+    }
+
+    /// Returns the present modes the surface supports. [`wgpu::PresentMode::Fifo`] is always supported, check here before using
+    /// [`wgpu::PresentMode::Immediate`] or [`wgpu::PresentMode::Mailbox`]. Empty for a headless painter
+    pub fn supported_present_modes(&self) -> Vec<wgpu::PresentMode> {
+        self.present_modes.clone()
+    }
+
+    /// Returns the name, backend and device type of the adapter the painter is using
+    pub fn adapter_info(&self) -> wgpu::AdapterInfo {
+        self.adapter_info.clone()
+    }
+
+    /// Returns the sample counts that can be used when rendering to the given format
+    pub fn supported_sample_counts(&self, format: wgpu::TextureFormat) -> Vec<u32> {
+        Self::sample_counts_for(&self.adapter, &self.device, format)
+    }
+
+    /// Returns the highest sample count for the preferred format that is at most `requested`
+    pub fn clamp_sample_count(&self, requested: u32) -> u32 {
+        self.supported_sample_counts(self.pref_format)
+            .into_iter()
+            .filter(|c| *c <= requested)
+            .max()
+            .unwrap_or(1)
+    }
+
     /// Get the sample count
     pub const fn sample_count(&self) -> u32 {
         self.sample_count
@@ -85,10 +304,17 @@ impl Painter {
     }
 
     /// Updates the sample count. If you do this, you take responsibility for updating all the relevant structures such as the [`Pipeline`].
-    pub fn update_sample_count(&mut self, samples: u32) {
-        log::info!("Updating sample count >> Old: {} || New: {}", self.sample_count, samples);
+    /// Returns [`ParrotError::UnsupportedSampleCount`] if the preferred format doesn't support it, see [`Painter::clamp_sample_count`]
+    pub fn update_sample_count(&mut self, samples: u32) -> Result<(), ParrotError> {
+        log::info!("Updating sample count >> Old: {} || Requested: {}", self.sample_count, samples);
+        if let Err(e) = Self::validate_sample_count(&self.adapter, &self.device, self.pref_format, samples) {
+            log::error!("Sample count not applied >> Requested: {} || Kept: {} || Error: {}", samples, self.sample_count, e);
+            return Err(e);
+        }
         self.sample_count = samples;
-        log::warn!("Updated sample count. The pipelines and textures must be updated")
+        self.depth = None;
+        log::warn!("Updated sample count >> Applied: {}. The pipelines and textures must be updated", samples);
+        Ok(())
     }
 
     /// Configure the surface
@@ -97,32 +323,57 @@ impl Painter {
         size: Size2D<u32, ScreenSpace>,
         mode: T,
         format: wgpu::TextureFormat,
-    ) {
+    ) -> Result<(), ParrotError> {
         log::info!("Configuring for surface");
+        if size != self.device.size() {
+            self.depth = None;
+        }
         self.device.configure(size, mode, format)
     }
 
-    /// Get the current rendereable frame. This creates a depth buffer for itself. If you have a pipeline that doesn't support depth buffers use [`Painter::current_frame_no_depth()`]. Will present when dropped.
-    pub fn current_frame(&self) -> Result<RenderFrame, wgpu::SurfaceError> {
+    /// Reconfigure the surface with the last size, present mode and format it was configured with. Useful after the surface is lost.
+    /// Returns false if the surface was never configured
+    pub fn reconfigure(&mut self) -> bool {
+        self.device.reconfigure()
+    }
+
+    /// Recreates the cached depth buffer used by [`Painter::current_frame`] at the current size and sample count.
+    pub fn resize_depth(&mut self) {
+        log::info!("Resizing depth buffer >> Size: {:?} || Samples: {}", self.device.size(), self.sample_count);
+        self.depth = Some(Arc::new(self.device.create_depth_buffer(self.sample_count, Some("Current frame depth texture"))));
+    }
+
+    /// Get the current rendereable frame. This uses the painter's cached depth buffer, creating it if needed. If you have a pipeline that doesn't support depth buffers use [`Painter::current_frame_no_depth()`]. Will present when dropped.
+    /// If the surface times out, is lost or is outdated it's tried again once, see [`Painter::acquire_frame`]
+    pub fn current_frame(&mut self) -> Result<RenderFrame, ParrotError> {
+        self.render_frame(1)
+    }
+
+    /// Grabs the surface texture with the cached depth buffer
+    fn render_frame(&mut self, max_retries: u32) -> Result<RenderFrame, ParrotError> {
         log::info!("Getting current frame");
-        let surface = self.device.surface.as_ref().unwrap();
-        let surface_texture = surface.get_current_texture()?;
+        let surface_texture = self.surface_texture(max_retries)?;
+        let size = self.device.size();
+        let depth = match &self.depth {
+            Some(depth) if depth.texture.size == size => depth.clone(),
+            _ => {
+                self.resize_depth();
+                self.depth.clone().unwrap()
+            }
+        };
         let view = surface_texture.texture.create_view(&TextureViewDescriptor::default());
         Ok(RenderFrame {
             wgpu: Some(surface_texture),
             view,
             size: self.device.size(),
-            depth: Some(self
-                .device
-                .create_depth_buffer(self.sample_count, Some("Current frame depth texture")))
+            depth: Some(depth)
         })
     }
     
     /// Get the current renderable frame without creating a depth buffer.
-    pub fn current_frame_no_depth(&self) -> Result<RenderFrame, wgpu::SurfaceError> {
+    pub fn current_frame_no_depth(&mut self) -> Result<RenderFrame, ParrotError> {
         log::info!("Getting current frame");
-        let surface = self.device.surface.as_ref().unwrap();
-        let surface_texture = surface.get_current_texture()?;
+        let surface_texture = self.surface_texture(1)?;
         let view = surface_texture.texture.create_view(&TextureViewDescriptor::default());
         Ok(RenderFrame {
             wgpu: Some(surface_texture),
@@ -132,6 +383,27 @@ impl Painter {
         })
     }
 
+    /// Same as [`Painter::current_frame`] but retries up to `max_retries` times when the surface times out, is lost or is outdated,
+    /// reconfiguring it in the latter cases. Other errors, such as [`wgpu::SurfaceError::OutOfMemory`], are returned straight away.
+    /// Use this in a render loop instead of unwrapping [`Painter::current_frame`]
+    pub fn acquire_frame(&mut self, max_retries: u32) -> Result<RenderFrame, ParrotError> {
+        self.render_frame(max_retries)
+    }
+
+    /// Grab the current surface texture, retrying up to `max_retries` times. A lost or outdated surface is reconfigured with the last known configuration before trying again.
+    fn surface_texture(&mut self, max_retries: u32) -> Result<wgpu::SurfaceTexture, ParrotError> {
+        let device = &mut self.device;
+        if device.surface.is_none() {
+            return Err(ParrotError::NoSurface);
+        }
+        Ok(retry_surface(max_retries, |last_error| {
+            if let Some(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) = last_error {
+                device.reconfigure();
+            }
+            device.surface.as_ref().expect("Surface checked above").get_current_texture()
+        })?)
+    }
+
     /// Create a texture
     pub fn texture(
         &self,
@@ -145,11 +417,80 @@ impl Painter {
         self.device.create_texture(size, format, usage, name, sample_count)
     }
 
+    /// Decode an encoded image (png or jpeg) into a [`wgpu::TextureFormat::Rgba8UnormSrgb`] texture the size of the image.
+    /// [`wgpu::TextureUsages::COPY_DST`] is always added to `usage` so the pixels can be uploaded
+    #[cfg(feature = "image")]
+    pub fn texture_from_image_bytes(&self, bytes: &[u8], usage: wgpu::TextureUsages, name: Option<&str>) -> Result<Texture, ParrotError> {
+        log::info!("Loading texture from image >> Name: {:?} || Bytes: {}", name, bytes.len());
+        let img = image::load_from_memory(bytes)
+            .map_err(|e| ParrotError::ImageDecode(e.to_string()))?
+            .to_rgba8();
+        let size = Size2D::new(img.width(), img.height());
+        let texture = self.device.create_texture(size, wgpu::TextureFormat::Rgba8UnormSrgb, usage | wgpu::TextureUsages::COPY_DST, name, 1);
+        // Image rows already start at the top, the same as textures, so no flipping is needed
+        Texture::fill(&texture, Rgba8::align(img.as_raw()), &self.device);
+        Ok(texture)
+    }
+
+    /// Create a 2D texture array. See [`Device::create_texture_array`]
+    pub fn texture_array(
+        &self,
+        size: Size2D<u32, ScreenSpace>,
+        layers: u32,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+        name: Option<&str>,
+    ) -> Texture {
+        self.device.create_texture_array(size, layers, format, usage, name)
+    }
+
+    /// Create a 1x1 [`wgpu::TextureFormat::Rgba8UnormSrgb`] texture filled with a single color. Useful for drawing
+    /// solid shapes with a textured pipeline
+    pub fn solid_texture(&self, color: Rgba8) -> Texture {
+        log::info!("Creating solid texture >> Color: {:?}", color);
+        let texture = self.device.create_texture(
+            Size2D::new(1, 1),
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+            Some("Solid texture"),
+            1,
+        );
+        Texture::fill(&texture, &[color], &self.device);
+        texture
+    }
+
+    /// Returns a shared 1x1 white texture, creating it the first time it's needed. See [`Painter::solid_texture`]
+    pub fn white_texture(&self) -> Arc<Texture> {
+        self.white.get_or_init(|| Arc::new(self.solid_texture(Rgba8::WHITE))).clone()
+    }
+
+    /// Returns a shared linear sampler, see [`Sampler::linear`]. It's created the first time this is called and reused after,
+    /// so simple apps don't need to create one per texture
+    pub fn default_sampler(&self) -> Arc<Sampler> {
+        self.sampler.get_or_init(|| Arc::new(Sampler::linear(&self.device))).clone()
+    }
+
+    /// Create a texture with a full mip chain. See [`Texture::generate_mipmaps`]
+    pub fn texture_mipmapped(
+        &self,
+        size: Size2D<u32, ScreenSpace>,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+        name: Option<&str>,
+    ) -> Texture {
+        self.device.create_texture_mipmapped(size, format, usage, name)
+    }
+
     /// Create a depth buffer
     pub fn depth_buffer(&self, name: Option<&str>) -> DepthBuffer {
         self.device.create_depth_buffer(self.sample_count, name)
     }
 
+    /// Create a depth buffer with the given depth format. Pipelines drawing with it need a matching [`crate::pipeline::DepthConfig::format`]
+    pub fn depth_buffer_with_format(&self, format: wgpu::TextureFormat, name: Option<&str>) -> DepthBuffer {
+        self.device.create_depth_buffer_with_format(self.sample_count, format, name)
+    }
+
     /// Create a vertex buffer
     pub fn vertex_buffer<T: bytemuck::Pod + Copy + 'static>(&self, verts: &[T], name: Option<&str>) -> VertexBuffer {
         self.device.create_vertex_buffer(verts, name)
@@ -165,13 +506,33 @@ impl Painter {
         self.device.create_index_buffer_32(indicies, name)
     }
 
+    /// Create an index buffer holding any [`Index`] type. Useful for code that works with both 16 and 32 bit indicies
+    pub fn typed_index_buffer<I: Index>(&self, indicies: &[I], name: Option<&str>) -> IndexBuffer<I> {
+        self.device.create_typed_index_buffer(indicies, name)
+    }
+
     /// Create a uniform buffer
     pub fn uniform_buffer<T: bytemuck::Pod + Copy + 'static>(&self, buf: &[T], name: Option<&str>) -> UniformBuffer {
         self.device.create_uniform_buffer(buf, name)
     }
 
+    /// Create a uniform buffer where each element can be selected with a dynamic offset
+    pub fn dynamic_uniform_buffer<T: bytemuck::Pod + Copy + 'static>(&self, buf: &[T], name: Option<&str>) -> UniformBuffer {
+        self.device.create_dynamic_uniform_buffer(buf, name)
+    }
+
+    /// Create a uniform buffer that can only be updated with `T`. See [`Painter::update_typed_uniform`]
+    pub fn typed_uniform_buffer<T: bytemuck::Pod + Copy + 'static>(&self, buf: &[T], name: Option<&str>) -> TypedUniform<T> {
+        TypedUniform::new(self.device.create_uniform_buffer(buf, name))
+    }
+
+    /// Create a storage buffer
+    pub fn storage_buffer<T: bytemuck::Pod + Copy + 'static>(&self, buf: &[T], name: Option<&str>) -> StorageBuffer {
+        self.device.create_storage_buffer(buf, name)
+    }
+
     /// Create a binding group
-    pub fn binding_group(&self, layout: &BindingGroupLayout, binds: &[&dyn Bind], name: Option<&str>) -> BindingGroup {
+    pub fn binding_group(&self, layout: &BindingGroupLayout, binds: &[&dyn Bind], name: Option<&str>) -> Result<BindingGroup, ParrotError> {
         self.device.create_binding_group(layout, binds, name)
     }
 
@@ -180,54 +541,96 @@ impl Painter {
         self.device.create_sampler(min_filter, mag_filter, name)
     }
 
+    /// Create a sampler from a [`SamplerDesc`]
+    pub fn sampler_with_desc(&self, desc: &SamplerDesc, name: Option<&str>) -> Sampler {
+        self.device.create_sampler_with_desc(desc, name)
+    }
+
+    /// Create a shader. Wgsl shaders are validated first so a broken shader returns [`ParrotError::ShaderCompile`] instead of panicking.
+    /// Use [`ShaderFile::validate`] to check a shader before creating a pipeline with it, as pipeline creation panics on invalid shaders
+    pub fn shader(&self, source: ShaderFile, name: Option<&str>) -> Result<crate::shader::Shader, ParrotError> {
+        self.device.create_shader(source, name)
+    }
+
     /// Create a pipeline. Has a depth texture by default.
     pub fn pipeline<T: Plumber<'static>>(&self, blending: Blending, format: TextureFormat, shader_name: Option<&str>) -> T {
         log::info!("Creating pipeline");
         let desc = T::description();
-        let pipe_layout = self.device.create_pipeline_layout(desc.pipeline_layout);
-        let vertex_layout = VertexLayout::from(desc.vertex_layout);
-        let shader = self.device.create_shader(desc.shader, shader_name);
-        let name = desc.name;
+        let depth = desc.depth;
+        self.create_plumber(desc, blending, format, Some(depth), shader_name)
+    }
 
-        T::setup(self.device.create_pipeline(
+    /// Rebuild the shader of a pipeline created with [`Painter::pipeline`] or [`Painter::pipeline_no_depth`] from wgsl source code, keeping the same layout and bindings.
+    /// The source is validated first, so a bad edit returns [`ParrotError::ShaderCompile`] and leaves the pipeline untouched
+    pub fn reload_pipeline_shader<T>(&self, pipe: &mut T, new_source: &str, blending: Blending, format: TextureFormat) -> Result<(), ParrotError>
+    where
+        T: Plumber<'static> + DerefMut,
+    {
+        let desc = T::description();
+        log::info!("Reloading pipeline shader >> Name: {:?}", desc.name);
+        let shader = self.device.create_shader(ShaderFile::WgslOwned(new_source.to_string()), desc.name)?;
+        let formats = Self::target_formats(format, desc.targets);
+        let core = pipe.deref_mut();
+        // Rebuild with or without a depth buffer to match the old pipeline
+        let config = self.pipeline_config(&desc, blending, core.pipeline.depth, &formats);
+        // Reuse the old layout so the existing bind groups stay valid
+        let pipe_layout = PipelineLayout {
+            b_layouts: std::mem::take(&mut core.pipeline.layout.b_layouts),
+            push_constants: std::mem::take(&mut core.pipeline.layout.push_constants),
+        };
+
+        core.pipeline = self.device.create_pipeline(
             pipe_layout,
-            vertex_layout,
-            blending,
+            core.pipeline.vertex_layout.clone(),
+            core.pipeline.instance_layout.clone(),
             shader,
-            format,
-            wgpu::MultisampleState {
-                count: self.sample_count,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            name
-        ),
-        &self)
+            &config,
+        );
+        Ok(())
     }
 
     /// Create a pipeline without a depth texture
     pub fn pipeline_no_depth<T: Plumber<'static>>(&self, blending: Blending, format: TextureFormat, shader_name: Option<&str>) -> T {
         log::info!("Creating pipeline with no depth buffer");
-        let desc = T::description();
-        let pipe_layout = self.device.create_pipeline_layout(desc.pipeline_layout);
+        self.create_plumber(T::description(), blending, format, None, shader_name)
+    }
+
+    /// Creates the layouts and shader of a description and sets up the pipeline with them
+    fn create_plumber<T: Plumber<'static>>(&self, desc: PipelineDescription, blending: Blending, format: TextureFormat, depth: Option<DepthConfig>, shader_name: Option<&str>) -> T {
+        let pipe_layout = self.device.create_pipeline_layout(desc.pipeline_layout, desc.push_constants);
         let vertex_layout = VertexLayout::from(desc.vertex_layout);
-        let shader = self.device.create_shader(desc.shader, shader_name);
-        let name = desc.name;
+        let instance_layout = desc.instance_layout.map(|i| VertexLayout::instance(i, desc.vertex_layout.len() as u32));
+        let formats = Self::target_formats(format, desc.targets);
+        let config = self.pipeline_config(&desc, blending, depth, &formats);
+        let shader = self.device.create_shader(desc.shader, shader_name).unwrap_or_else(|e| panic!("{}", e));
 
-        T::setup(self.device.create_pipeline_no_depth(
+        T::setup(self.device.create_pipeline(
             pipe_layout,
             vertex_layout,
-            blending,
+            instance_layout,
             shader,
-            format,
-            wgpu::MultisampleState {
+            &config,
+        ),
+        self)
+    }
+
+    /// Returns the [`PipelineConfig`] of a description, drawing to the given formats
+    fn pipeline_config<'a>(&self, desc: &PipelineDescription<'a>, blending: Blending, depth: Option<DepthConfig>, formats: &'a [TextureFormat]) -> PipelineConfig<'a> {
+        PipelineConfig {
+            blending,
+            write_mask: desc.write_mask,
+            primitive: self.primitive_state(desc),
+            depth,
+            vertex_entry: desc.vertex_entry,
+            fragment_entry: desc.fragment_entry,
+            formats,
+            multisample: wgpu::MultisampleState {
                 count: self.sample_count,
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
-            name
-        ),
-        &self)
+            name: desc.name,
+        }
     }
 
     /// Create a pipeline, However your have the responsibility of providing the [`Pipeline`].
@@ -237,9 +640,9 @@ impl Painter {
     {
         log::info!("Creating pipeline");
         let desc = T::description();
-        let pipe_layout = self.device.create_pipeline_layout(desc.pipeline_layout);
+        let pipe_layout = self.device.create_pipeline_layout(desc.pipeline_layout, desc.push_constants);
         let vertex_layout = VertexLayout::from(desc.vertex_layout);
-        let shader = self.device.create_shader(desc.shader, shader_name).wgpu;
+        let shader = self.device.create_shader(desc.shader, shader_name).unwrap_or_else(|e| panic!("{}", e)).wgpu;
         let name = desc.name;
 
         let mut b_layouts = Vec::new();
@@ -262,34 +665,88 @@ impl Painter {
         &self)
     }
 
-    /// Update the pipeline
-    pub fn update_pipeline<'a, T: Plumber<'a>>(&mut self, pipe: &'a mut T, prep: T::PrepareContext) {
-        for (buffer, uniforms) in pipe.prepare(prep, self) {
-            log::info!("Updating pipeline -------");
-            if let Some(b) = self.update_buffer::<T::Uniforms>(uniforms.as_slice(), buffer) {
-                *buffer = b;
-            }
+    /// Create a compute pipeline. The entry point defaults to `main` if none is given.
+    pub fn compute_pipeline(&self, sets: Option<&[Set<'_>]>, shader: ShaderFile, entry_point: Option<&str>, name: Option<&str>) -> ComputePipeline {
+        let pipe_layout = self.device.create_pipeline_layout(sets, &[]);
+        let shader = self.device.create_shader(shader, name).unwrap_or_else(|e| panic!("{}", e));
+        self.device.create_compute_pipeline(pipe_layout, shader, entry_point.unwrap_or("main"), name)
+    }
+
+    /// Update the pipeline's uniforms. Every buffer's new size is checked first, then the buffers that fit are all written
+    /// and the ones that are too small are recreated with their new data. The writes are sent with the next submission.
+    ///
+    /// Returns the recreated buffers along with their positions, in the order [`Plumber::prepare`] returned them. The pipeline
+    /// still holds the old buffers, so the caller must swap these in and recreate any binding groups holding them
+    #[must_use = "recreated uniform buffers must be swapped into the pipeline"]
+    pub fn update_pipeline<'a, T: Plumber<'a>>(&mut self, pipe: &'a mut T, prep: T::PrepareContext) -> Vec<(usize, UniformBuffer)> {
+        log::info!("Updating pipeline -------");
+        let (fits, grown): (Vec<_>, Vec<_>) = pipe
+            .prepare(prep, self)
+            .into_iter()
+            .enumerate()
+            .partition(|(_, (buffer, uniforms))| Self::uniform_len(uniforms, buffer) <= buffer.byte_size());
+
+        for (_, (buffer, uniforms)) in fits {
+            self.device.update_buffer(uniforms.as_slice(), buffer);
+        }
+        let reallocated: Vec<(usize, UniformBuffer)> = grown
+            .into_iter()
+            .map(|(i, (buffer, uniforms))| (i, self.recreate_uniform(uniforms.as_slice(), buffer)))
+            .collect();
+        if !reallocated.is_empty() {
+            let positions: Vec<usize> = reallocated.iter().map(|(i, _)| *i).collect();
+            log::warn!("Uniform buffers were recreated >> Positions: {:?}. Their binding groups must be recreated", positions);
         }
+        reallocated
     }
 
     /// Update a uniform buffer
     pub fn update_buffer<T: bytemuck::Pod + Copy + 'static>(&mut self, data: &[T], buffer: &mut UniformBuffer) -> Option<UniformBuffer> {
-        let bytes: &[u8] = bytemuck::cast_slice(data);
+        let len = Self::uniform_len(data, buffer);
         // Check if the uniform buffer is too big
-        if bytes.len() <= buffer.size * buffer.count {
-            log::info!("Updating uniform buffer >> Current max: {} || Updated size: {}",buffer.size * buffer.count, bytes.len());
+        if len <= buffer.byte_size() {
+            log::info!("Updating uniform buffer >> Current max: {} || Updated size: {}", buffer.byte_size(), len);
             self.device.update_buffer(data, buffer);
             None
         } else {
-            log::info!("Creating new uniform buffer >> Current max: {} || Updated size: {}",buffer.size * buffer.count, bytes.len());
-            if let Some(name) = buffer.name.clone() {
-                Some(self.uniform_buffer(data, Some(name.as_str())))
-            } else {
-                Some(self.uniform_buffer(data, None))
-            }
+            Some(self.recreate_uniform(data, buffer))
         }
     }
 
+    /// The amount of bytes `data` takes up in `buffer`
+    fn uniform_len<T>(data: &[T], buffer: &UniformBuffer) -> usize {
+        if buffer.dynamic {
+            UniformBuffer::aligned_stride(std::mem::size_of::<T>()) * data.len()
+        } else {
+            std::mem::size_of_val(data)
+        }
+    }
+
+    /// Create a uniform buffer like `buffer` holding `data`
+    fn recreate_uniform<T: bytemuck::Pod + Copy + 'static>(&self, data: &[T], buffer: &UniformBuffer) -> UniformBuffer {
+        log::info!("Creating new uniform buffer >> Current max: {} || Updated size: {}", buffer.byte_size(), Self::uniform_len(data, buffer));
+        let name = buffer.name.as_deref();
+        if buffer.dynamic {
+            self.dynamic_uniform_buffer(data, name)
+        } else {
+            self.uniform_buffer(data, name)
+        }
+    }
+
+    /// Update a typed uniform buffer. Returns [`ParrotError::UniformTypeMismatch`] if `U` isn't the type the buffer was created with.
+    /// The buffer is recreated if the data doesn't fit
+    pub fn update_typed_uniform<T, U>(&mut self, data: &[U], uniform: &mut TypedUniform<T>) -> Result<(), ParrotError>
+    where
+        T: bytemuck::Pod + Copy + 'static,
+        U: bytemuck::Pod + Copy + 'static,
+    {
+        uniform.check::<U>()?;
+        if let Some(b) = self.update_buffer(data, &mut uniform.buffer) {
+            uniform.buffer = b;
+        }
+        Ok(())
+    }
+
     /// Updates the vertex buffer or, if too big, creates a new one big enough to fit the data
     pub fn update_vertex_buffer<T: bytemuck::Pod + Copy + 'static>(&mut self, vertices: &[T], buffer: &mut VertexBuffer) -> Option<VertexBuffer> {
         let bytes: &[u8] = bytemuck::cast_slice(vertices);
@@ -308,38 +765,48 @@ impl Painter {
         }
     }
     
-    /// Updates an index buffer 32 or, if too big, creates a new one big enough to fit the new data
-    pub fn update_index_buffer_32(&mut self, indicies:Vec<u32>, buffer: &mut IndexBuffer32) -> Option<IndexBuffer32> {
+    /// Updates part of a vertex buffer in place, starting `offset` bytes in. Unlike [`Painter::update_vertex_buffer`] the buffer is never
+    /// recreated, so an error is returned if the data doesn't fit. See [`Device::update_vertex_buffer_at`]
+    pub fn update_vertex_buffer_at<T: bytemuck::Pod + Copy + 'static>(&mut self, offset: u64, vertices: &[T], buffer: &mut VertexBuffer) -> Result<(), ParrotError> {
+        log::info!("Updating vertex buffer range >> Offset: {} || Bytes: {} || Size: {}", offset, std::mem::size_of_val(vertices), buffer.size);
+        self.device.update_vertex_buffer_at(offset, vertices, buffer)
+    }
+
+    /// Updates an index buffer holding any [`Index`] type or, if too big, creates a new one big enough to fit the new data
+    pub fn update_typed_index_buffer<I: Index>(&mut self, indicies: Vec<I>, buffer: &mut IndexBuffer<I>) -> Option<IndexBuffer<I>> {
         // Check if the index buffer is big enough to fit the indicies
         if indicies.len() <= buffer.size as usize {
-            log::info!("Updating index buffer 32 >> Current size: {} || Updated size: {}", buffer.size, indicies.len());
-            self.device.update_index_buffer_32(indicies, buffer);
+            log::info!("Updating index buffer >> Format: {:?} || Current size: {} || Updated size: {}", I::FORMAT, buffer.size, indicies.len());
+            self.device.update_typed_index_buffer(indicies, buffer);
             None
         } else {
-            log::info!("Creating new index buffer >> Current size: {} || Updated size: {}", buffer.size, indicies.len());
+            log::info!("Creating new index buffer >> Format: {:?} || Current size: {} || Updated size: {}", I::FORMAT, buffer.size, indicies.len());
             if let Some(name) = buffer.name.clone() {
-                Some(self.index_buffer_32(indicies.as_slice(), Some(name.as_str())))
+                Some(self.typed_index_buffer(indicies.as_slice(), Some(name.as_str())))
             } else {
-                Some(self.index_buffer_32(indicies.as_slice(), None))
+                Some(self.typed_index_buffer(indicies.as_slice(), None))
             }
         }
     }
 
+    /// Updates an index buffer 32 or, if too big, creates a new one big enough to fit the new data
+    pub fn update_index_buffer_32(&mut self, indicies: Vec<u32>, buffer: &mut IndexBuffer32) -> Option<IndexBuffer32> {
+        self.update_typed_index_buffer(indicies, buffer)
+    }
+
     /// Updates an index buffer or, if too big, creates a new one big enough to fit the new data
     pub fn update_index_buffer(&mut self, indicies: Vec<u16>, buffer: &mut IndexBuffer) -> Option<IndexBuffer> {
-        // Check if the index buffer is big enough to fit the indicies
-        if indicies.len() <= buffer.size as usize {
-            log::info!("Updating index buffer >> Current size: {} || Updated size: {}", buffer.size, indicies.len());
-            self.device.update_index_buffer(indicies, buffer);
-            None
-        } else {
-            log::info!("Creating new index buffer >> Current size: {} || Updated size: {}", buffer.size, indicies.len());
-            if let Some(name) = buffer.name.clone() {
-                Some(self.index_buffer(indicies.as_slice(), Some(name.as_str())))
-            } else {
-                Some(self.index_buffer(indicies.as_slice(), None))
-            }
-        }
+        self.update_typed_index_buffer(indicies, buffer)
+    }
+
+    /// Write raw bytes to a buffer. Useful for buffers that aren't one of parrot's buffer types.
+    pub fn write_buffer_raw(&self, buffer: &wgpu::Buffer, offset: u64, data: &[u8]) {
+        self.device.queue.write_buffer(buffer, offset, data);
+    }
+
+    /// Write raw bytes to a texture. See [`wgpu::Queue::write_texture`]
+    pub fn write_texture_raw(&self, destination: wgpu::ImageCopyTexture, data: &[u8], layout: wgpu::ImageDataLayout, size: wgpu::Extent3d) {
+        self.device.queue.write_texture(destination, data, layout, size);
     }
 
     /// Creates a [`FrameBuffer`] with a depth texture
@@ -352,6 +819,12 @@ impl Painter {
         self.device.create_frame_buffer(size, format, self.sample_count, name, false)
     }
 
+    /// Creates a [`FrameBuffer`] with a depth texture and the [`FrameBuffer::HDR_FORMAT`] format, for accumulating light or bloom
+    /// without clamping. Pipelines drawing to it must be created with the same format. Read it back with [`Texture::read_rgba16f`]
+    pub fn create_hdr_frame_buffer(&self, size: Size2D<u32, ScreenSpace>, name: Option<&str>) -> FrameBuffer {
+        self.device.create_frame_buffer(size, FrameBuffer::HDR_FORMAT, self.sample_count, name, true)
+    }
+
     /// Get a frame
     pub fn frame(&mut self) -> Frame {
         log::info!("Created frame");
@@ -362,12 +835,93 @@ impl Painter {
     /// Present a frame
     pub fn present(&mut self, frame: Frame) {
         log::info!("Submitting frame commands");
+        log::debug!("Render passes this frame >> {}", frame.pass_count);
         self.device.submit(vec![frame.encoder.finish()]);
     }
 
+    /// Present a frame and call `callback` once the gpu has finished it. The callback runs while the device is polled,
+    /// which happens when wgpu maps buffers or presents, or manually with [`Device::poll`]
+    pub fn present_with_callback<F: FnOnce() + Send + 'static>(&mut self, frame: Frame, callback: F) {
+        self.present(frame);
+        self.device.queue.on_submitted_work_done(callback);
+    }
+
+    /// Present a frame and block until the gpu has finished it. Useful for readback and tests.
+    ///
+    /// This stalls the cpu for the whole frame, so don't use it in a real-time render loop
+    pub fn submit_and_wait(&mut self, frame: Frame) {
+        self.present(frame);
+        log::info!("Waiting for gpu");
+        self.device.poll(wgpu::Maintain::Wait);
+    }
+
+    /// Render into a [`FrameBuffer`]. A pass is started on `frame` that clears or loads the frame buffer with `op`,
+    /// then the closure records its draws into it. Submit the frame with [`Painter::present`] afterwards.
+    ///
+    /// The frame buffer has the format it was created with, so any pipeline drawing to it must be created with the same format.
+    pub fn render_to<'a, F: FnOnce(&mut wgpu::RenderPass<'a>)>(&self, frame: &'a mut Frame, fb: &'a FrameBuffer, op: PassOp, f: F) {
+        log::info!("Rendering to frame buffer >> Size: {:?} || Op: {:?}", fb.texture.size, op);
+        let mut pass = frame.pass(op, fb, None);
+        f(&mut pass);
+    }
+
+    /// Clear the surface to `color` and present it. Useful for loading and pause screens where nothing else is drawn.
+    /// Use [`Painter::render_to`] to clear a frame buffer instead
+    pub fn clear(&mut self, color: Rgba) -> Result<(), ParrotError> {
+        log::info!("Clearing surface >> Color: {:?}", color);
+        let current = self.current_frame_no_depth()?;
+        let mut frame = self.frame();
+        frame.pass(PassOp::Clear(color), &current, None);
+        self.present(frame);
+        Ok(())
+    }
+
     /// Create a [`wgpu::RenderBundleEncoder`] for creating render bundles
     pub fn create_render_bundle(&self, name: Option<&str>, format: wgpu::TextureFormat) -> wgpu::RenderBundleEncoder {
-        self.device.create_render_bundle_encoder(format, name, self.sample_count)
+        self.device.create_render_bundle_encoder(format, Some(DepthBuffer::FORMAT), name, self.sample_count)
+    }
+
+    /// Record a [`RenderBundle`] for passes with the given format and depth format (none for passes without a depth buffer).
+    /// The closure records the draws, which can then be replayed every frame with [`RenderPassExtention::execute_parrot_bundle`]
+    pub fn record_render_bundle<'a, F>(&'a self, format: wgpu::TextureFormat, depth_format: Option<wgpu::TextureFormat>, name: Option<&str>, f: F) -> RenderBundle
+    where
+        F: FnOnce(&mut RenderBundleEncoder<'a>),
+    {
+        let mut encoder = self.device.create_render_bundle_encoder(format, depth_format, name, self.sample_count);
+        f(&mut encoder);
+        RenderBundle {
+            wgpu: encoder.finish(&wgpu::RenderBundleDescriptor { label: name }),
+            format,
+            depth_format,
+            sample_count: self.sample_count,
+        }
+    }
+
+    /// Same as [`Painter::create_render_bundle`] but for passes with **no** depth buffer, like [`Painter::current_frame_no_depth`]
+    pub fn create_render_bundle_no_depth(&self, name: Option<&str>, format: wgpu::TextureFormat) -> wgpu::RenderBundleEncoder<'_> {
+        self.device.create_render_bundle_encoder(format, None, name, self.sample_count)
+    }
+
+    /// Same as [`Painter::create_render_bundle`] but for passes with the given depth format, or no depth buffer
+    pub fn create_render_bundle_with_depth(&self, name: Option<&str>, format: wgpu::TextureFormat, depth_format: Option<wgpu::TextureFormat>) -> wgpu::RenderBundleEncoder<'_> {
+        self.device.create_render_bundle_encoder(format, depth_format, name, self.sample_count)
+    }
+}
+
+/// Calls `acquire` until it succeeds or fails with something other than a timeout, lost or outdated surface, retrying at most
+/// `max_retries` times. `acquire` is given the error from the previous attempt so it can recover first
+fn retry_surface<T>(max_retries: u32, mut acquire: impl FnMut(Option<wgpu::SurfaceError>) -> Result<T, wgpu::SurfaceError>) -> Result<T, wgpu::SurfaceError> {
+    let mut last_error = None;
+    let mut attempts = 0;
+    loop {
+        match acquire(last_error.take()) {
+            Err(e @ (wgpu::SurfaceError::Timeout | wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated)) if attempts < max_retries => {
+                attempts += 1;
+                log::warn!("Failed to acquire surface texture, retrying >> Error: {:?} || Attempt: {}/{}", e, attempts, max_retries);
+                last_error = Some(e);
+            }
+            result => return result,
+        }
     }
 }
 
@@ -384,7 +938,7 @@ pub struct RenderFrame {
     pub view: wgpu::TextureView,
     pub wgpu: Option<wgpu::SurfaceTexture>,
     pub size: Size2D<u32, ScreenSpace>,
-    pub depth: Option<DepthBuffer>,
+    pub depth: Option<Arc<DepthBuffer>>,
 
 }
 
@@ -412,9 +966,14 @@ impl Drop for RenderFrame {
 }
 
 /// Wrapper around [`wgpu::LoadOp`]. Instructs wgpu to either clear the screen with a color, or load from memory
+///
+/// [`PassOp::Clear`] takes a linear color. On a `*Srgb` target it's encoded when written, so a color picked in sRGB space
+/// (like from an image editor) looks too bright. Use [`PassOp::ClearSrgb`] for those so the stored value matches the authored one
 #[derive(Debug)]
 pub enum PassOp {
     Clear(Rgba),
+    /// Clear with a color in sRGB space. It's converted to linear before clearing
+    ClearSrgb(Rgba),
     Load(),
 }
 
@@ -422,6 +981,7 @@ impl PassOp {
     fn to_wgpu(&self) -> wgpu::LoadOp<wgpu::Color> {
         match self {
             PassOp::Clear(color) => wgpu::LoadOp::Clear((*color).into()),
+            PassOp::ClearSrgb(color) => wgpu::LoadOp::Clear(color.to_linear().into()),
             PassOp::Load() => wgpu::LoadOp::Load
         }
     }
@@ -433,6 +993,54 @@ impl From<PassOp> for wgpu::LoadOp<wgpu::Color> {
     }
 }
 
+/// Instructs wgpu to either clear the depth buffer to a value, or load it from memory. Loading keeps the depth from earlier passes.
+/// Defaults to clearing to 1.0
+///
+/// [`DepthOp::Clear`] and [`DepthOp::Load`] leave the stencil alone, which is all a depth only format like [`DepthBuffer::FORMAT`] supports.
+/// Use the stencil variants with a depth buffer that has a stencil aspect
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DepthOp {
+    Clear(f32),
+    Load,
+    /// Clear the depth and the stencil
+    ClearWithStencil(f32, u32),
+    /// Load the depth and the stencil
+    LoadWithStencil,
+}
+
+impl Default for DepthOp {
+    fn default() -> Self {
+        DepthOp::Clear(1.0)
+    }
+}
+
+impl DepthOp {
+    fn to_wgpu(self) -> wgpu::LoadOp<f32> {
+        match self {
+            DepthOp::Clear(depth) | DepthOp::ClearWithStencil(depth, _) => wgpu::LoadOp::Clear(depth),
+            DepthOp::Load | DepthOp::LoadWithStencil => wgpu::LoadOp::Load,
+        }
+    }
+
+    fn stencil(self) -> Option<wgpu::Operations<u32>> {
+        let load = match self {
+            DepthOp::Clear(_) | DepthOp::Load => return None,
+            DepthOp::ClearWithStencil(_, stencil) => wgpu::LoadOp::Clear(stencil),
+            DepthOp::LoadWithStencil => wgpu::LoadOp::Load,
+        };
+        Some(wgpu::Operations {
+            load,
+            store: true,
+        })
+    }
+}
+
+impl From<DepthOp> for wgpu::LoadOp<f32> {
+    fn from(op: DepthOp) -> Self {
+        op.to_wgpu()
+    }
+}
+
 /// An extention on [`wgpu::RenderPass`] allowing it to perform actions on parrot's types
 pub trait RenderPassExtention<'a> {
     fn begin(
@@ -443,20 +1051,64 @@ pub trait RenderPassExtention<'a> {
         op: PassOp
     ) -> Self;
 
+    /// Same as [`RenderPassExtention::begin`] but allows you to specify whether the color attachment is stored and how the depth buffer is loaded.
+    /// When resolving a multisampled texture, `store` can be false to discard the multisampled texture after the resolve.
+    fn begin_with_ops(
+        encoder: &'a mut wgpu::CommandEncoder,
+        view: &'a wgpu::TextureView,
+        resolve_target: Option<&'a wgpu::TextureView>,
+        depth: Option<&'a wgpu::TextureView>,
+        op: PassOp,
+        depth_op: DepthOp,
+        store: bool,
+    ) -> Self;
+
+    /// Begin a pass that renders to every view in `views` at once. Views are bound to the color locations in the order they're given,
+    /// so the pipeline needs a matching target for each, see [`PipelineDescription::targets`]. Multisample resolving isn't supported
+    fn begin_multi(
+        encoder: &'a mut wgpu::CommandEncoder,
+        views: &[&'a wgpu::TextureView],
+        depth: Option<&'a wgpu::TextureView>,
+        op: PassOp,
+        depth_op: DepthOp,
+    ) -> Self;
+
     fn set_parrot_pipeline<'b, T: Plumber<'b>>(&mut self, pipeline: &'a T);
 
     fn set_binding(&mut self, group: &'a BindingGroup, offsets: &[u32]);
 
-    fn set_parrot_index_buffer(&mut self, index_buf: &'a IndexBuffer);
+    /// Set the index buffer. Works with both 16 and 32 bit index buffers
+    fn set_parrot_index_buffer<I: Index>(&mut self, index_buf: &'a IndexBuffer<I>);
     fn set_parrot_vertex_buffer(&mut self, vertex_buf: &'a VertexBuffer);
+    fn set_parrot_instance_buffer(&mut self, instance_buf: &'a VertexBuffer);
+    /// Same as `set_parrot_index_buffer`. Kept for older code
     fn set_parrot_index_buffer_32(&mut self, index_buf: &'a IndexBuffer32);
+    /// Set push constants for the given stages. The pipeline must have a matching [`crate::pipeline::PushConstantRange`]
+    fn set_parrot_push_constants<T: bytemuck::Pod>(&mut self, stages: wgpu::ShaderStages, offset: u32, data: &[T]);
+    /// Only draw inside `rect`. The rect is clamped to `target`, the size of the texture being rendered to
+    fn set_parrot_scissor(&mut self, rect: Rect<u32, ScreenSpace>, target: Size2D<u32, ScreenSpace>);
+    /// Map normalised device coordinates to `rect`. The rect is clamped to `target`, the size of the texture being rendered to
+    fn set_parrot_viewport(&mut self, rect: Rect<f32, ScreenSpace>, depth: Range<f32>, target: Size2D<u32, ScreenSpace>);
     fn draw_buffer_range(&mut self, buf: &'a VertexBuffer, range: Range<u32>);
     fn draw_parrot_indexed(&mut self, indicies: Range<u32>, instances: Range<u32>);
+    /// Same as `draw_parrot_indexed` but `base_vertex` is added to each index before the vertex is looked up.
+    /// Lets multiple meshes share a vertex and index buffer without remapping their indices
+    fn draw_parrot_indexed_base(&mut self, indicies: Range<u32>, base_vertex: i32, instances: Range<u32>);
+    /// Same as `draw_parrot_indexed` but debug asserts the range fits inside the index buffer
+    fn draw_parrot_indexed_checked<I: Index>(&mut self, index_buf: &IndexBuffer<I>, indicies: Range<u32>, instances: Range<u32>);
+    /// Set the reference value used by the pipeline's [`crate::pipeline::StencilConfig`]
+    fn set_parrot_stencil_reference(&mut self, reference: u32);
+    /// Execute a recorded [`RenderBundle`]. The pipeline, bindings and buffers set on the pass are cleared afterwards
+    fn execute_parrot_bundle(&mut self, bundle: &'a RenderBundle);
 }
 
 impl<'a> RenderPassExtention<'a> for wgpu::RenderPass<'a> {
     fn begin(encoder: &'a mut wgpu::CommandEncoder, view: &'a wgpu::TextureView, resolve_target: Option<&'a wgpu::TextureView>, depth: Option<&'a wgpu::TextureView>, op: PassOp) -> Self {
-        log::info!("Began render pass");
+        Self::begin_with_ops(encoder, view, resolve_target, depth, op, DepthOp::default(), true)
+    }
+
+    fn begin_with_ops(encoder: &'a mut wgpu::CommandEncoder, view: &'a wgpu::TextureView, resolve_target: Option<&'a wgpu::TextureView>, depth: Option<&'a wgpu::TextureView>, op: PassOp, depth_op: DepthOp, store: bool) -> Self {
+        log::debug!("Began render pass >> Store: {} || Depth op: {:?}", store, depth_op);
         if let Some(depth) = depth {
             encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: None,
@@ -465,19 +1117,16 @@ impl<'a> RenderPassExtention<'a> for wgpu::RenderPass<'a> {
                     resolve_target,
                     ops: wgpu::Operations {
                         load: op.into(),
-                        store: true,
+                        store,
                     },
                 })],
                 depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
                     view: depth,
                     depth_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(1.0),
+                        load: depth_op.into(),
                         store: true,
                     }),
-                    stencil_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(0),
-                        store: true,
-                    })
+                    stencil_ops: depth_op.stencil(),
                 }),
             })
         } else {
@@ -488,7 +1137,7 @@ impl<'a> RenderPassExtention<'a> for wgpu::RenderPass<'a> {
                     resolve_target,
                     ops: wgpu::Operations {
                         load: op.into(),
-                        store: true,
+                        store,
                     },
                 })],
                 depth_stencil_attachment: None,
@@ -496,6 +1145,31 @@ impl<'a> RenderPassExtention<'a> for wgpu::RenderPass<'a> {
         }
     }
 
+    fn begin_multi(encoder: &'a mut wgpu::CommandEncoder, views: &[&'a wgpu::TextureView], depth: Option<&'a wgpu::TextureView>, op: PassOp, depth_op: DepthOp) -> Self {
+        log::debug!("Began multi target render pass >> Targets: {} || Depth op: {:?}", views.len(), depth_op);
+        let load = op.to_wgpu();
+        let color_attachments: Vec<_> = views.iter().map(|view| Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load,
+                store: true,
+            },
+        })).collect();
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &color_attachments,
+            depth_stencil_attachment: depth.map(|depth| wgpu::RenderPassDepthStencilAttachment {
+                view: depth,
+                depth_ops: Some(wgpu::Operations {
+                    load: depth_op.into(),
+                    store: true,
+                }),
+                stencil_ops: depth_op.stencil(),
+            }),
+        })
+    }
+
     fn set_parrot_pipeline<'b, T: Plumber<'b>>(&mut self, pipeline: &'a T) {
         log::info!("Set pipeline");
         self.set_pipeline(&pipeline.pipeline.wgpu);
@@ -509,14 +1183,13 @@ impl<'a> RenderPassExtention<'a> for wgpu::RenderPass<'a> {
         self.set_bind_group(group.set_index, &group.wgpu, offsets);
     }
 
-    fn set_parrot_index_buffer(&mut self, index_buf: &'a IndexBuffer) {
-        log::info!("Set index buffer >> Name: {:?}", index_buf.name);
-        self.set_index_buffer(index_buf.slice(), wgpu::IndexFormat::Uint16)
+    fn set_parrot_index_buffer<I: Index>(&mut self, index_buf: &'a IndexBuffer<I>) {
+        log::info!("Set index buffer >> Name: {:?} || Format: {:?}", index_buf.name, I::FORMAT);
+        self.set_index_buffer(index_buf.slice(), I::FORMAT)
     }
 
     fn set_parrot_index_buffer_32(&mut self, index_buf: &'a IndexBuffer32) {
-        log::info!("Set index buffer 32 >> Name: {:?}", index_buf.name);
-        self.set_index_buffer(index_buf.slice(), wgpu::IndexFormat::Uint32)
+        self.set_parrot_index_buffer(index_buf)
     }
 
     fn set_parrot_vertex_buffer(&mut self, vertex_buf: &'a VertexBuffer) {
@@ -524,6 +1197,33 @@ impl<'a> RenderPassExtention<'a> for wgpu::RenderPass<'a> {
         self.set_vertex_buffer(0, vertex_buf.slice())
     }
 
+    fn set_parrot_instance_buffer(&mut self, instance_buf: &'a VertexBuffer) {
+        log::info!("Set instance buffer >> Name: {:?}", instance_buf.name);
+        self.set_vertex_buffer(INSTANCE_BUFFER_SLOT, instance_buf.slice())
+    }
+
+    fn set_parrot_push_constants<T: bytemuck::Pod>(&mut self, stages: wgpu::ShaderStages, offset: u32, data: &[T]) {
+        log::info!("Set push constants >> Stages: {:?} || Offset: {}", stages, offset);
+        self.set_push_constants(stages, offset, bytemuck::cast_slice(data))
+    }
+
+    fn set_parrot_scissor(&mut self, rect: Rect<u32, ScreenSpace>, target: Size2D<u32, ScreenSpace>) {
+        // A rect outside the target becomes an empty scissor, which draws nothing
+        let rect = rect.intersection(&target.into()).unwrap_or_default();
+        log::info!("Set scissor >> Rect: {:?}", rect);
+        self.set_scissor_rect(rect.origin.x, rect.origin.y, rect.size.width, rect.size.height)
+    }
+
+    fn set_parrot_viewport(&mut self, rect: Rect<f32, ScreenSpace>, depth: Range<f32>, target: Size2D<u32, ScreenSpace>) {
+        match rect.intersection(&target.to_f32().into()) {
+            Some(rect) if !rect.is_empty() => {
+                log::info!("Set viewport >> Rect: {:?} || Depth: {:?}", rect, depth);
+                self.set_viewport(rect.origin.x, rect.origin.y, rect.size.width, rect.size.height, depth.start, depth.end)
+            }
+            _ => log::error!("Viewport {:?} is outside the target {:?}. The viewport wasn't changed", rect, target),
+        }
+    }
+
     fn draw_buffer_range(&mut self, buf: &'a VertexBuffer, range: Range<u32>) {
         log::info!("Drawing buffer range >> Name: {:?} || Range: {:?}", buf.name, range);
         self.set_parrot_vertex_buffer(buf);
@@ -531,8 +1231,54 @@ impl<'a> RenderPassExtention<'a> for wgpu::RenderPass<'a> {
     }
 
     fn draw_parrot_indexed(&mut self, indicies: Range<u32>, instances: Range<u32>) {
-        log::info!("Drawing indexed >> Indicies: {:?} || Instances: {:?}", indicies, instances);
-        self.draw_indexed(indicies, 0, instances)
+        self.draw_parrot_indexed_base(indicies, 0, instances)
+    }
+
+    fn draw_parrot_indexed_base(&mut self, indicies: Range<u32>, base_vertex: i32, instances: Range<u32>) {
+        log::info!("Drawing indexed >> Indicies: {:?} || Base vertex: {} || Instances: {:?}", indicies, base_vertex, instances);
+        self.draw_indexed(indicies, base_vertex, instances)
+    }
+
+    fn draw_parrot_indexed_checked<I: Index>(&mut self, index_buf: &IndexBuffer<I>, indicies: Range<u32>, instances: Range<u32>) {
+        debug_assert!(
+            indicies.start <= indicies.end && indicies.end <= index_buf.size,
+            "Index range {:?} is out of bounds for index buffer {:?} of size {}", indicies, index_buf.name, index_buf.size
+        );
+        self.draw_parrot_indexed(indicies, instances)
+    }
+
+    fn set_parrot_stencil_reference(&mut self, reference: u32) {
+        log::info!("Set stencil reference >> Reference: {}", reference);
+        self.set_stencil_reference(reference);
+    }
+
+    fn execute_parrot_bundle(&mut self, bundle: &'a RenderBundle) {
+        log::info!("Executing render bundle >> Format: {:?} || Depth format: {:?}", bundle.format, bundle.depth_format);
+        self.execute_bundles(std::iter::once(&bundle.wgpu));
+    }
+}
+
+/// An extention on [`wgpu::ComputePass`] allowing it to perform actions on parrot's types
+pub trait ComputePassExtention<'a> {
+    fn set_parrot_compute_pipeline(&mut self, pipeline: &'a ComputePipeline);
+    fn set_binding(&mut self, group: &'a BindingGroup, offsets: &[u32]);
+    fn dispatch(&mut self, x: u32, y: u32, z: u32);
+}
+
+impl<'a> ComputePassExtention<'a> for wgpu::ComputePass<'a> {
+    fn set_parrot_compute_pipeline(&mut self, pipeline: &'a ComputePipeline) {
+        log::info!("Set compute pipeline");
+        self.set_pipeline(&pipeline.wgpu);
+    }
+
+    fn set_binding(&mut self, group: &'a BindingGroup, offsets: &[u32]) {
+        log::info!("Set compute binding group >> Index: {:?}", group.set_index);
+        self.set_bind_group(group.set_index, &group.wgpu, offsets);
+    }
+
+    fn dispatch(&mut self, x: u32, y: u32, z: u32) {
+        log::info!("Dispatching compute >> Workgroups: ({}, {}, {})", x, y, z);
+        self.dispatch_workgroups(x, y, z);
     }
 }
 
@@ -540,10 +1286,19 @@ impl<'a> RenderPassExtention<'a> for wgpu::RenderPass<'a> {
 pub trait RenderBundleExtention<'a> {
     fn set_parrot_pipeline<'b, T: Plumber<'b>>(&mut self, pipeline: &'a T);
     fn set_binding(&mut self, group: &'a BindingGroup, offsets: &[u32]);
-    fn set_parrot_index_buffer(&mut self, index_buf: &'a IndexBuffer);
+    /// Set the index buffer. Works with both 16 and 32 bit index buffers
+    fn set_parrot_index_buffer<I: Index>(&mut self, index_buf: &'a IndexBuffer<I>);
+    /// Same as `set_parrot_index_buffer`. Kept for older code
+    fn set_parrot_index_buffer_32(&mut self, index_buf: &'a IndexBuffer32);
     fn set_parrot_vertex_buffer(&mut self, vertex_buf: &'a VertexBuffer);
+    fn set_parrot_instance_buffer(&mut self, instance_buf: &'a VertexBuffer);
     fn draw_buffer_range(&mut self, buf: &'a VertexBuffer, range: Range<u32>);
     fn draw_parrot_indexed(&mut self, indicies: Range<u32>, instances: Range<u32>);
+    /// Same as `draw_parrot_indexed` but `base_vertex` is added to each index before the vertex is looked up.
+    /// Lets multiple meshes share a vertex and index buffer without remapping their indices
+    fn draw_parrot_indexed_base(&mut self, indicies: Range<u32>, base_vertex: i32, instances: Range<u32>);
+    /// Same as `draw_parrot_indexed` but debug asserts the range fits inside the index buffer
+    fn draw_parrot_indexed_checked<I: Index>(&mut self, index_buf: &IndexBuffer<I>, indicies: Range<u32>, instances: Range<u32>);
 }
 
 impl<'a> RenderBundleExtention<'a> for RenderBundleEncoder<'a> {
@@ -552,9 +1307,18 @@ impl<'a> RenderBundleExtention<'a> for RenderBundleEncoder<'a> {
         self.set_vertex_buffer(0, vertex_buf.slice());
     }
 
-    fn set_parrot_index_buffer(&mut self, index_buf: &'a IndexBuffer) {
-        log::info!("Set render bundle index buffer >> Name: {:?}", index_buf.name);
-        self.set_index_buffer(index_buf.slice(), wgpu::IndexFormat::Uint16)
+    fn set_parrot_instance_buffer(&mut self, instance_buf: &'a VertexBuffer) {
+        log::info!("Set render bundle instance buffer >> Name: {:?}", instance_buf.name);
+        self.set_vertex_buffer(INSTANCE_BUFFER_SLOT, instance_buf.slice());
+    }
+
+    fn set_parrot_index_buffer<I: Index>(&mut self, index_buf: &'a IndexBuffer<I>) {
+        log::info!("Set render bundle index buffer >> Name: {:?} || Format: {:?}", index_buf.name, I::FORMAT);
+        self.set_index_buffer(index_buf.slice(), I::FORMAT)
+    }
+
+    fn set_parrot_index_buffer_32(&mut self, index_buf: &'a IndexBuffer32) {
+        self.set_parrot_index_buffer(index_buf)
     }
 
     fn set_binding(&mut self, group: &'a BindingGroup, offsets: &[u32]) {
@@ -577,7 +1341,217 @@ impl<'a> RenderBundleExtention<'a> for RenderBundleEncoder<'a> {
     }
 
     fn draw_parrot_indexed(&mut self, indicies: Range<u32>, instances: Range<u32>) {
-        log::info!("Render bundle drawing indexed >> Indicies: {:?} || Instances: {:?}", indicies, instances);
-        self.draw_indexed(indicies, 0, instances)
+        self.draw_parrot_indexed_base(indicies, 0, instances)
+    }
+
+    fn draw_parrot_indexed_base(&mut self, indicies: Range<u32>, base_vertex: i32, instances: Range<u32>) {
+        log::info!("Render bundle drawing indexed >> Indicies: {:?} || Base vertex: {} || Instances: {:?}", indicies, base_vertex, instances);
+        self.draw_indexed(indicies, base_vertex, instances)
     }
-}
\ No newline at end of file
+
+    fn draw_parrot_indexed_checked<I: Index>(&mut self, index_buf: &IndexBuffer<I>, indicies: Range<u32>, instances: Range<u32>) {
+        debug_assert!(
+            indicies.start <= indicies.end && indicies.end <= index_buf.size,
+            "Index range {:?} is out of bounds for index buffer {:?} of size {}", indicies, index_buf.name, index_buf.size
+        );
+        self.draw_parrot_indexed(indicies, instances)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::pipeline::PipelineCore;
+
+    #[test]
+    fn retry_after_timeout() {
+        let mut results = vec![Ok(7), Err(wgpu::SurfaceError::Timeout)];
+        let mut seen = vec![];
+        let result = retry_surface(3, |last_error| {
+            seen.push(last_error);
+            results.pop().unwrap()
+        });
+        assert_eq!(result, Ok(7));
+        assert_eq!(seen, vec![None, Some(wgpu::SurfaceError::Timeout)]);
+    }
+
+    #[test]
+    fn retry_gives_up() {
+        let mut calls = 0;
+        let result: Result<(), _> = retry_surface(2, |_| {
+            calls += 1;
+            Err(wgpu::SurfaceError::Lost)
+        });
+        assert_eq!(result, Err(wgpu::SurfaceError::Lost));
+        assert_eq!(calls, 3);
+
+        // Out of memory isn't worth retrying
+        calls = 0;
+        let result: Result<(), _> = retry_surface(2, |_| {
+            calls += 1;
+            Err(wgpu::SurfaceError::OutOfMemory)
+        });
+        assert_eq!(result, Err(wgpu::SurfaceError::OutOfMemory));
+        assert_eq!(calls, 1);
+    }
+
+    /// Open a window and create a painter for it, for tests that need a surface. The window is returned so it outlives the surface
+    #[cfg(target_os = "linux")]
+    pub(crate) fn windowed() -> (winit::event_loop::EventLoop<()>, winit::window::Window, Painter) {
+        use winit::platform::unix::EventLoopExtUnix;
+        let event_loop = winit::event_loop::EventLoop::<()>::new_any_thread();
+        let window = winit::window::Window::new(&event_loop).expect("Failed to create window");
+        let instance = wgpu::Instance::new(wgpu::Backends::all());
+        let surface = unsafe { instance.create_surface(&window) };
+        let paint = pollster::block_on(Painter::for_surface(surface, &instance, 1)).expect("No adapter");
+        (event_loop, window, paint)
+    }
+
+    /// Create a painter without a surface for tests that need a gpu. Returns [`None`] if there's no adapter so those tests are skipped
+    pub(crate) fn headless() -> Option<Painter> {
+        let instance = wgpu::Instance::new(wgpu::Backends::all());
+        pollster::block_on(Painter::headless(&instance, 1)).ok()
+    }
+
+    #[test]
+    fn checked_draw_asserts_range() {
+        let paint = match headless() {
+            Some(paint) if cfg!(debug_assertions) => paint,
+            _ => return,
+        };
+        let index_buf = paint.index_buffer(&[0, 1, 2, 0, 2, 3], None);
+        let mut encoder = paint.create_render_bundle(None, paint.preferred_format());
+
+        encoder.draw_parrot_indexed_checked(&index_buf, index_buf.full_range(), 0..1);
+        encoder.draw_parrot_indexed_checked(&index_buf, 3..6, 0..1);
+        for range in [0..7, 5..9] {
+            let draw = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                encoder.draw_parrot_indexed_checked(&index_buf, range.clone(), 0..1)
+            }));
+            assert!(draw.is_err(), "{:?} wasn't caught", range);
+        }
+    }
+
+    #[test]
+    fn defaults_are_shared() {
+        let paint = match headless() {
+            Some(paint) => paint,
+            None => return,
+        };
+        assert!(Arc::ptr_eq(&paint.white_texture(), &paint.white_texture()));
+        assert!(Arc::ptr_eq(&paint.default_sampler(), &paint.default_sampler()));
+    }
+
+    #[test]
+    fn painter_is_send_sync() {
+        fn check<T: Send + Sync>() {}
+        check::<Painter>();
+    }
+
+    const TEST_SHADER: &str = "
+        @vertex
+        fn vs_main(@location(0) pos: vec3<f32>) -> @builtin(position) vec4<f32> {
+            return vec4<f32>(pos, 1.0);
+        }
+
+        @fragment
+        fn fs_main() -> @location(0) vec4<f32> {
+            return vec4<f32>(1.0, 1.0, 1.0, 1.0);
+        }
+    ";
+
+    struct TestPipe(PipelineCore);
+
+    impl std::ops::Deref for TestPipe {
+        type Target = PipelineCore;
+
+        fn deref(&self) -> &PipelineCore {
+            &self.0
+        }
+    }
+
+    impl DerefMut for TestPipe {
+        fn deref_mut(&mut self) -> &mut PipelineCore {
+            &mut self.0
+        }
+    }
+
+    impl<'a> Plumber<'a> for TestPipe {
+        /// New data for each uniform buffer
+        type PrepareContext = Vec<Vec<[f32; 4]>>;
+        type Uniforms = [f32; 4];
+
+        fn description() -> PipelineDescription<'a> {
+            PipelineDescription::new(&[crate::vertex::VertexFormat::Floatx3], None, ShaderFile::Wgsl(TEST_SHADER), Some("Test pipeline"))
+        }
+
+        fn setup(pipeline: Pipeline, _paint: &Painter) -> Self {
+            Self(PipelineCore { pipeline, bindings: vec![], uniforms: vec![] })
+        }
+
+        fn prepare(&'a mut self, context: Self::PrepareContext, _paint: &mut Painter) -> Vec<(&'a mut UniformBuffer, Vec<[f32; 4]>)> {
+            self.0.uniforms.iter_mut().zip(context).collect()
+        }
+    }
+
+    #[test]
+    fn reload_keeps_depth() {
+        let paint = match headless() {
+            Some(paint) => paint,
+            None => return,
+        };
+        let format = paint.preferred_format();
+        let mut with_depth: TestPipe = paint.pipeline(Blending::default(), format, None);
+        let mut no_depth: TestPipe = paint.pipeline_no_depth(Blending::default(), format, None);
+        assert_eq!(with_depth.pipeline.depth, Some(TestPipe::description().depth));
+        assert_eq!(no_depth.pipeline.depth, None);
+
+        paint.reload_pipeline_shader(&mut with_depth, TEST_SHADER, Blending::default(), format).unwrap();
+        paint.reload_pipeline_shader(&mut no_depth, TEST_SHADER, Blending::default(), format).unwrap();
+        assert_eq!(with_depth.pipeline.depth, Some(TestPipe::description().depth));
+        assert_eq!(no_depth.pipeline.depth, None);
+    }
+
+    #[test]
+    fn update_pipeline_returns_reallocated() {
+        let mut paint = match headless() {
+            Some(paint) => paint,
+            None => return,
+        };
+        let mut pipe: TestPipe = paint.pipeline(Blending::default(), paint.preferred_format(), None);
+        pipe.0.uniforms.push(paint.uniform_buffer(&[[0.0f32; 4]; 2], Some("Fits")));
+        pipe.0.uniforms.push(paint.uniform_buffer(&[[0.0f32; 4]], Some("Grows")));
+
+        let reallocated = paint.update_pipeline(&mut pipe, vec![vec![[1.0; 4]; 2], vec![[1.0; 4]; 3]]);
+        assert_eq!(reallocated.len(), 1);
+        let (i, buffer) = &reallocated[0];
+        assert_eq!(*i, 1);
+        assert_eq!(buffer.name.as_deref(), Some("Grows"));
+        assert!(buffer.byte_size() >= 3 * 16);
+        // The pipeline keeps the old buffer until the caller swaps it
+        assert_eq!(pipe.0.uniforms[1].byte_size(), 16);
+        assert_eq!(pipe.0.uniforms[0].count, 2);
+    }
+
+    #[test]
+    #[ignore = "needs a window"]
+    #[cfg(target_os = "linux")]
+    fn depth_is_reused_between_frames() {
+        let (_event_loop, _window, mut paint) = windowed();
+        let format = paint.preferred_format();
+        paint.configure(Size2D::new(64, 48), wgpu::PresentMode::Fifo, format).unwrap();
+
+        let first = paint.current_frame().unwrap().depth.clone().unwrap();
+        let second = paint.current_frame().unwrap().depth.clone().unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // Configuring the same size keeps it, a new size replaces it
+        paint.configure(Size2D::new(64, 48), wgpu::PresentMode::Fifo, format).unwrap();
+        let same = paint.current_frame().unwrap().depth.clone().unwrap();
+        assert!(Arc::ptr_eq(&first, &same));
+        paint.configure(Size2D::new(32, 32), wgpu::PresentMode::Fifo, format).unwrap();
+        let resized = paint.current_frame().unwrap().depth.clone().unwrap();
+        assert!(!Arc::ptr_eq(&first, &resized));
+        assert_eq!(resized.texture.size, Size2D::new(32, 32));
+    }
+}
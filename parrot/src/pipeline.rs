@@ -1,4 +1,6 @@
-use std::ops::Deref;
+use std::ops::{Deref, Range};
+
+pub use wgpu::ColorWrites;
 
 use crate::{
     binding::{
@@ -8,7 +10,7 @@ use crate::{
     },
     vertex::{VertexLayout, VertexFormat},
     shader::ShaderFile,
-    buffers::uniform::UniformBuffer, Painter,
+    buffers::{uniform::UniformBuffer, IndexBuffer}, Painter,
 };
 
 #[derive(Debug)]
@@ -20,11 +22,26 @@ pub struct Pipeline {
     pub layout: PipelineLayout,
     /// Layout of the verticies in the pipeline
     pub vertex_layout: VertexLayout,
+    /// Layout of the per instance data in the pipeline, bound at slot 1
+    pub instance_layout: Option<VertexLayout>,
+    /// How the pipeline uses the depth buffer. [`None`] if it was created without one
+    pub depth: Option<DepthConfig>,
+}
+
+#[derive(Debug)]
+/// Represents a compute pipeline
+pub struct ComputePipeline {
+    /// Wrapper around [`wgpu::ComputePipeline`]
+    pub wgpu: wgpu::ComputePipeline,
+    /// Layout of the pipeline
+    pub layout: PipelineLayout,
 }
 
 #[derive(Debug)]
 pub struct PipelineLayout {
     pub b_layouts: Vec<BindingGroupLayout>,
+    /// Push constant ranges used by the pipeline
+    pub push_constants: Vec<wgpu::PushConstantRange>,
 }
 
 /// A trait for creating and managing a pipeline.
@@ -62,59 +79,569 @@ pub struct PipelineCore {
 /// A Set of bindings
 pub struct Set<'a>(pub &'a[Binding], pub Option<&'a str>);
 
+/// A range of push constants visible to the given shader stages. Requires [`wgpu::Features::PUSH_CONSTANTS`]
+#[derive(Debug, Clone)]
+pub struct PushConstantRange {
+    /// The shader stages that can see the push constants
+    pub stages: wgpu::ShaderStages,
+    /// The byte range of the push constants. Must be a multiple of 4
+    pub range: Range<u32>,
+}
+
+impl PushConstantRange {
+    pub fn new(stages: wgpu::ShaderStages, range: Range<u32>) -> Self {
+        Self { stages, range }
+    }
+
+    pub(crate) fn as_wgpu(&self) -> wgpu::PushConstantRange {
+        wgpu::PushConstantRange {
+            stages: self.stages,
+            range: self.range.clone(),
+        }
+    }
+}
+
+impl From<&PushConstantRange> for wgpu::PushConstantRange {
+    fn from(pc: &PushConstantRange) -> Self {
+        pc.as_wgpu()
+    }
+}
+
 #[derive(Debug)]
 /// A description of how a pipeline is laid out. This is used by parrot to create your pipeline.
 pub struct PipelineDescription<'a> {
     /// Vertex layout of the pipeline
     pub vertex_layout: &'a [VertexFormat],
+    /// Layout of the per instance data, if any. Its shader locations follow on from the vertex layout
+    pub instance_layout: Option<&'a [VertexFormat]>,
     /// Bindings used to create a pipeline layout
     pub pipeline_layout: Option<&'a [Set<'a>]>,
     /// Shader file
     pub shader: ShaderFile,
+    /// Name of the vertex shader's entry point. Usually `"vs_main"`
+    pub vertex_entry: &'a str,
+    /// Name of the fragment shader's entry point. Usually `"fs_main"`
+    pub fragment_entry: &'a str,
+    /// Name of the pipeline
+    pub name: Option<&'a str>,
+    /// How the vertices are assembled into primitives
+    pub topology: PrimitiveTopology,
+    /// Format of the index buffer strip topologies are drawn with, for example `IndexBuffer::<u32>::FORMAT`. Ignored for lists
+    pub index_format: wgpu::IndexFormat,
+    /// Which face of a primitive is culled, if any
+    pub cull_mode: Option<Face>,
+    /// The winding order of a front facing primitive
+    pub front_face: FrontFace,
+    /// How the polygons are rasterised. Useful for wireframes
+    pub polygon_mode: PolygonMode,
+    /// How the pipeline uses the depth buffer. Only used by pipelines with a depth buffer
+    pub depth: DepthConfig,
+    /// Which color channels the pipeline writes to. Use [`ColorWrites::ALL`] to write them all
+    pub write_mask: ColorWrites,
+    /// Formats of extra color targets, written to locations 1 and up after the format the pipeline is created with.
+    /// Leave empty to render to a single target. Every target shares the same blending and write mask
+    pub targets: &'a [wgpu::TextureFormat],
+    /// Push constant ranges used by the pipeline. Leave empty if there are none
+    pub push_constants: &'a [PushConstantRange],
+}
+
+impl<'a> PipelineDescription<'a> {
+    /// Create a description of a pipeline with no per instance data, `vs_main` and `fs_main` entry points, a triangle list with no culling,
+    /// u16 indices, the default [`DepthConfig`], and one target that every color channel is written to. Override the rest with struct update syntax
+    pub fn new(vertex_layout: &'a [VertexFormat], pipeline_layout: Option<&'a [Set<'a>]>, shader: ShaderFile, name: Option<&'a str>) -> Self {
+        Self {
+            vertex_layout,
+            instance_layout: None,
+            pipeline_layout,
+            shader,
+            vertex_entry: "vs_main",
+            fragment_entry: "fs_main",
+            name,
+            topology: PrimitiveTopology::default(),
+            index_format: IndexBuffer::<u16>::FORMAT,
+            cull_mode: None,
+            front_face: FrontFace::default(),
+            polygon_mode: PolygonMode::default(),
+            depth: DepthConfig::default(),
+            write_mask: ColorWrites::ALL,
+            targets: &[],
+            push_constants: &[],
+        }
+    }
+
+    /// Returns the [`wgpu::PrimitiveState`] described by the pipeline. Strip topologies use [`PipelineDescription::index_format`]
+    pub fn primitive_state(&self) -> wgpu::PrimitiveState {
+        wgpu::PrimitiveState {
+            topology: self.topology.into(),
+            strip_index_format: if self.topology.is_strip() { Some(self.index_format) } else { None },
+            front_face: self.front_face.into(),
+            cull_mode: self.cull_mode.map(Face::into),
+            polygon_mode: self.polygon_mode.into(),
+            unclipped_depth: false,
+            conservative: false,
+        }
+    }
+}
+
+/// Everything besides the layouts and shader that [`crate::Device::create_pipeline`] needs to create a [`Pipeline`].
+/// [`Painter`] fills this in from a [`PipelineDescription`]
+#[derive(Debug, Clone)]
+pub struct PipelineConfig<'a> {
+    /// How the pipeline blends with what is already in the targets
+    pub blending: Blending,
+    /// Which color channels the pipeline writes to
+    pub write_mask: ColorWrites,
+    /// How the vertices are assembled and rasterised
+    pub primitive: wgpu::PrimitiveState,
+    /// How the pipeline uses the depth buffer. None creates a pipeline without a depth buffer
+    pub depth: Option<DepthConfig>,
+    /// Name of the vertex shader's entry point
+    pub vertex_entry: &'a str,
+    /// Name of the fragment shader's entry point
+    pub fragment_entry: &'a str,
+    /// Formats of the color targets, in location order
+    pub formats: &'a [wgpu::TextureFormat],
+    /// Multisampling of the targets
+    pub multisample: wgpu::MultisampleState,
     /// Name of the pipeline
-    pub name: Option<&'a str>
+    pub name: Option<&'a str>,
+}
+
+/// Wrapper around [`wgpu::PrimitiveTopology`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrimitiveTopology {
+    PointList,
+    LineList,
+    LineStrip,
+    #[default]
+    TriangleList,
+    TriangleStrip,
+}
+
+impl PrimitiveTopology {
+    fn as_wgpu(&self) -> wgpu::PrimitiveTopology {
+        match self {
+            PrimitiveTopology::PointList => wgpu::PrimitiveTopology::PointList,
+            PrimitiveTopology::LineList => wgpu::PrimitiveTopology::LineList,
+            PrimitiveTopology::LineStrip => wgpu::PrimitiveTopology::LineStrip,
+            PrimitiveTopology::TriangleList => wgpu::PrimitiveTopology::TriangleList,
+            PrimitiveTopology::TriangleStrip => wgpu::PrimitiveTopology::TriangleStrip,
+        }
+    }
+
+    /// Returns true if the topology is a strip topology
+    pub fn is_strip(&self) -> bool {
+        matches!(self, PrimitiveTopology::LineStrip | PrimitiveTopology::TriangleStrip)
+    }
+}
+
+impl From<PrimitiveTopology> for wgpu::PrimitiveTopology {
+    fn from(topology: PrimitiveTopology) -> Self {
+        topology.as_wgpu()
+    }
+}
+
+/// Describes how a pipeline tests against and writes to the depth buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepthConfig {
+    /// Whether the depth of each fragment is written to the depth buffer
+    pub write_enabled: bool,
+    /// The function used to test a fragment against the depth buffer
+    pub compare: CompareFunction,
+    /// The format of the depth buffer the pipeline draws with. Defaults to [`crate::buffers::DepthBuffer::FORMAT`]
+    pub format: wgpu::TextureFormat,
+    /// How the pipeline tests against and writes to the stencil buffer. Ignores the stencil by default
+    pub stencil: StencilConfig,
+}
+
+impl Default for DepthConfig {
+    fn default() -> Self {
+        Self {
+            write_enabled: true,
+            compare: CompareFunction::LessEqual,
+            format: crate::buffers::DepthBuffer::FORMAT,
+            stencil: StencilConfig::default(),
+        }
+    }
+}
+
+/// Describes how a pipeline tests against and writes to the stencil buffer. The depth format must have a stencil,
+/// see [`crate::buffers::DepthBuffer::has_stencil`]. The value tested against is set with [`crate::RenderPassExtention::set_parrot_stencil_reference`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StencilConfig {
+    /// Stencil state for front faces
+    pub front: StencilFaceState,
+    /// Stencil state for back faces
+    pub back: StencilFaceState,
+    /// Mask applied to the stencil value and reference before they're compared
+    pub read_mask: u32,
+    /// Mask applied to values written to the stencil buffer
+    pub write_mask: u32,
+}
+
+impl StencilConfig {
+    /// Write the reference value wherever the pipeline draws, creating a mask
+    pub fn write_mask() -> Self {
+        Self {
+            front: StencilFaceState::write(),
+            back: StencilFaceState::write(),
+            read_mask: !0,
+            write_mask: !0,
+        }
+    }
+
+    /// Only draw where the stencil buffer equals the reference value
+    pub fn draw_inside_mask() -> Self {
+        Self {
+            front: StencilFaceState::test(CompareFunction::Equal),
+            back: StencilFaceState::test(CompareFunction::Equal),
+            read_mask: !0,
+            write_mask: 0,
+        }
+    }
+
+    fn as_wgpu(&self) -> wgpu::StencilState {
+        wgpu::StencilState {
+            front: self.front.into(),
+            back: self.back.into(),
+            read_mask: self.read_mask,
+            write_mask: self.write_mask,
+        }
+    }
+}
+
+impl Default for StencilConfig {
+    fn default() -> Self {
+        Self {
+            front: StencilFaceState::IGNORE,
+            back: StencilFaceState::IGNORE,
+            read_mask: 0,
+            write_mask: 0,
+        }
+    }
+}
+
+impl From<StencilConfig> for wgpu::StencilState {
+    fn from(config: StencilConfig) -> Self {
+        config.as_wgpu()
+    }
+}
+
+/// Wrapper around [`wgpu::StencilFaceState`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StencilFaceState {
+    /// The function used to test the reference value against the stencil buffer
+    pub compare: CompareFunction,
+    /// Operation applied when the stencil test fails
+    pub fail_op: StencilOperation,
+    /// Operation applied when the stencil test passes but the depth test fails
+    pub depth_fail_op: StencilOperation,
+    /// Operation applied when both tests pass
+    pub pass_op: StencilOperation,
+}
+
+impl StencilFaceState {
+    /// Always passes and never writes
+    pub const IGNORE: Self = Self {
+        compare: CompareFunction::Always,
+        fail_op: StencilOperation::Keep,
+        depth_fail_op: StencilOperation::Keep,
+        pass_op: StencilOperation::Keep,
+    };
+
+    /// Always passes and writes the reference value
+    pub const fn write() -> Self {
+        Self {
+            pass_op: StencilOperation::Replace,
+            ..Self::IGNORE
+        }
+    }
+
+    /// Passes if the comparison passes and never writes
+    pub const fn test(compare: CompareFunction) -> Self {
+        Self {
+            compare,
+            ..Self::IGNORE
+        }
+    }
+
+    fn as_wgpu(&self) -> wgpu::StencilFaceState {
+        wgpu::StencilFaceState {
+            compare: self.compare.into(),
+            fail_op: self.fail_op.into(),
+            depth_fail_op: self.depth_fail_op.into(),
+            pass_op: self.pass_op.into(),
+        }
+    }
+}
+
+impl From<StencilFaceState> for wgpu::StencilFaceState {
+    fn from(state: StencilFaceState) -> Self {
+        state.as_wgpu()
+    }
+}
+
+/// Wrapper around [`wgpu::StencilOperation`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StencilOperation {
+    Keep,
+    Zero,
+    Replace,
+    Invert,
+    IncrementClamp,
+    DecrementClamp,
+    IncrementWrap,
+    DecrementWrap,
+}
+
+impl StencilOperation {
+    fn as_wgpu(&self) -> wgpu::StencilOperation {
+        match self {
+            StencilOperation::Keep => wgpu::StencilOperation::Keep,
+            StencilOperation::Zero => wgpu::StencilOperation::Zero,
+            StencilOperation::Replace => wgpu::StencilOperation::Replace,
+            StencilOperation::Invert => wgpu::StencilOperation::Invert,
+            StencilOperation::IncrementClamp => wgpu::StencilOperation::IncrementClamp,
+            StencilOperation::DecrementClamp => wgpu::StencilOperation::DecrementClamp,
+            StencilOperation::IncrementWrap => wgpu::StencilOperation::IncrementWrap,
+            StencilOperation::DecrementWrap => wgpu::StencilOperation::DecrementWrap,
+        }
+    }
+}
+
+impl From<StencilOperation> for wgpu::StencilOperation {
+    fn from(op: StencilOperation) -> Self {
+        op.as_wgpu()
+    }
+}
+
+/// Wrapper around [`wgpu::CompareFunction`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareFunction {
+    Never,
+    Less,
+    Equal,
+    LessEqual,
+    Greater,
+    NotEqual,
+    GreaterEqual,
+    Always,
+}
+
+impl CompareFunction {
+    fn as_wgpu(&self) -> wgpu::CompareFunction {
+        match self {
+            CompareFunction::Never => wgpu::CompareFunction::Never,
+            CompareFunction::Less => wgpu::CompareFunction::Less,
+            CompareFunction::Equal => wgpu::CompareFunction::Equal,
+            CompareFunction::LessEqual => wgpu::CompareFunction::LessEqual,
+            CompareFunction::Greater => wgpu::CompareFunction::Greater,
+            CompareFunction::NotEqual => wgpu::CompareFunction::NotEqual,
+            CompareFunction::GreaterEqual => wgpu::CompareFunction::GreaterEqual,
+            CompareFunction::Always => wgpu::CompareFunction::Always,
+        }
+    }
+}
+
+impl From<CompareFunction> for wgpu::CompareFunction {
+    fn from(compare: CompareFunction) -> Self {
+        compare.as_wgpu()
+    }
+}
+
+/// Wrapper around [`wgpu::Face`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Face {
+    Front,
+    Back,
+}
+
+impl Face {
+    fn as_wgpu(&self) -> wgpu::Face {
+        match self {
+            Face::Front => wgpu::Face::Front,
+            Face::Back => wgpu::Face::Back,
+        }
+    }
+}
+
+impl From<Face> for wgpu::Face {
+    fn from(face: Face) -> Self {
+        face.as_wgpu()
+    }
+}
+
+/// Wrapper around [`wgpu::FrontFace`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrontFace {
+    /// Counter clockwise triangles face forwards
+    #[default]
+    Ccw,
+    /// Clockwise triangles face forwards
+    Cw,
+}
+
+impl FrontFace {
+    fn as_wgpu(&self) -> wgpu::FrontFace {
+        match self {
+            FrontFace::Ccw => wgpu::FrontFace::Ccw,
+            FrontFace::Cw => wgpu::FrontFace::Cw,
+        }
+    }
+}
+
+impl From<FrontFace> for wgpu::FrontFace {
+    fn from(front_face: FrontFace) -> Self {
+        front_face.as_wgpu()
+    }
+}
+
+/// Wrapper around [`wgpu::PolygonMode`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PolygonMode {
+    /// Fill in the polygons
+    #[default]
+    Fill,
+    /// Draw the edges of the polygons. Requires [`wgpu::Features::POLYGON_MODE_LINE`]
+    Line,
+    /// Draw the vertices of the polygons. Requires [`wgpu::Features::POLYGON_MODE_POINT`]
+    Point,
+}
+
+impl PolygonMode {
+    fn as_wgpu(&self) -> wgpu::PolygonMode {
+        match self {
+            PolygonMode::Fill => wgpu::PolygonMode::Fill,
+            PolygonMode::Line => wgpu::PolygonMode::Line,
+            PolygonMode::Point => wgpu::PolygonMode::Point,
+        }
+    }
+
+    /// The feature the device needs to support this polygon mode
+    pub fn required_feature(&self) -> wgpu::Features {
+        match self {
+            PolygonMode::Fill => wgpu::Features::empty(),
+            PolygonMode::Line => wgpu::Features::POLYGON_MODE_LINE,
+            PolygonMode::Point => wgpu::Features::POLYGON_MODE_POINT,
+        }
+    }
+}
+
+impl From<PolygonMode> for wgpu::PolygonMode {
+    fn from(mode: PolygonMode) -> Self {
+        mode.as_wgpu()
+    }
 }
 
+/// Describes how a pipeline blends its output with what is already in the render target.
+/// The color and alpha channels can be blended separately using [`Blending::separate`]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Blending {
     src_factor: BlendFactor,
     dst_factor: BlendFactor,
     operation: BlendOp,
+    alpha_src_factor: BlendFactor,
+    alpha_dst_factor: BlendFactor,
+    alpha_operation: BlendOp,
 }
 
 impl Blending {
+    /// Create a blending where the color and alpha channels are blended the same way
     pub fn new(src_factor: BlendFactor, dst_factor: BlendFactor, operation: BlendOp) -> Self {
         Blending {
             src_factor,
             dst_factor,
             operation,
+            alpha_src_factor: src_factor,
+            alpha_dst_factor: dst_factor,
+            alpha_operation: operation,
         }
     }
 
-    pub fn constant() -> Self {
+    /// Create a blending where the color and alpha channels are blended independently
+    pub fn separate(
+        src_factor: BlendFactor,
+        dst_factor: BlendFactor,
+        operation: BlendOp,
+        alpha_src_factor: BlendFactor,
+        alpha_dst_factor: BlendFactor,
+        alpha_operation: BlendOp,
+    ) -> Self {
         Blending {
-            src_factor: BlendFactor::One,
-            dst_factor: BlendFactor::Zero,
-            operation: BlendOp::Add,
+            src_factor,
+            dst_factor,
+            operation,
+            alpha_src_factor,
+            alpha_dst_factor,
+            alpha_operation,
         }
     }
 
-    pub fn as_wgpu(&self) -> (wgpu::BlendFactor, wgpu::BlendFactor, wgpu::BlendOperation) {
+    pub fn constant() -> Self {
+        Blending::new(BlendFactor::One, BlendFactor::Zero, BlendOp::Add)
+    }
+
+    /// Standard alpha blending. `color = src * src_alpha + dst * (1 - src_alpha)` and `alpha = src_alpha + dst_alpha * (1 - src_alpha)`
+    pub fn alpha() -> Self {
+        Blending::separate(
+            BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha, BlendOp::Add,
+            BlendFactor::One, BlendFactor::OneMinusSrcAlpha, BlendOp::Add,
+        )
+    }
+
+    /// Adds the source on top of the destination, good for lights and particles. `color = src * src_alpha + dst` and `alpha = src_alpha + dst_alpha`
+    pub fn additive() -> Self {
+        Blending::separate(
+            BlendFactor::SrcAlpha, BlendFactor::One, BlendOp::Add,
+            BlendFactor::One, BlendFactor::One, BlendOp::Add,
+        )
+    }
+
+    /// Multiplies the destination by the source, good for shadows and tinting. `color = src * dst` and the destination alpha is kept
+    pub fn multiply() -> Self {
+        Blending::separate(
+            BlendFactor::Dst, BlendFactor::Zero, BlendOp::Add,
+            BlendFactor::Zero, BlendFactor::One, BlendOp::Add,
+        )
+    }
+
+    /// Alpha blending for colors already multiplied by their alpha. `color = src + dst * (1 - src_alpha)` and `alpha = src_alpha + dst_alpha * (1 - src_alpha)`
+    pub fn premultiplied_alpha() -> Self {
+        Blending::new(BlendFactor::One, BlendFactor::OneMinusSrcAlpha, BlendOp::Add)
+    }
+
+    /// Returns the color and alpha blend components
+    pub fn as_wgpu(&self) -> (wgpu::BlendComponent, wgpu::BlendComponent) {
         (
-            self.src_factor.as_wgpu(),
-            self.dst_factor.as_wgpu(),
-            self.operation.as_wgpu()
+            wgpu::BlendComponent {
+                src_factor: self.src_factor.as_wgpu(),
+                dst_factor: self.dst_factor.as_wgpu(),
+                operation: self.operation.as_wgpu(),
+            },
+            wgpu::BlendComponent {
+                src_factor: self.alpha_src_factor.as_wgpu(),
+                dst_factor: self.alpha_dst_factor.as_wgpu(),
+                operation: self.alpha_operation.as_wgpu(),
+            },
         )
     }
+
+    /// Returns the equivalent [`wgpu::BlendState`]
+    pub fn as_blend_state(&self) -> wgpu::BlendState {
+        let (color, alpha) = self.as_wgpu();
+        wgpu::BlendState { color, alpha }
+    }
 }
 
 impl Default for Blending {
     fn default() -> Self {
-        Blending {
-            src_factor: BlendFactor::SrcAlpha,
-            dst_factor: BlendFactor::OneMinusSrcAlpha,
-            operation: BlendOp::Add,
-        }
+        Blending::new(BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha, BlendOp::Add)
+    }
+}
+
+impl From<Blending> for wgpu::BlendState {
+    fn from(blending: Blending) -> Self {
+        blending.as_blend_state()
     }
 }
 
@@ -123,8 +650,14 @@ impl Default for Blending {
 pub enum BlendFactor {
     One,
     Zero,
+    Src,
+    OneMinusSrc,
     SrcAlpha,
     OneMinusSrcAlpha,
+    Dst,
+    OneMinusDst,
+    DstAlpha,
+    OneMinusDstAlpha,
 }
 
 impl BlendFactor {
@@ -134,6 +667,12 @@ impl BlendFactor {
             BlendFactor::OneMinusSrcAlpha => wgpu::BlendFactor::OneMinusSrcAlpha,
             BlendFactor::One => wgpu::BlendFactor::One,
             BlendFactor::Zero => wgpu::BlendFactor::Zero,
+            BlendFactor::Src => wgpu::BlendFactor::Src,
+            BlendFactor::OneMinusSrc => wgpu::BlendFactor::OneMinusSrc,
+            BlendFactor::Dst => wgpu::BlendFactor::Dst,
+            BlendFactor::OneMinusDst => wgpu::BlendFactor::OneMinusDst,
+            BlendFactor::DstAlpha => wgpu::BlendFactor::DstAlpha,
+            BlendFactor::OneMinusDstAlpha => wgpu::BlendFactor::OneMinusDstAlpha,
         }
     }
 }
@@ -144,16 +683,29 @@ impl From<BlendFactor> for wgpu::BlendFactor {
     }
 }
 
-/// Wrapper around [`wgpu::BlendOperation`]
+/// Wrapper around [`wgpu::BlendOperation`]. Note that [`BlendOp::Min`] and [`BlendOp::Max`] ignore the blend factors.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BlendOp {
+    /// Src + Dst
     Add,
+    /// Src - Dst
+    Subtract,
+    /// Dst - Src
+    ReverseSubtract,
+    /// min(Src, Dst)
+    Min,
+    /// max(Src, Dst)
+    Max,
 }
 
 impl BlendOp {
     fn as_wgpu(&self) -> wgpu::BlendOperation {
         match self {
             BlendOp::Add => wgpu::BlendOperation::Add,
+            BlendOp::Subtract => wgpu::BlendOperation::Subtract,
+            BlendOp::ReverseSubtract => wgpu::BlendOperation::ReverseSubtract,
+            BlendOp::Min => wgpu::BlendOperation::Min,
+            BlendOp::Max => wgpu::BlendOperation::Max,
         }
     }
 }
@@ -162,4 +714,117 @@ impl From<BlendOp> for wgpu::BlendOperation {
     fn from(b_op: BlendOp) -> Self {
         b_op.as_wgpu()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_blends_alpha_like_color() {
+        let (color, alpha) = Blending::new(BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha, BlendOp::Add).as_wgpu();
+        assert_eq!(color, alpha);
+    }
+
+    #[test]
+    fn separate_blends_alpha_independently() {
+        // Premultiplied color with the alpha channel kept as is
+        let blending = Blending::separate(
+            BlendFactor::One, BlendFactor::OneMinusSrcAlpha, BlendOp::Add,
+            BlendFactor::Zero, BlendFactor::One, BlendOp::Add,
+        );
+        let state = blending.as_blend_state();
+        assert_eq!(state.color, wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+            operation: wgpu::BlendOperation::Add,
+        });
+        assert_eq!(state.alpha, wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::Zero,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Add,
+        });
+    }
+
+    #[test]
+    fn strip_index_format() {
+        let list = PipelineDescription::new(&[VertexFormat::Floatx2], None, ShaderFile::Wgsl(""), None);
+        assert_eq!(list.primitive_state().strip_index_format, None);
+
+        let u16_strip = PipelineDescription { topology: PrimitiveTopology::LineStrip, ..list };
+        assert_eq!(u16_strip.primitive_state().strip_index_format, Some(wgpu::IndexFormat::Uint16));
+
+        let u32_strip = PipelineDescription {
+            topology: PrimitiveTopology::TriangleStrip,
+            index_format: IndexBuffer::<u32>::FORMAT,
+            ..PipelineDescription::new(&[VertexFormat::Floatx2], None, ShaderFile::Wgsl(""), None)
+        };
+        assert_eq!(u32_strip.primitive_state().strip_index_format, Some(wgpu::IndexFormat::Uint32));
+    }
+
+    #[test]
+    fn blend_ops_map_to_wgpu() {
+        let ops = [
+            (BlendOp::Add, wgpu::BlendOperation::Add),
+            (BlendOp::Subtract, wgpu::BlendOperation::Subtract),
+            (BlendOp::ReverseSubtract, wgpu::BlendOperation::ReverseSubtract),
+            (BlendOp::Min, wgpu::BlendOperation::Min),
+            (BlendOp::Max, wgpu::BlendOperation::Max),
+        ];
+        for (op, wgpu_op) in ops {
+            assert_eq!(wgpu::BlendOperation::from(op), wgpu_op);
+        }
+    }
+
+    /// Blend a single channel of `src` onto `dst` the way the gpu would
+    fn blend(component: wgpu::BlendComponent, src: [f32; 4], dst: [f32; 4], channel: usize) -> f32 {
+        let factor = |f: wgpu::BlendFactor| match f {
+            wgpu::BlendFactor::One => 1.0,
+            wgpu::BlendFactor::Zero => 0.0,
+            wgpu::BlendFactor::Src => src[channel],
+            wgpu::BlendFactor::OneMinusSrc => 1.0 - src[channel],
+            wgpu::BlendFactor::SrcAlpha => src[3],
+            wgpu::BlendFactor::OneMinusSrcAlpha => 1.0 - src[3],
+            wgpu::BlendFactor::Dst => dst[channel],
+            wgpu::BlendFactor::OneMinusDst => 1.0 - dst[channel],
+            wgpu::BlendFactor::DstAlpha => dst[3],
+            wgpu::BlendFactor::OneMinusDstAlpha => 1.0 - dst[3],
+            f => unimplemented!("{:?}", f),
+        };
+        let (s, d) = (src[channel] * factor(component.src_factor), dst[channel] * factor(component.dst_factor));
+        match component.operation {
+            wgpu::BlendOperation::Add => s + d,
+            wgpu::BlendOperation::Subtract => s - d,
+            wgpu::BlendOperation::ReverseSubtract => d - s,
+            wgpu::BlendOperation::Min => src[channel].min(dst[channel]),
+            wgpu::BlendOperation::Max => src[channel].max(dst[channel]),
+        }
+    }
+
+    /// Returns the blended color and alpha
+    fn apply(blending: Blending, src: [f32; 4], dst: [f32; 4]) -> (f32, f32) {
+        let state = blending.as_blend_state();
+        (blend(state.color, src, dst, 0), blend(state.alpha, src, dst, 3))
+    }
+
+    #[test]
+    fn blend_presets() {
+        let src = [0.8, 0.0, 0.0, 0.25];
+        let dst = [0.4, 0.0, 0.0, 0.5];
+        let presets = [
+            // color = src * src_alpha + dst * (1 - src_alpha), alpha = src_alpha + dst_alpha * (1 - src_alpha)
+            (Blending::alpha(), (0.8 * 0.25 + 0.4 * 0.75, 0.25 + 0.5 * 0.75)),
+            // color = src * src_alpha + dst, alpha = src_alpha + dst_alpha
+            (Blending::additive(), (0.8 * 0.25 + 0.4, 0.25 + 0.5)),
+            // color = src * dst, alpha = dst_alpha
+            (Blending::multiply(), (0.8 * 0.4, 0.5)),
+            // color = src + dst * (1 - src_alpha), alpha = src_alpha + dst_alpha * (1 - src_alpha)
+            (Blending::premultiplied_alpha(), (0.8 + 0.4 * 0.75, 0.25 + 0.5 * 0.75)),
+            (Blending::constant(), (0.8, 0.25)),
+        ];
+        for (blending, (color, alpha)) in presets {
+            let (c, a) = apply(blending.clone(), src, dst);
+            assert!((c - color).abs() < 1e-6 && (a - alpha).abs() < 1e-6, "{:?} gave {} {}", blending, c, a);
+        }
+    }
+}
+
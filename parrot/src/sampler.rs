@@ -1,4 +1,5 @@
-use crate::binding::Bind;
+use crate::{binding::Bind, error::ParrotError, pipeline::CompareFunction};
+use std::{num::NonZeroU8, ops::Range};
 
 /// Represents a sampler
 /// 
@@ -8,6 +9,18 @@ pub struct Sampler {
     pub wgpu: wgpu::Sampler,
 }
 
+impl Sampler {
+    /// A sampler that picks the nearest texel, for pixel art. Clamps to the edge
+    pub fn nearest(device: &crate::Device) -> Self {
+        device.create_sampler(wgpu::FilterMode::Nearest, wgpu::FilterMode::Nearest, Some("Nearest sampler"))
+    }
+
+    /// A sampler that blends between texels. Clamps to the edge
+    pub fn linear(device: &crate::Device) -> Self {
+        device.create_sampler(wgpu::FilterMode::Linear, wgpu::FilterMode::Linear, Some("Linear sampler"))
+    }
+}
+
 impl Bind for Sampler {
     fn binding(&self, index: u32) -> wgpu::BindGroupEntry {
         wgpu::BindGroupEntry {
@@ -15,4 +28,171 @@ impl Bind for Sampler {
             resource: wgpu::BindingResource::Sampler(&self.wgpu),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Wrapper around [`wgpu::AddressMode`]. Defines what happens when a texture is sampled outside of its 0-1 coordinates.
+///
+/// Note that [`AddressMode::ClampToBorder`] requires [`wgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER`] and that on GL
+/// [`AddressMode::Repeat`] and [`AddressMode::MirrorRepeat`] may require the texture to have power of two dimensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressMode {
+    ClampToEdge,
+    Repeat,
+    MirrorRepeat,
+    ClampToBorder,
+}
+
+impl AddressMode {
+    fn as_wgpu(&self) -> wgpu::AddressMode {
+        match self {
+            AddressMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+            AddressMode::Repeat => wgpu::AddressMode::Repeat,
+            AddressMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+            AddressMode::ClampToBorder => wgpu::AddressMode::ClampToBorder,
+        }
+    }
+}
+
+impl From<AddressMode> for wgpu::AddressMode {
+    fn from(mode: AddressMode) -> Self {
+        mode.as_wgpu()
+    }
+}
+
+/// Describes how a [`Sampler`] should be created
+#[derive(Debug, Clone, PartialEq)]
+pub struct SamplerDesc {
+    /// Filter used when the texture is magnified
+    pub mag_filter: wgpu::FilterMode,
+    /// Filter used when the texture is minified
+    pub min_filter: wgpu::FilterMode,
+    /// Filter used when sampling between mip levels. Use [`wgpu::FilterMode::Linear`] for trilinear filtering
+    pub mipmap_filter: wgpu::FilterMode,
+    /// Range of mip levels that can be sampled. Level 0 is the full size texture. Negative values are treated as 0
+    pub lod_clamp: Range<f32>,
+    /// Address mode along the u axis
+    pub address_mode_u: AddressMode,
+    /// Address mode along the v axis
+    pub address_mode_v: AddressMode,
+    /// Address mode along the w axis
+    pub address_mode_w: AddressMode,
+    /// Border color used with [`AddressMode::ClampToBorder`]. Defaults to transparent black when not set.
+    pub border_color: Option<wgpu::SamplerBorderColor>,
+    /// Max anisotropy used when sampling. Requires all the filters to be [`wgpu::FilterMode::Linear`]
+    pub anisotropy: Option<NonZeroU8>,
+    /// Makes this a comparison sampler. Comparison samplers must be bound with [`crate::binding::BindingType::ComparisonSampler`]
+    /// and can only be used with `textureSampleCompare` in the shader
+    pub compare: Option<CompareFunction>,
+}
+
+impl SamplerDesc {
+    /// Create a sampler description with the given filters that clamps to the edge
+    pub fn new(mag_filter: wgpu::FilterMode, min_filter: wgpu::FilterMode) -> Self {
+        Self {
+            mag_filter,
+            min_filter,
+            ..Default::default()
+        }
+    }
+
+    /// Set the filter used between mip levels
+    pub fn with_mipmap_filter(mut self, filter: wgpu::FilterMode) -> Self {
+        self.mipmap_filter = filter;
+        self
+    }
+
+    /// Only sample mip levels inside `lod_clamp`
+    pub fn with_lod_clamp(mut self, lod_clamp: Range<f32>) -> Self {
+        self.lod_clamp = lod_clamp;
+        self
+    }
+
+    /// Use the same address mode on all three axes
+    pub fn with_address_mode(mut self, mode: AddressMode) -> Self {
+        self.address_mode_u = mode;
+        self.address_mode_v = mode;
+        self.address_mode_w = mode;
+        self
+    }
+
+    /// Set the border color used with [`AddressMode::ClampToBorder`]
+    pub fn with_border_color(mut self, color: wgpu::SamplerBorderColor) -> Self {
+        self.border_color = Some(color);
+        self
+    }
+
+    /// Set the max anisotropy. Also sets all the filters to [`wgpu::FilterMode::Linear`] as wgpu requires
+    pub fn with_anisotropy(mut self, anisotropy: NonZeroU8) -> Self {
+        self.anisotropy = Some(anisotropy);
+        self.mag_filter = wgpu::FilterMode::Linear;
+        self.min_filter = wgpu::FilterMode::Linear;
+        self.mipmap_filter = wgpu::FilterMode::Linear;
+        self
+    }
+
+    /// Make this a comparison sampler that compares against the depth value with `compare`
+    pub fn with_compare(mut self, compare: CompareFunction) -> Self {
+        self.compare = Some(compare);
+        self
+    }
+
+    /// Checks the description can be used to create a sampler
+    pub fn validate(&self) -> Result<(), ParrotError> {
+        if let Some(anisotropy) = self.anisotropy {
+            let linear = [self.mag_filter, self.min_filter, self.mipmap_filter].iter().all(|f| *f == wgpu::FilterMode::Linear);
+            if !linear || ![1, 2, 4, 8, 16].contains(&anisotropy.get()) {
+                return Err(ParrotError::InvalidAnisotropy(anisotropy.get()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the min and max lod clamps to be used by wgpu. The min is raised to 0 and the max is raised to the min
+    pub fn wgpu_lod_clamp(&self) -> (f32, f32) {
+        let min = self.lod_clamp.start.max(0.0);
+        (min, self.lod_clamp.end.max(min))
+    }
+
+    /// Returns the border color to be used by wgpu. This is only set if one of the axes clamps to the border.
+    pub fn wgpu_border_color(&self) -> Option<wgpu::SamplerBorderColor> {
+        let uses_border = [self.address_mode_u, self.address_mode_v, self.address_mode_w].contains(&AddressMode::ClampToBorder);
+        if uses_border {
+            Some(self.border_color.unwrap_or(wgpu::SamplerBorderColor::TransparentBlack))
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for SamplerDesc {
+    fn default() -> Self {
+        Self {
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            lod_clamp: 0.0..100.0,
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            border_color: None,
+            anisotropy: None,
+            compare: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lod_clamp() {
+        assert_eq!(SamplerDesc::default().wgpu_lod_clamp(), (0.0, 100.0));
+        assert_eq!(SamplerDesc::default().with_lod_clamp(1.0..4.0).wgpu_lod_clamp(), (1.0, 4.0));
+        // Negative mins are raised to 0
+        assert_eq!(SamplerDesc::default().with_lod_clamp(-2.0..3.0).wgpu_lod_clamp(), (0.0, 3.0));
+        // The max is never below the min
+        assert_eq!(SamplerDesc::default().with_lod_clamp(-2.0..-1.0).wgpu_lod_clamp(), (0.0, 0.0));
+        assert_eq!(SamplerDesc::default().with_lod_clamp(5.0..2.0).wgpu_lod_clamp(), (5.0, 5.0));
+    }
+}
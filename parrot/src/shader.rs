@@ -1,3 +1,5 @@
+use crate::error::ParrotError;
+
 /// A shader
 #[derive(Debug)]
 pub struct Shader {
@@ -6,11 +8,79 @@ pub struct Shader {
 }
 
 /// Represents a shader file. I recommend using wgsl as it is first class supported and spirv is planned to be
-/// depreciated. The entry points for the vertex shader is vs_main and fragment shader fs_main
+/// depreciated. The entry points are set by [`crate::pipeline::PipelineDescription`], usually vs_main for the vertex shader and fs_main for the fragment shader
 #[derive(Debug, Clone)]
 pub enum ShaderFile {
     Wgsl(&'static str),
+    /// Wgsl source code loaded at runtime, for example when hot-reloading a shader
+    WgslOwned(String),
     Spirv(&'static [u8])
 }
 
-pub use wgpu::ShaderStages;
\ No newline at end of file
+impl ShaderFile {
+    /// Parse and validate a wgsl shader, returning [`ParrotError::ShaderCompile`] with the line and column of the first error.
+    /// Spirv isn't checked
+    pub fn validate(&self) -> Result<(), ParrotError> {
+        match self {
+            ShaderFile::Wgsl(source) => validate_wgsl(source),
+            ShaderFile::WgslOwned(source) => validate_wgsl(source),
+            ShaderFile::Spirv(_) => Ok(()),
+        }
+    }
+}
+
+/// Parse and validate wgsl source code so errors can be handled instead of panicking inside wgpu
+pub fn validate_wgsl(source: &str) -> Result<(), ParrotError> {
+    let module = naga::front::wgsl::parse_str(source)
+        .map_err(|e| ParrotError::ShaderCompile { message: e.emit_to_string(source) })?;
+    naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::all())
+        .validate(&module)
+        .map_err(|e| {
+            let message = match e.location(source) {
+                Some(loc) => format!("{}:{}: {}", loc.line_number, loc.line_position, e),
+                None => e.to_string(),
+            };
+            ParrotError::ShaderCompile { message }
+        })?;
+    Ok(())
+}
+
+pub use wgpu::ShaderStages;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID: &str = "
+@vertex
+fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(f32(i), 0.0, 0.0, 1.0);
+}
+";
+
+    #[test]
+    fn valid_shader_passes() {
+        assert!(validate_wgsl(VALID).is_ok());
+        assert!(ShaderFile::WgslOwned(VALID.to_string()).validate().is_ok());
+    }
+
+    #[test]
+    fn parse_error_has_location() {
+        // Missing semicolon after the return on line 4
+        let broken = VALID.replace("1.0);", "1.0)");
+        match ShaderFile::WgslOwned(broken).validate() {
+            Err(ParrotError::ShaderCompile { message }) => assert!(message.contains(":5:1"), "{}", message),
+            other => panic!("Expected a compile error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn validation_error_has_location() {
+        // Parses but returns the wrong type
+        let broken = VALID.replace("return vec4<f32>(f32(i), 0.0, 0.0, 1.0);", "return 1.0;");
+        match validate_wgsl(&broken) {
+            Err(ParrotError::ShaderCompile { message }) => assert!(message.starts_with("4:"), "{}", message),
+            other => panic!("Expected a compile error, got {:?}", other),
+        }
+    }
+}
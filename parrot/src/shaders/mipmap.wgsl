@@ -0,0 +1,29 @@
+// Used to downsample one mip level into the next
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+}
+
+// Draws a triangle that covers the whole screen
+@vertex
+fn vs_main(
+    @builtin(vertex_index) index: u32,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let x = f32((index << 1u) & 2u);
+    let y = f32(index & 2u);
+    out.tex_coords = vec2<f32>(x, y);
+    out.clip_position = vec4<f32>(x * 2.0 - 1.0, 1.0 - y * 2.0, 0.0, 1.0);
+    return out;
+}
+
+@group(0) @binding(0)
+var t_source: texture_2d<f32>;
+@group(0) @binding(1)
+var s_source: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(t_source, s_source, in.tex_coords);
+}
@@ -1,7 +1,8 @@
 use euclid::{Size2D, Rect, Point2D};
 
 use crate::{
-    binding::Bind, device::Device, transform::ScreenSpace, color::Color
+    binding::{Bind, Binding, BindingType}, device::Device, transform::ScreenSpace, color::{Color, Rgba},
+    sampler::SamplerDesc, Painter, error::ParrotError,
 };
 
 /// Parrots texture types. Note that textures coordinate system has the y-axis pointing down and the origin at the top right
@@ -17,10 +18,121 @@ pub struct Texture {
     /// Format of texture
     pub format: wgpu::TextureFormat,
     /// Size of the texture
-    pub size: Size2D<u32, ScreenSpace>
+    pub size: Size2D<u32, ScreenSpace>,
+    /// Number of mip levels in the texture
+    pub mip_level_count: u32,
+    /// How the texture can be used
+    pub usage: wgpu::TextureUsages,
 }
 
 impl Texture {
+    /// Returns the amount of layers in the texture. This is 1 unless it was created with [`Painter::texture_array`]
+    pub const fn layers(&self) -> u32 {
+        self.extent.depth_or_array_layers
+    }
+
+    /// Returns the size of a pixel in bytes, for example 4 for [`wgpu::TextureFormat::Rgba8UnormSrgb`] and 8 for [`wgpu::TextureFormat::Rgba16Float`]
+    pub fn bytes_per_pixel(&self) -> u32 {
+        self.format.describe().block_size as u32
+    }
+
+    /// Returns the amount of mip levels needed for a full mip chain of a texture of the given size
+    pub fn mip_levels_for(size: Size2D<u32, ScreenSpace>) -> u32 {
+        32 - size.width.max(size.height).max(1).leading_zeros()
+    }
+
+    /// Fills each mip level of the texture by downsampling the level above it. The texture must have been created with the
+    /// [`wgpu::TextureUsages::TEXTURE_BINDING`] and [`wgpu::TextureUsages::RENDER_ATTACHMENT`] usages and a filterable, renderable format.
+    pub fn generate_mipmaps(&self, painter: &mut Painter) {
+        if self.mip_level_count <= 1 {
+            return;
+        }
+        log::info!("Generating mipmaps >> Levels: {}", self.mip_level_count);
+
+        let device = &painter.device;
+        let shader = device.create_wgsl_shader(include_str!("./shaders/mipmap.wgsl"), Some("Mipmap shader")).unwrap();
+        let bind_layout = device.create_binding_group_layout(0, &[
+            Binding {
+                binding: BindingType::Texture { multisampled: false, view_dimension: wgpu::TextureViewDimension::D2 },
+                stage: wgpu::ShaderStages::FRAGMENT,
+            },
+            Binding {
+                binding: BindingType::Sampler,
+                stage: wgpu::ShaderStages::FRAGMENT,
+            },
+        ], Some("Mipmap bind group layout"));
+        let layout = device.wgpu.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Mipmap pipeline layout"),
+            bind_group_layouts: &[&bind_layout.wgpu],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.wgpu.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Mipmap pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader.wgpu,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader.wgpu,
+                entry_point: "fs_main",
+                targets: &[Some(self.format.into())],
+            }),
+            multiview: None,
+        });
+        let sampler = device.create_sampler_with_desc(
+            &SamplerDesc::new(wgpu::FilterMode::Linear, wgpu::FilterMode::Linear),
+            Some("Mipmap sampler")
+        );
+
+        // A view for each mip level
+        let views: Vec<wgpu::TextureView> = (0..self.mip_level_count).map(|mip| {
+            self.wgpu.create_view(&wgpu::TextureViewDescriptor {
+                label: Some("Mip view"),
+                base_mip_level: mip,
+                mip_level_count: std::num::NonZeroU32::new(1),
+                ..Default::default()
+            })
+        }).collect();
+
+        let mut encoder = device.create_command_encoder();
+        for target in 1..views.len() {
+            let bind_group = device.wgpu.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Mipmap bind group"),
+                layout: &bind_layout.wgpu,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&views[target - 1]),
+                    },
+                    sampler.binding(1),
+                ],
+            });
+
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Mipmap pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &views[target],
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+            });
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+
+        painter.device.submit(Some(encoder.finish()));
+    }
+
     /// Clears a texture with a singular color
     pub fn clear<T> (
         texture: &Texture,
@@ -41,29 +153,52 @@ impl Texture {
         )
     }
     
-    /// Fill a texture with texture pixels
+    /// Fill a texture with texture pixels. For texture arrays this fills the first layer
     pub fn fill<T> (
         texture: &Texture,
         t_pixels: &[T],
         device: &Device
     ) where
     T: bytemuck::Pod + Clone + Copy + 'static + Color,
+    {
+        Self::fill_layer(texture, 0, t_pixels, device)
+    }
+
+    /// Fill a layer of a texture array with texture pixels
+    pub fn fill_layer<T> (
+        texture: &Texture,
+        layer: u32,
+        t_pixels: &[T],
+        device: &Device
+    ) where
+    T: bytemuck::Pod + Clone + Copy + 'static + Color,
     {
         assert!(
             t_pixels.len() as u32 >= texture.size.area(),
             "Fatal: incorrect length for t_pixel buffer. Pixels length: {} || Required buffer length: {}", t_pixels.len(), texture.size.area()
         );
+        assert!(
+            layer < texture.layers(),
+            "Fatal: layer {} is out of bounds for a texture with {} layers", layer, texture.layers()
+        );
+        assert!(
+            std::mem::size_of::<T>() as u32 == texture.bytes_per_pixel(),
+            "Fatal: pixel size of {} bytes doesn't match the {:?} texture format", std::mem::size_of::<T>(), texture.format
+        );
         
         let t_pixels = bytemuck::cast_slice(t_pixels);
         
         Self::copy(
             texture,
-            texture.size,
             Point2D::new(0, 0),
+            layer,
             &device.queue,
             t_pixels,
-            t_pixels.len() as u32 / texture.extent.height,
-            texture.extent
+            texture.extent.width * texture.bytes_per_pixel(),
+            wgpu::Extent3d {
+                depth_or_array_layers: 1,
+                ..texture.extent
+            }
         )
     }
     
@@ -82,23 +217,96 @@ impl Texture {
         );
         
         let t_pixels: &[u8] = bytemuck::cast_slice(t_pixels);
+        assert!(
+            t_pixels.len() as u32 >= dest_rect.area() * texture.bytes_per_pixel(),
+            "Fatal: incorrect length for t_pixel buffer. Bytes: {} || Required bytes: {}", t_pixels.len(), dest_rect.area() * texture.bytes_per_pixel()
+        );
         
         let extent = wgpu::Extent3d {
             width: dest_rect.width(),
             height: dest_rect.height(),
-            depth_or_array_layers: 0,
+            depth_or_array_layers: 1,
         };
         Self::copy(
             &texture,
-            dest_rect.size,
             dest_rect.origin,
+            0,
             &device.queue,
             t_pixels,
-            t_pixels.len() as u32 / texture.extent.height * 4 as u32,
+            dest_rect.width() * texture.bytes_per_pixel(),
             extent
         )
     }
     
+    /// Free the texture's gpu memory now instead of waiting for it to be dropped
+    pub fn destroy(self) {
+        log::info!("Destroying texture >> Size: {:?} || Format: {:?}", self.size, self.format);
+        self.wgpu.destroy();
+    }
+
+    /// Reads the texture back from the gpu. The returned bytes are tightly packed rows of pixels.
+    /// This blocks until the gpu is finished and requires the texture to have the [`wgpu::TextureUsages::COPY_SRC`] usage.
+    pub fn read(&self, device: &Device) -> Result<Vec<u8>, ParrotError> {
+        if !self.usage.contains(wgpu::TextureUsages::COPY_SRC) {
+            return Err(ParrotError::MissingTextureUsage(wgpu::TextureUsages::COPY_SRC));
+        }
+        log::info!("Reading texture >> Size: {:?}", self.size);
+
+        // Rows copied into a buffer must be aligned
+        let unpadded_bytes_per_row = self.size.width * self.bytes_per_pixel();
+        let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer_size = (padded_bytes_per_row * self.size.height) as usize;
+
+        let buffer = device.create_staging_buffer(buffer_size as u64, Some("Texture read buffer"));
+        let mut encoder = device.create_command_encoder();
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &self.wgpu,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(padded_bytes_per_row),
+                    rows_per_image: std::num::NonZeroU32::new(self.size.height),
+                },
+            },
+            wgpu::Extent3d {
+                width: self.size.width,
+                height: self.size.height,
+                depth_or_array_layers: 1,
+            },
+        );
+        device.queue.submit(Some(encoder.finish()));
+
+        // Strip the padding from each row
+        let padded: Vec<u8> = device.map_and_read_buffer(&buffer, buffer_size)?;
+        Ok(padded
+            .chunks(padded_bytes_per_row as usize)
+            .flat_map(|row| &row[..unpadded_bytes_per_row as usize])
+            .copied()
+            .collect())
+    }
+
+    /// Reads back a [`wgpu::TextureFormat::Rgba16Float`] texture, such as a HDR frame buffer, converting each pixel to [`Rgba`].
+    /// Unlike the 8-bit formats the values aren't clamped, so they can be above 1.0
+    pub fn read_rgba16f(&self, device: &Device) -> Result<Vec<Rgba>, ParrotError> {
+        if self.format != wgpu::TextureFormat::Rgba16Float {
+            return Err(ParrotError::WrongTextureFormat { expected: wgpu::TextureFormat::Rgba16Float, got: self.format });
+        }
+        let bytes = self.read(device)?;
+        Ok(bytes
+            .chunks_exact(8)
+            .map(|p| {
+                let c = |i: usize| f16_to_f32(u16::from_le_bytes([p[i * 2], p[i * 2 + 1]]));
+                Rgba::new(c(0), c(1), c(2), c(3))
+            })
+            .collect())
+    }
+
     /// Transfer one section of a texture to another. 
     pub fn blit(
         &self,
@@ -135,7 +343,7 @@ impl Texture {
             wgpu::Extent3d {
                 width: src.width(),
                 height: src.height(),
-                depth_or_array_layers: 0,
+                depth_or_array_layers: 1,
             }
         )
     }
@@ -143,8 +351,8 @@ impl Texture {
     /// Note that the rect is only used for its origin and height which are in wgpu coordinates.
     fn copy (
         texture: &Texture,
-        size: Size2D<u32, ScreenSpace>,
         origin: Point2D<u32, ScreenSpace>,
+        layer: u32,
         queue: &wgpu::Queue,
         t_pixels: &[u8],
         bytes_per_row: u32,
@@ -157,7 +365,7 @@ impl Texture {
                 origin: wgpu::Origin3d {
                     x: origin.x,
                     y: origin.y,
-                    z: 0
+                    z: layer
                 },
                 aspect: wgpu::TextureAspect::All
             },
@@ -165,7 +373,7 @@ impl Texture {
             wgpu::ImageDataLayout {
                 offset: 0,
                 bytes_per_row: std::num::NonZeroU32::new(bytes_per_row),
-                rows_per_image: std::num::NonZeroU32::new(size.height),
+                rows_per_image: std::num::NonZeroU32::new(extent.height),
             },
             extent, 
         )
@@ -180,3 +388,67 @@ impl Bind for Texture {
         }
     }
 }
+
+/// Convert the bits of a half precision float to a f32
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let mantissa = (bits & 0x3ff) as f32;
+    match exponent {
+        // Subnormal
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color::Rgba8, painter::tests::headless};
+
+    /// A 3x2 texture, so a row is 12 bytes and gets padded when it's copied to a buffer
+    fn readable_texture(paint: &Painter) -> Texture {
+        paint.texture(Size2D::new(3, 2), wgpu::TextureFormat::Rgba8Unorm, wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC, None, false)
+    }
+
+    #[test]
+    #[ignore = "needs a gpu"]
+    fn read_round_trip() {
+        let paint = headless().expect("No adapter");
+        let texture = readable_texture(&paint);
+        let pixels: Vec<Rgba8> = (0..6).map(|i| Rgba8::new(i * 10, i * 10 + 1, i * 10 + 2, 255)).collect();
+        Texture::fill(&texture, &pixels, &paint.device);
+        let bytes = texture.read(&paint.device).unwrap();
+        assert_eq!(bytes.len(), 3 * 2 * 4);
+        assert_eq!(Rgba8::align(&bytes), pixels.as_slice());
+    }
+
+    #[test]
+    #[ignore = "needs a gpu"]
+    fn transfer_readback() {
+        let paint = headless().expect("No adapter");
+        let texture = readable_texture(&paint);
+        Texture::clear(&texture, Rgba8::BLACK, &paint.device);
+        // A 2x1 rect in the bottom right corner. The row length comes from the rect, not the texture
+        let red = Rgba8::new(255, 0, 0, 255);
+        Texture::transfer(&texture, &[red, red], Rect::new(Point2D::new(1, 1), Size2D::new(2, 1)), &paint.device);
+        let bytes = texture.read(&paint.device).unwrap();
+        let black = Rgba8::BLACK;
+        assert_eq!(Rgba8::align(&bytes), &[black, black, black, black, red, red]);
+    }
+
+    #[test]
+    fn mip_levels() {
+        assert_eq!(Texture::mip_levels_for(Size2D::new(1, 1)), 1);
+        assert_eq!(Texture::mip_levels_for(Size2D::new(256, 256)), 9);
+        // The longest side decides the chain
+        assert_eq!(Texture::mip_levels_for(Size2D::new(256, 16)), 9);
+        assert_eq!(Texture::mip_levels_for(Size2D::new(1, 1024)), 11);
+        // Sizes that aren't a power of two round down at each level
+        assert_eq!(Texture::mip_levels_for(Size2D::new(300, 200)), 9);
+        assert_eq!(Texture::mip_levels_for(Size2D::new(5, 3)), 3);
+        assert_eq!(Texture::mip_levels_for(Size2D::new(0, 0)), 1);
+    }
+}
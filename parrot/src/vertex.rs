@@ -6,6 +6,19 @@ pub enum VertexFormat {
     Floatx3,
     Floatx4,
     Uint32,
+    Uint8x4,
+    /// Four bytes normalised to 0.0..1.0 in the shader. Useful for packed colors
+    Unorm8x4,
+    /// Four bytes normalised to -1.0..1.0 in the shader
+    Snorm8x4,
+    Sint32,
+    Sint32x2,
+    Sint32x3,
+    Sint32x4,
+    /// Two half floats, read as `vec2<f32>` in the shader
+    Float16x2,
+    /// Four half floats, read as `vec4<f32>` in the shader
+    Float16x4,
 }
 
 // wgpu conversion
@@ -24,6 +37,15 @@ impl VertexFormat {
             VertexFormat::Floatx3 => wgpu::VertexFormat::Float32x3,
             VertexFormat::Floatx4 => wgpu::VertexFormat::Float32x4,
             VertexFormat::Uint32 => wgpu::VertexFormat::Uint32,
+            VertexFormat::Uint8x4 => wgpu::VertexFormat::Uint8x4,
+            VertexFormat::Unorm8x4 => wgpu::VertexFormat::Unorm8x4,
+            VertexFormat::Snorm8x4 => wgpu::VertexFormat::Snorm8x4,
+            VertexFormat::Sint32 => wgpu::VertexFormat::Sint32,
+            VertexFormat::Sint32x2 => wgpu::VertexFormat::Sint32x2,
+            VertexFormat::Sint32x3 => wgpu::VertexFormat::Sint32x3,
+            VertexFormat::Sint32x4 => wgpu::VertexFormat::Sint32x4,
+            VertexFormat::Float16x2 => wgpu::VertexFormat::Float16x2,
+            VertexFormat::Float16x4 => wgpu::VertexFormat::Float16x4,
         }
     }
 
@@ -34,16 +56,39 @@ impl VertexFormat {
             VertexFormat::Floatx3 => 12,
             VertexFormat::Floatx4 => 16,
             VertexFormat::Uint32 => 4,
+            VertexFormat::Uint8x4 => 4,
+            VertexFormat::Unorm8x4 => 4,
+            VertexFormat::Snorm8x4 => 4,
+            VertexFormat::Sint32 => 4,
+            VertexFormat::Sint32x2 => 8,
+            VertexFormat::Sint32x3 => 12,
+            VertexFormat::Sint32x4 => 16,
+            VertexFormat::Float16x2 => 4,
+            VertexFormat::Float16x4 => 8,
         }
     }
 }
 
+/// The vertex buffer slot per instance data is bound to. The per vertex data is always in slot 0
+pub const INSTANCE_BUFFER_SLOT: u32 = 1;
+
+/// Returns the buffer layouts for a pipeline, indexed by slot. The instance layout, if there is one, is at [`INSTANCE_BUFFER_SLOT`]
+pub fn buffer_layouts<'a>(vertex_layout: &'a VertexLayout, instance_layout: Option<&'a VertexLayout>) -> Vec<wgpu::VertexBufferLayout<'a>> {
+    let mut layouts = vec![vertex_layout.to_wgpu()];
+    if let Some(instance_layout) = instance_layout {
+        layouts.push(instance_layout.to_wgpu());
+    }
+    layouts
+}
+
 /// Represents a vertex layout and easily able to be converted to a [wgpu::VertexBufferLayout]
 #[derive(Debug, Clone)]
 pub struct VertexLayout {
     /// Vertex attributes
     wgpu_attrs: Vec<wgpu::VertexAttribute>,
     size: usize,
+    /// Whether the buffer is stepped per vertex or per instance
+    step_mode: wgpu::VertexStepMode,
 }
 
 impl VertexLayout {
@@ -52,24 +97,36 @@ impl VertexLayout {
         Self {
             wgpu_attrs: vec![],
             size: 0,
+            step_mode: wgpu::VertexStepMode::Vertex,
         }
     }
 
     pub fn to_wgpu(&self) -> wgpu::VertexBufferLayout {
         wgpu::VertexBufferLayout {
             array_stride: self.size as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Vertex,
+            step_mode: self.step_mode,
             attributes: self.wgpu_attrs.as_slice(),
         }
     }
 
     /// Convert from an array of VertexFormat to a VertexLayout
     pub fn from(vformats: &[VertexFormat]) -> Self {
+        Self::with_locations(vformats, 0)
+    }
+
+    /// Convert from an array of VertexFormat to a per instance VertexLayout. The shader locations start at `first_location` so they don't overlap the vertex layout
+    pub fn instance(vformats: &[VertexFormat], first_location: u32) -> Self {
+        let mut vl = Self::with_locations(vformats, first_location);
+        vl.step_mode = wgpu::VertexStepMode::Instance;
+        vl
+    }
+
+    fn with_locations(vformats: &[VertexFormat], first_location: u32) -> Self {
         let mut vl = Self::empty();
 
         for vfmt in vformats {
             vl.wgpu_attrs.push(wgpu::VertexAttribute {
-                shader_location: vl.wgpu_attrs.len() as u32,
+                shader_location: first_location + vl.wgpu_attrs.len() as u32,
                 offset: vl.size as wgpu::BufferAddress,
                 format: vfmt.to_wgpu(),
             });
@@ -85,4 +142,58 @@ impl<'a> From<&'a VertexLayout> for wgpu::VertexBufferLayout<'a> {
     fn from(vl: &'a VertexLayout) -> Self {
         vl.to_wgpu()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORMATS: [VertexFormat; 14] = [
+        VertexFormat::Floatx1, VertexFormat::Floatx2, VertexFormat::Floatx3, VertexFormat::Floatx4,
+        VertexFormat::Uint32, VertexFormat::Uint8x4, VertexFormat::Unorm8x4, VertexFormat::Snorm8x4,
+        VertexFormat::Sint32, VertexFormat::Sint32x2, VertexFormat::Sint32x3, VertexFormat::Sint32x4,
+        VertexFormat::Float16x2, VertexFormat::Float16x4,
+    ];
+
+    #[test]
+    fn sizes_match_wgpu() {
+        for format in FORMATS {
+            assert_eq!(format.bytesize() as u64, format.to_wgpu().size(), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn layout_offsets() {
+        let layout = VertexLayout::from(&[VertexFormat::Floatx3, VertexFormat::Unorm8x4, VertexFormat::Float16x2, VertexFormat::Sint32x2]);
+        let wgpu = layout.to_wgpu();
+        let offsets: Vec<_> = wgpu.attributes.iter().map(|a| a.offset).collect();
+        let locations: Vec<_> = wgpu.attributes.iter().map(|a| a.shader_location).collect();
+        assert_eq!(offsets, vec![0, 12, 16, 20]);
+        assert_eq!(locations, vec![0, 1, 2, 3]);
+        assert_eq!(wgpu.array_stride, 28);
+        assert_eq!(wgpu.step_mode, wgpu::VertexStepMode::Vertex);
+    }
+
+    #[test]
+    fn instance_locations_follow_vertex() {
+        let layout = VertexLayout::instance(&[VertexFormat::Floatx2, VertexFormat::Uint8x4], 3);
+        let wgpu = layout.to_wgpu();
+        let locations: Vec<_> = wgpu.attributes.iter().map(|a| a.shader_location).collect();
+        assert_eq!(locations, vec![3, 4]);
+        assert_eq!(wgpu.attributes[1].offset, 8);
+        assert_eq!(wgpu.step_mode, wgpu::VertexStepMode::Instance);
+    }
+
+    #[test]
+    fn instance_buffer_slot() {
+        let vertex = VertexLayout::from(&[VertexFormat::Floatx3, VertexFormat::Floatx2]);
+        let instance = VertexLayout::instance(&[VertexFormat::Floatx4], 2);
+        assert_eq!(buffer_layouts(&vertex, None).len(), 1);
+
+        let layouts = buffer_layouts(&vertex, Some(&instance));
+        assert_eq!(layouts.len(), 2);
+        assert_eq!(layouts[0].step_mode, wgpu::VertexStepMode::Vertex);
+        let slot = &layouts[INSTANCE_BUFFER_SLOT as usize];
+        assert_eq!(slot.step_mode, wgpu::VertexStepMode::Instance);
+        assert_eq!(slot.attributes[0].shader_location, 2);
+    }
+}
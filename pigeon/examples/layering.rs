@@ -1,8 +1,8 @@
 extern crate winit;
 use euclid::Size2D;
 use pigeon_2d::graphics::primative::Rectangle;
-use pigeon_2d::graphics::Rgba;
-use pigeon_2d::pigeon::{draw, add_triangle, Pigeon};
+use pigeon_2d::graphics::{Rgba, SolidQuad};
+use pigeon_2d::pigeon::{draw, Pigeon};
 use winit::event::{Event, WindowEvent};
 use winit::event_loop::ControlFlow;
 
@@ -36,6 +36,8 @@ fn main() {
     let rect = Rectangle::new((0.0, 0.0, 0.0), (100.0, 100.0), Rgba::GREEN);
     let rect2 = Rectangle::new((0.0, 0.0, -1.0), (90.0, 30.0), Rgba::BLUE);
     let rect3 = Rectangle::new((0.0, 0.0, 1.0), (300.0, 20.0), Rgba::RED);
+    // Drawn by the quad pipeline, but still layered between the rectangles
+    let quad = SolidQuad::new((0.0, 0.0, -0.5), (20.0, 200.0), Rgba::WHITE);
 
     // Initiate the event loop
     event_loop.run(move |event, _, control_flow| {
@@ -60,7 +62,7 @@ fn main() {
                             size,
                             wgpu::PresentMode::Fifo,
                             p.paint.preferred_format(),
-                        );
+                        ).unwrap();
                         let size = euclid::Size2D::new(size.width as f32, size.height as f32);
                         p.update_size(size);
                     }
@@ -70,7 +72,10 @@ fn main() {
             Event::RedrawRequested(_) => {
                 // Time to draw our shape :D
                 draw(&mut p, |cont| {
-                    add_triangle(cont, vec![&rect, &rect2, &rect3])
+                    cont.add(&rect);
+                    cont.add(&rect2);
+                    cont.add(&rect3);
+                    cont.add(&quad);
                 })
             }
             _ => (),
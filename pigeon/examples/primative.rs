@@ -2,7 +2,7 @@ extern crate winit;
 use winit::event_loop::ControlFlow;
 use winit::event::{WindowEvent, Event};
 use pigeon_2d::pigeon::{Pigeon, add_triangle, draw};
-use pigeon_2d::graphics::{Rectangle, Triangle};
+use pigeon_2d::graphics::{Rectangle, Triangle, Circle, Ellipse, Line, Polyline, LineCap};
 use pigeon_2d::graphics::Rgba;
 use euclid::Size2D;
 
@@ -27,6 +27,10 @@ fn main() {
     let rect2 = Rectangle::new((-450.0, 0.0, 0.0), (300.0, 20.0), Rgba::BLUE);
     let rect3 = Rectangle::new((450.0, 0.0, 0.0), (300.0, 20.0), Rgba::RED);
     let tri = Triangle::new((0.0, 20.0, 0.0), (-10.0, 0.0, 0.0), (10.0, 0.0, 0.0), (0.0, 200.0, 0.0), Rgba::BLACK);
+    let circle = Circle::new((-200.0, 150.0, 0.0), 50.0, Rgba::RED);
+    let ellipse = Ellipse::new((200.0, 150.0, 0.0), (80.0, 40.0), Rgba::BLUE).with_segments(32);
+    let line = Line::new((-300.0, -250.0, 0.0), (300.0, -250.0, 0.0), 4.0, Rgba::WHITE).with_cap(LineCap::Square);
+    let polyline = Polyline::new(vec![(-100.0, 50.0, 0.0).into(), (0.0, 100.0, 0.0).into(), (100.0, 50.0, 0.0).into()], 8.0, Rgba::GREEN);
 
     // Initiate the event loop
     event_loop.run(move |event, _, control_flow| {
@@ -45,7 +49,7 @@ fn main() {
                     // Update the surface if resized
                     WindowEvent::Resized(size) => {
                         let size = euclid::Size2D::new(size.width, size.height);
-                        p.paint.configure(size, wgpu::PresentMode::Fifo, p.paint.preferred_format());
+                        p.paint.configure(size, wgpu::PresentMode::Fifo, p.paint.preferred_format()).unwrap();
                         let size = euclid::Size2D::new(size.width as f32, size.height as f32);
                         p.update_size(size);
                     }
@@ -54,7 +58,7 @@ fn main() {
             },
             Event::RedrawRequested(_) => {
                 // Time to draw our shape :D
-                draw(&mut p, |cont| add_triangle(cont, vec![&rect, &rect2, &rect3, &tri]))
+                draw(&mut p, |cont| add_triangle(cont, vec![&rect, &rect2, &rect3, &tri, &circle, &ellipse, &line, &polyline]))
             }
             _ => ()
         }
@@ -63,7 +63,7 @@ fn main() {
                     // Update the surface if resized
                     WindowEvent::Resized(size) => {
                         let size = euclid::Size2D::new(size.width, size.height);
-                        p.paint.configure(size, wgpu::PresentMode::Fifo, p.paint.preferred_format());
+                        p.paint.configure(size, wgpu::PresentMode::Fifo, p.paint.preferred_format()).unwrap();
                         let size = euclid::Size2D::new(size.width as f32, size.height as f32);
                         p.update_size(size);
                     }
@@ -0,0 +1,69 @@
+extern crate winit;
+extern crate pigeon_parrot as parrot;
+use winit::event_loop::ControlFlow;
+use winit::event::{WindowEvent, Event};
+use pigeon_2d::pigeon::{Pigeon, add_text, draw};
+use pigeon_2d::graphics::{Text, Rgba};
+use euclid::Size2D;
+
+// Draws some text. Run with the path to a ttf or otf font:
+// cargo run --example text -- path/to/font.ttf
+fn main() {
+    env_logger::builder().filter_level(log::LevelFilter::Info).init();
+
+    // Load the font file
+    let path = std::env::args().nth(1).expect("Pass the path to a ttf or otf font");
+    let font_bytes = std::fs::read(path).expect("Failed to read the font");
+
+    // Create an event loop
+    let event_loop = winit::event_loop::EventLoop::new();
+    // Create a window to draw to
+    let window = winit::window::WindowBuilder::new().with_title("Text :D").build(&event_loop).unwrap();
+
+    // Create a wgpu instance
+    let instance = wgpu::Instance::new(wgpu::Backends::all());
+    let surface = unsafe { instance.create_surface(&window) };
+
+    // Get the size of the window
+    let winsize = window.inner_size();
+
+    let mut p = Pigeon::new(surface, &instance, Size2D::new(winsize.width as f32, winsize.height as f32), 1);
+
+    // Fonts are loaded by the text pipeline so their glyphs can share its atlas
+    let font = p.text.load_font(&font_bytes).unwrap();
+
+    let title = Text::new("Hello pigeon!", (-200.0, 50.0, 0.0), 48.0, font.clone());
+    let body = Text::new("Text is drawn from a glyph atlas\nthat grows as new glyphs are used", (-200.0, -20.0, 0.0), 24.0, font)
+        .with_color(Rgba::new(1.0, 0.8, 0.2, 1.0));
+
+    // Initiate the event loop
+    event_loop.run(move |event, _, control_flow| {
+        // Only update the event loop if input is recieved
+        *control_flow = ControlFlow::Wait;
+
+        match event {
+            // Window event
+            Event::WindowEvent { event: win_event, .. } => {
+                match win_event {
+                    // Close if a close request is detected
+                    WindowEvent::CloseRequested => {
+                        println!("The close button was pressed; stopping");
+                        *control_flow = ControlFlow::Exit
+                    },
+                    // Update the surface if resized
+                    WindowEvent::Resized(size) => {
+                        let size = euclid::Size2D::new(size.width, size.height);
+                        p.paint.configure(size, wgpu::PresentMode::Fifo, p.paint.preferred_format()).unwrap();
+                        let size = euclid::Size2D::new(size.width as f32, size.height as f32);
+                        p.update_size(size);
+                    }
+                    _ => ()
+                }
+            },
+            Event::RedrawRequested(_) => {
+                draw(&mut p, |cont| add_text(cont, vec![&title, &body]))
+            }
+            _ => ()
+        }
+    });
+}
@@ -27,7 +27,7 @@ fn main() {
     let mut rect2 = Rectangle::new((-450.0, 0.0, 0.0), (40.0, 40.0), Rgba::BLUE);
     let mut rect3 = Rectangle::new((450.0, 0.0, 0.0), (40.0, 40.0), Rgba::RED);
     let mut tri = Triangle::new((0.0, 20.0, 0.0), (-10.0, 0.0, 0.0), (10.0, 0.0, 0.0), (0.0, 100.0, 0.0), Rgba::BLACK);
-    let mut tri2 = tri.clone();
+    let mut tri2 = tri;
 
     rect2.rotate(Rotation3D::around_z(Angle::degrees(45.0)));
 
@@ -57,7 +57,7 @@ fn main() {
                     // Update the surface if resized
                     WindowEvent::Resized(size) => {
                         let size = euclid::Size2D::new(size.width, size.height);
-                        p.paint.configure(size, wgpu::PresentMode::Fifo, p.paint.preferred_format());
+                        p.paint.configure(size, wgpu::PresentMode::Fifo, p.paint.preferred_format()).unwrap();
                         let size = euclid::Size2D::new(size.width as f32, size.height as f32);
                         p.update_size(size);
                     }
@@ -0,0 +1,126 @@
+use crate::pigeon::OPENGL_TO_WGPU_MATRIX;
+use euclid::{Angle, Point2D, Size2D, Transform2D, Transform3D, Vector2D};
+use parrot::transform::{ScreenSpace, WorldSpace};
+
+/// A 2D orthographic camera. Produces the transform used by pigeon's pipelines and converts points between the world and the screen.
+///
+/// Screen points are in pixels with the origin in the top left and y going down, so they can be compared against cursor positions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera2D {
+    /// The point in the world at the centre of the screen
+    pub position: Point2D<f32, WorldSpace>,
+    /// How much the world is magnified. Zooming scales around the camera's position
+    pub zoom: f32,
+    /// The rotation of the camera
+    pub rotation: Angle<f32>,
+    /// The size of the area the camera draws to
+    pub viewport_size: Size2D<f32, WorldSpace>,
+}
+
+impl Camera2D {
+    /// Create a camera centred on the world origin
+    pub fn new(viewport_size: impl Into<Size2D<f32, WorldSpace>>) -> Self {
+        Self {
+            position: Point2D::origin(),
+            zoom: 1.0,
+            rotation: Angle::zero(),
+            viewport_size: viewport_size.into(),
+        }
+    }
+
+    /// Set the position of the camera
+    pub fn with_position(mut self, position: impl Into<Point2D<f32, WorldSpace>>) -> Self {
+        self.position = position.into();
+        self
+    }
+
+    /// Set the zoom of the camera
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    /// Set the rotation of the camera
+    pub fn with_rotation(mut self, rotation: Angle<f32>) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Moves the world so the camera's position is at the origin, then rotates and zooms around it
+    fn view(&self) -> Transform2D<f32, WorldSpace, WorldSpace> {
+        Transform2D::translation(-self.position.x, -self.position.y)
+            .then_rotate(-self.rotation)
+            .then_scale(self.zoom, self.zoom)
+    }
+
+    /// The transform from the world to the screen used by pigeon's pipelines
+    pub fn transform(&self) -> Transform3D<f32, WorldSpace, ScreenSpace> {
+        let (w, h) = (self.viewport_size.width, self.viewport_size.height);
+        let ortho: Transform3D<f32, WorldSpace, ScreenSpace> = Transform3D::ortho(-w / 2.0, w / 2.0, -h / 2.0, h / 2.0, 50.0, -50.0);
+        self.view().to_3d().then(&OPENGL_TO_WGPU_MATRIX).then(&ortho)
+    }
+
+    /// Convert a point in the world to a pixel on the screen
+    pub fn world_to_screen(&self, point: Point2D<f32, WorldSpace>) -> Point2D<f32, ScreenSpace> {
+        let view = self.view().transform_point(point);
+        Point2D::new(
+            view.x + self.viewport_size.width / 2.0,
+            self.viewport_size.height / 2.0 - view.y,
+        )
+    }
+
+    /// Convert a pixel on the screen to a point in the world. Returns [`None`] if the zoom is 0
+    pub fn screen_to_world(&self, point: Point2D<f32, ScreenSpace>) -> Option<Point2D<f32, WorldSpace>> {
+        let view = Point2D::new(
+            point.x - self.viewport_size.width / 2.0,
+            self.viewport_size.height / 2.0 - point.y,
+        );
+        self.view().inverse().map(|inv| inv.transform_point(view))
+    }
+
+    /// Move the camera
+    pub fn translate(&mut self, translation: Vector2D<f32, WorldSpace>) {
+        self.position += translation;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use euclid::Point3D;
+
+    fn close(a: Point2D<f32, WorldSpace>, b: Point2D<f32, WorldSpace>) -> bool {
+        (a - b).length() < 1e-3
+    }
+
+    #[test]
+    fn screen_round_trip() {
+        let camera = Camera2D::new((800.0, 600.0))
+            .with_position((120.0, -40.0))
+            .with_zoom(2.5)
+            .with_rotation(Angle::degrees(30.0));
+        for point in [Point2D::new(0.0, 0.0), Point2D::new(120.0, -40.0), Point2D::new(-300.0, 75.5)] {
+            let back = camera.screen_to_world(camera.world_to_screen(point)).unwrap();
+            assert!(close(point, back), "{:?} came back as {:?}", point, back);
+        }
+    }
+
+    #[test]
+    fn position_is_screen_centre() {
+        let camera = Camera2D::new((800.0, 600.0)).with_position((50.0, 50.0)).with_zoom(3.0);
+        assert_eq!(camera.world_to_screen(Point2D::new(50.0, 50.0)), Point2D::new(400.0, 300.0));
+        // y goes down on the screen
+        assert_eq!(camera.world_to_screen(Point2D::new(50.0, 60.0)), Point2D::new(400.0, 270.0));
+        assert!(camera.with_zoom(0.0).screen_to_world(Point2D::new(0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn transform_fills_viewport() {
+        let camera = Camera2D::new((800.0, 600.0)).with_position((100.0, 0.0));
+        let transform = camera.transform();
+        let centre = transform.transform_point3d(Point3D::new(100.0, 0.0, 0.0)).unwrap();
+        assert!(centre.x.abs() < 1e-5 && centre.y.abs() < 1e-5);
+        let corner = transform.transform_point3d(Point3D::new(500.0, 300.0, 0.0)).unwrap();
+        assert!((corner.x - 1.0).abs() < 1e-5 && (corner.y - 1.0).abs() < 1e-5);
+    }
+}
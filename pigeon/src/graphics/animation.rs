@@ -0,0 +1,200 @@
+use super::{AtlasRegion, Breakdown, Drawable, Sprite, Texture};
+use crate::pipeline::quad::{QuadPipe, QuadVertex};
+use euclid::{Point3D, Rect, Size2D};
+use parrot::transform::{ObjectSpace, ScreenSpace, WorldSpace};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// How an [`AnimatedSprite`] behaves when it reaches its last frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Playback {
+    /// Start again from the first frame
+    #[default]
+    Loop,
+    /// Stay on the last frame and finish
+    Once,
+}
+
+/// A [`Sprite`] that steps through frames of a sprite sheet. Call [`AnimatedSprite::update`] each frame to advance it
+pub struct AnimatedSprite {
+    /// The sprite being animated. Its region is replaced by the current frame when drawn
+    pub sprite: Sprite,
+    /// The region of each frame on the sheet
+    frames: Vec<AtlasRegion>,
+    /// How long each frame is shown
+    pub frame_duration: Duration,
+    /// What happens when the last frame is reached
+    pub playback: Playback,
+    playhead: Playhead,
+}
+
+impl AnimatedSprite {
+    /// Create an animated sprite from a sheet laid out in a grid. Frames are read left to right, top to bottom
+    pub fn from_grid(
+        origin: impl Into<Point3D<f32, WorldSpace>>,
+        size: impl Into<Size2D<f32, ObjectSpace>>,
+        sheet: Rc<Texture>,
+        cols: u32,
+        rows: u32,
+        frame_duration: Duration,
+    ) -> Self {
+        let frames = grid_rects(sheet.size(), cols, rows);
+        Self::from_rects(origin, size, sheet, &frames, frame_duration)
+    }
+
+    /// Create an animated sprite from the pixel rects of each frame on the sheet
+    pub fn from_rects(
+        origin: impl Into<Point3D<f32, WorldSpace>>,
+        size: impl Into<Size2D<f32, ObjectSpace>>,
+        sheet: Rc<Texture>,
+        frames: &[Rect<u32, ScreenSpace>],
+        frame_duration: Duration,
+    ) -> Self {
+        Self {
+            frames: frame_regions(sheet.size(), frames),
+            sprite: Sprite::new(origin, size, sheet),
+            frame_duration,
+            playback: Playback::default(),
+            playhead: Playhead::default(),
+        }
+    }
+
+    /// Set how the animation behaves when it reaches its last frame
+    pub fn with_playback(mut self, playback: Playback) -> Self {
+        self.playback = playback;
+        self
+    }
+
+    /// Advance the animation by `dt`
+    pub fn update(&mut self, dt: Duration) {
+        self.playhead.advance(dt, self.frames.len(), self.frame_duration, self.playback);
+    }
+
+    /// Go back to the first frame
+    pub fn reset(&mut self) {
+        self.playhead = Playhead::default();
+    }
+
+    /// Returns true once a [`Playback::Once`] animation has shown its last frame
+    pub fn is_finished(&self) -> bool {
+        self.playhead.finished
+    }
+
+    /// The index of the current frame
+    pub fn frame(&self) -> usize {
+        self.playhead.current
+    }
+
+    /// The number of frames in the animation
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// The region of the sheet for the current frame
+    pub fn region(&self) -> AtlasRegion {
+        self.frames.get(self.playhead.current).copied().unwrap_or_default()
+    }
+}
+
+/// The pixel rects of a sheet of the given size split into a grid, left to right, top to bottom
+fn grid_rects(sheet_size: Size2D<u32, ScreenSpace>, cols: u32, rows: u32) -> Vec<Rect<u32, ScreenSpace>> {
+    let frame_size = Size2D::new(sheet_size.width / cols.max(1), sheet_size.height / rows.max(1));
+    (0..rows)
+        .flat_map(|row| (0..cols).map(move |col| (col, row)))
+        .map(|(col, row)| Rect::new((frame_size.width * col, frame_size.height * row).into(), frame_size))
+        .collect()
+}
+
+/// Converts the pixel rects of each frame to regions of a sheet of the given size
+fn frame_regions(sheet_size: Size2D<u32, ScreenSpace>, frames: &[Rect<u32, ScreenSpace>]) -> Vec<AtlasRegion> {
+    // Inset each frame so neighbouring frames don't bleed in when sampled
+    frames.iter().map(|rect| AtlasRegion::from_pixels(*rect, sheet_size, true)).collect()
+}
+
+/// Keeps track of which frame an animation is on
+#[derive(Debug, Clone, Copy, Default)]
+struct Playhead {
+    current: usize,
+    elapsed: Duration,
+    finished: bool,
+}
+
+impl Playhead {
+    fn advance(&mut self, dt: Duration, frame_count: usize, frame_duration: Duration, playback: Playback) {
+        if self.finished || frame_count < 2 || frame_duration.is_zero() {
+            return;
+        }
+        self.elapsed += dt;
+        while self.elapsed >= frame_duration && !self.finished {
+            self.elapsed -= frame_duration;
+            if self.current + 1 < frame_count {
+                self.current += 1;
+            } else {
+                match playback {
+                    Playback::Loop => self.current = 0,
+                    Playback::Once => self.finished = true,
+                }
+            }
+        }
+    }
+}
+
+impl Drawable for AnimatedSprite {
+    type Pipeline = QuadPipe;
+
+    fn breakdown(&self) -> Breakdown<QuadVertex> {
+        self.sprite.breakdown_region(self.region())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn grid_frames() {
+        let sheet = Size2D::new(64, 32);
+        let rects = grid_rects(sheet, 4, 2);
+        assert_eq!(rects.len(), 8);
+        assert_eq!(rects[1], Rect::new((16, 0).into(), (16, 16).into()));
+        assert_eq!(rects[4], Rect::new((0, 16).into(), (16, 16).into()));
+
+        // Frames are inset by half a texel
+        let regions = frame_regions(sheet, &rects);
+        assert_eq!(regions[0], AtlasRegion { min: (0.5 / 64.0, 0.5 / 32.0), max: (15.5 / 64.0, 15.5 / 32.0) });
+        assert_eq!(regions[7], AtlasRegion { min: (48.5 / 64.0, 16.5 / 32.0), max: (63.5 / 64.0, 31.5 / 32.0) });
+    }
+
+    #[test]
+    fn stepping_loops() {
+        let mut playhead = Playhead::default();
+        playhead.advance(Duration::from_millis(250), 8, FRAME, Playback::Loop);
+        assert_eq!(playhead.current, 2);
+        // The 50ms left over carries on, so this passes six frames and wraps to the start
+        playhead.advance(Duration::from_millis(600), 8, FRAME, Playback::Loop);
+        assert_eq!(playhead.current, 0);
+        playhead.advance(Duration::from_millis(50), 8, FRAME, Playback::Loop);
+        assert_eq!(playhead.current, 1);
+        assert!(!playhead.finished);
+    }
+
+    #[test]
+    fn stepping_once_finishes() {
+        let mut playhead = Playhead::default();
+        playhead.advance(Duration::from_secs(10), 8, FRAME, Playback::Once);
+        assert_eq!(playhead.current, 7);
+        assert!(playhead.finished);
+        playhead.advance(FRAME, 8, FRAME, Playback::Once);
+        assert_eq!(playhead.current, 7);
+    }
+
+    #[test]
+    fn single_frames_dont_step() {
+        let mut playhead = Playhead::default();
+        playhead.advance(Duration::from_secs(1), 1, FRAME, Playback::Loop);
+        playhead.advance(Duration::from_secs(1), 8, Duration::ZERO, Playback::Loop);
+        assert_eq!(playhead.current, 0);
+    }
+}
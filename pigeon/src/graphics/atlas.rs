@@ -0,0 +1,250 @@
+use super::Texture;
+use euclid::{Point2D, Rect, Size2D};
+use parrot::{
+    color::Rgba8,
+    transform::ScreenSpace,
+    Painter, Sampler,
+};
+use std::rc::Rc;
+
+/// Errors that can occur when packing a [`TextureAtlas`]
+#[derive(Debug, thiserror::Error)]
+pub enum AtlasError {
+    #[error("No images were given to pack")]
+    Empty,
+    #[error("Image of size {0:?} is larger than the max atlas size {1:?}")]
+    TooLarge(Size2D<u32, ScreenSpace>, Size2D<u32, ScreenSpace>),
+    #[error("Images don't fit in the max atlas size {0:?}")]
+    Full(Size2D<u32, ScreenSpace>),
+    #[error("Image has {found} pixels but its size needs {expected}")]
+    WrongPixelCount { expected: usize, found: usize },
+}
+
+/// A region of a [`TextureAtlas`] in uv coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasRegion {
+    /// Top left uv coordinate
+    pub min: (f32, f32),
+    /// Bottom right uv coordinate
+    pub max: (f32, f32),
+}
+
+impl AtlasRegion {
+    /// The region covering an entire texture
+    pub const FULL: Self = Self {
+        min: (0.0, 0.0),
+        max: (1.0, 1.0),
+    };
+
+    /// Converts a rect in pixels to uv coordinates on a texture of the given size. If `inset` is true the edges are moved in to
+    /// the centre of the outer texels so neighbouring pixels don't bleed in when sampled
+    pub fn from_pixels(rect: Rect<u32, ScreenSpace>, texture_size: Size2D<u32, ScreenSpace>, inset: bool) -> Self {
+        let (w, h) = (texture_size.width as f32, texture_size.height as f32);
+        let inset = if inset { 0.5 } else { 0.0 };
+        Self {
+            min: ((rect.min_x() as f32 + inset) / w, (rect.min_y() as f32 + inset) / h),
+            max: ((rect.max_x() as f32 - inset) / w, (rect.max_y() as f32 - inset) / h),
+        }
+    }
+
+    /// Returns true if the two regions overlap
+    pub fn intersects(&self, other: &AtlasRegion) -> bool {
+        self.min.0 < other.max.0
+            && other.min.0 < self.max.0
+            && self.min.1 < other.max.1
+            && other.min.1 < self.max.1
+    }
+}
+
+impl Default for AtlasRegion {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+/// Packs rectangles into rows (shelves) from top to bottom
+#[derive(Debug)]
+pub(crate) struct ShelfPacker {
+    max_size: Size2D<u32, ScreenSpace>,
+    x: u32,
+    y: u32,
+    shelf_height: u32,
+}
+
+impl ShelfPacker {
+    pub(crate) fn new(max_size: Size2D<u32, ScreenSpace>) -> Self {
+        Self {
+            max_size,
+            x: 0,
+            y: 0,
+            shelf_height: 0,
+        }
+    }
+
+    /// Find a spot for a rectangle of the given size
+    pub(crate) fn pack(&mut self, size: Size2D<u32, ScreenSpace>) -> Result<Point2D<u32, ScreenSpace>, AtlasError> {
+        if size.width > self.max_size.width || size.height > self.max_size.height {
+            return Err(AtlasError::TooLarge(size, self.max_size));
+        }
+        // Start a new shelf if this one is full
+        if self.x + size.width > self.max_size.width {
+            self.y += self.shelf_height;
+            self.x = 0;
+            self.shelf_height = 0;
+        }
+        if self.y + size.height > self.max_size.height {
+            return Err(AtlasError::Full(self.max_size));
+        }
+
+        let point = Point2D::new(self.x, self.y);
+        self.x += size.width;
+        self.shelf_height = self.shelf_height.max(size.height);
+        Ok(point)
+    }
+}
+
+/// Multiple images packed into a single texture. Sprites created with [`super::Sprite::from_atlas`] share the atlas' texture,
+/// so they can be drawn together without switching bind groups
+#[derive(Debug)]
+pub struct TextureAtlas {
+    /// The texture all the images are packed into
+    pub texture: Rc<Texture>,
+    /// The region of each image, in the order they were given
+    regions: Vec<AtlasRegion>,
+}
+
+impl TextureAtlas {
+    /// Pack the images into a new atlas no bigger than `max_size`.
+    pub fn new(
+        paint: &Painter,
+        images: &[(Size2D<u32, ScreenSpace>, &[Rgba8])],
+        max_size: Size2D<u32, ScreenSpace>,
+        sampler: Rc<Sampler>,
+        name: &str,
+    ) -> Result<Self, AtlasError> {
+        log::info!("Creating texture atlas >> Name: {} || Images: {} || Max size: {:?}", name, images.len(), max_size);
+        let rects = Self::pack(images, max_size)?;
+
+        // Only make the texture as big as it needs to be
+        let size = rects
+            .iter()
+            .fold(Size2D::zero(), |size: Size2D<u32, ScreenSpace>, rect| {
+                size.max(Size2D::new(rect.max_x(), rect.max_y()))
+            });
+
+        let texture = paint.texture(
+            size,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            Some(name),
+            false,
+        );
+        for ((_, pixels), rect) in images.iter().zip(rects.iter()) {
+            parrot::Texture::transfer(&texture, pixels, *rect, &paint.device);
+        }
+
+        let regions = rects
+            .iter()
+            .map(|rect| AtlasRegion::from_pixels(*rect, size, false))
+            .collect();
+
+        Ok(Self {
+            texture: Rc::new(Texture::new(texture, sampler, name)),
+            regions,
+        })
+    }
+
+    /// Work out where each image goes in the atlas. The rects are returned in the same order as the images.
+    fn pack(
+        images: &[(Size2D<u32, ScreenSpace>, &[Rgba8])],
+        max_size: Size2D<u32, ScreenSpace>,
+    ) -> Result<Vec<Rect<u32, ScreenSpace>>, AtlasError> {
+        if images.is_empty() {
+            return Err(AtlasError::Empty);
+        }
+        for (size, pixels) in images {
+            if size.area() as usize != pixels.len() {
+                return Err(AtlasError::WrongPixelCount {
+                    expected: size.area() as usize,
+                    found: pixels.len(),
+                });
+            }
+        }
+
+        // Packing the tallest images first wastes less space
+        let mut order: Vec<usize> = (0..images.len()).collect();
+        order.sort_by(|a, b| images[*b].0.height.cmp(&images[*a].0.height));
+
+        let mut packer = ShelfPacker::new(max_size);
+        let mut rects = vec![Rect::zero(); images.len()];
+        for i in order {
+            let size = images[i].0;
+            rects[i] = Rect::new(packer.pack(size)?, size);
+        }
+        Ok(rects)
+    }
+
+    /// Get the region of the image at `index`
+    pub fn region(&self, index: usize) -> Option<AtlasRegion> {
+        self.regions.get(index).copied()
+    }
+
+    /// Get the regions of all the images in the atlas
+    pub fn regions(&self) -> &[AtlasRegion] {
+        &self.regions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packed_rects_dont_overlap() {
+        let sizes = [(16, 16), (8, 24), (32, 4), (5, 5), (20, 10), (7, 13), (16, 16)];
+        let pixels: Vec<Vec<Rgba8>> = sizes.iter().map(|(w, h)| vec![Rgba8::WHITE; w * h]).collect();
+        let images: Vec<(Size2D<u32, ScreenSpace>, &[Rgba8])> = sizes
+            .iter()
+            .zip(pixels.iter())
+            .map(|((w, h), p)| (Size2D::new(*w as u32, *h as u32), p.as_slice()))
+            .collect();
+        let max_size = Size2D::new(40, 64);
+
+        let rects = TextureAtlas::pack(&images, max_size).unwrap();
+        assert_eq!(rects.len(), images.len());
+        for (i, rect) in rects.iter().enumerate() {
+            // Rects keep the order and size of their image and stay inside the atlas
+            assert_eq!(rect.size, images[i].0);
+            assert!(rect.max_x() <= max_size.width && rect.max_y() <= max_size.height);
+            for other in &rects[i + 1..] {
+                assert!(!rect.intersects(other), "{:?} overlaps {:?}", rect, other);
+            }
+        }
+    }
+
+    #[test]
+    fn pack_rejects_bad_images() {
+        let pixels = vec![Rgba8::WHITE; 16];
+        assert!(matches!(TextureAtlas::pack(&[], Size2D::new(8, 8)), Err(AtlasError::Empty)));
+        assert!(matches!(
+            TextureAtlas::pack(&[(Size2D::new(4, 3), pixels.as_slice())], Size2D::new(8, 8)),
+            Err(AtlasError::WrongPixelCount { expected: 12, found: 16 })
+        ));
+        assert!(matches!(
+            TextureAtlas::pack(&[(Size2D::new(16, 1), pixels.as_slice())], Size2D::new(8, 8)),
+            Err(AtlasError::TooLarge(..))
+        ));
+        assert!(matches!(
+            TextureAtlas::pack(&[(Size2D::new(4, 4), pixels.as_slice()); 5], Size2D::new(8, 8)),
+            Err(AtlasError::Full(..))
+        ));
+    }
+
+    #[test]
+    fn regions_from_pixels() {
+        let region = AtlasRegion::from_pixels(Rect::new(Point2D::new(2, 4), Size2D::new(4, 4)), Size2D::new(8, 8), false);
+        assert_eq!(region, AtlasRegion { min: (0.25, 0.5), max: (0.75, 1.0) });
+        assert!(region.intersects(&AtlasRegion::FULL));
+        assert!(!region.intersects(&AtlasRegion { min: (0.0, 0.0), max: (0.25, 0.5) }));
+    }
+}
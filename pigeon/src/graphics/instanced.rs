@@ -0,0 +1,129 @@
+use super::{AtlasRegion, Breakdown, Drawable, SolidQuad, Sprite, Texture};
+use crate::pipeline::instanced::{InstancedQuadPipe, QuadInstance};
+use euclid::{Point3D, Rotation3D, Size2D, Transform3D, Translation3D};
+use parrot::{
+    color::Rgba,
+    transform::{ObjectSpace, WorldSpace},
+};
+use std::rc::Rc;
+
+/// A rectangle drawn with the [`InstancedQuadPipe`] pipeline. Its position, size and rotation are applied on the gpu, so it's cheaper
+/// than a [`Sprite`] or [`SolidQuad`] when drawing thousands of them. Uses the same position and size system as [`Sprite`]
+pub struct InstancedQuad {
+    /// The centre of the quad
+    pub origin: Point3D<f32, WorldSpace>,
+    /// The size of the quad
+    pub size: Size2D<f32, ObjectSpace>,
+    /// The rotation of the quad
+    pub rotation: Rotation3D<f32, ObjectSpace, ObjectSpace>,
+    /// The texture of the quad. The pipeline's white texture is used if there is none
+    pub texture: Option<Rc<Texture>>,
+    /// The region of the texture the quad uses
+    pub region: AtlasRegion,
+    /// The color the texture is multiplied by
+    pub color: Rgba,
+}
+
+impl InstancedQuad {
+    /// Create a new solid colored quad
+    pub fn new(
+        origin: impl Into<Point3D<f32, WorldSpace>>,
+        size: impl Into<Size2D<f32, ObjectSpace>>,
+        color: Rgba,
+    ) -> Self {
+        Self {
+            origin: origin.into(),
+            size: size.into(),
+            rotation: Rotation3D::identity(),
+            texture: None,
+            region: AtlasRegion::FULL,
+            color,
+        }
+    }
+
+    /// Create a new textured quad
+    pub fn textured(
+        origin: impl Into<Point3D<f32, WorldSpace>>,
+        size: impl Into<Size2D<f32, ObjectSpace>>,
+        texture: Rc<Texture>,
+    ) -> Self {
+        Self {
+            texture: Some(texture),
+            ..Self::new(origin, size, Rgba::WHITE)
+        }
+    }
+
+    /// Only draw a region of the texture
+    pub fn with_region(mut self, region: AtlasRegion) -> Self {
+        self.region = region;
+        self
+    }
+
+    /// Rotate the quad
+    pub fn rotate(&mut self, rotation: Rotation3D<f32, ObjectSpace, ObjectSpace>) {
+        self.rotation = rotation;
+    }
+
+    /// Translate the quad
+    pub fn translate(&mut self, translation: Translation3D<f32, WorldSpace, WorldSpace>) {
+        self.origin = translation.transform_point3d(&self.origin);
+    }
+
+    /// Set the quads size
+    pub fn scale(&mut self, size: Size2D<f32, ObjectSpace>) {
+        self.size = size;
+    }
+
+    /// Set the color the texture is multiplied by
+    pub fn set_color(&mut self, color: Rgba) {
+        self.color = color;
+    }
+
+    /// Returns the matrix that moves a 1x1 quad centred on the origin to this quad's size, rotation and position
+    pub fn model(&self) -> Transform3D<f32, ObjectSpace, WorldSpace> {
+        // Same as scaling, rotating then translating but without multiplying the matrices together
+        let r = self.rotation.to_transform();
+        let (w, h) = (self.size.width, self.size.height);
+        Transform3D::new(
+            r.m11 * w, r.m12 * w, r.m13 * w, 0.0,
+            r.m21 * h, r.m22 * h, r.m23 * h, 0.0,
+            r.m31, r.m32, r.m33, 0.0,
+            self.origin.x, self.origin.y, self.origin.z, 1.0,
+        )
+    }
+}
+
+impl From<&Sprite> for InstancedQuad {
+    fn from(sprite: &Sprite) -> Self {
+        Self {
+            origin: sprite.origin,
+            size: sprite.size,
+            rotation: sprite.rotation,
+            texture: Some(sprite.texture.clone()),
+            region: sprite.uv_region(),
+            color: sprite.tint,
+        }
+    }
+}
+
+impl From<&SolidQuad> for InstancedQuad {
+    fn from(quad: &SolidQuad) -> Self {
+        Self {
+            rotation: quad.rotation,
+            ..Self::new(quad.origin, quad.size, quad.color)
+        }
+    }
+}
+
+impl Drawable for InstancedQuad {
+    type Pipeline = InstancedQuadPipe;
+
+    fn breakdown(&self) -> Breakdown<QuadInstance> {
+        // A single instance, the quad's geometry lives in the pipeline
+        Breakdown {
+            vertices: vec![QuadInstance::new(self.model(), self.region, self.color)],
+            indicies: vec![],
+            texture: self.texture.clone(),
+        }
+    }
+}
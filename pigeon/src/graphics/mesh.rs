@@ -0,0 +1,124 @@
+use super::{Breakdown, Drawable, Texture};
+use crate::pipeline::Render;
+use parrot::{painter::RenderPassExtention, IndexBuffer, Painter, VertexBuffer};
+use std::{marker::PhantomData, rc::Rc};
+use wgpu::RenderPass;
+
+/// Geometry uploaded to the gpu once and reused every frame. Use it for large static graphics, like backgrounds, that would
+/// otherwise be broken down and re-uploaded by their pipeline each draw. The buffers are only rebuilt after [`CachedMesh::invalidate`]
+#[derive(Debug)]
+pub struct CachedMesh<V: bytemuck::Pod + bytemuck::Zeroable + Clone + Copy> {
+    vertex_buffer: VertexBuffer,
+    index_buffer: IndexBuffer,
+    index_count: u32,
+    texture: Option<Rc<Texture>>,
+    dirty: bool,
+    _marker: PhantomData<V>,
+}
+
+impl<V: bytemuck::Pod + bytemuck::Zeroable + Clone + Copy> CachedMesh<V> {
+    /// Breakdown a drawable and upload it
+    pub fn new<D>(paint: &Painter, drawable: &D) -> Self
+    where
+        D: Drawable,
+        D::Pipeline: Render<Vertex = V>,
+    {
+        Self::from_breakdown(paint, drawable.breakdown())
+    }
+
+    /// Upload a breakdown
+    pub fn from_breakdown(paint: &Painter, breakdown: Breakdown<V>) -> Self {
+        log::info!("Creating cached mesh >> Vertices: {} || Indices: {}", breakdown.vertices.len(), breakdown.indicies.len());
+        Self {
+            vertex_buffer: paint.vertex_buffer(breakdown.vertices.as_slice(), Some("Cached mesh vertex buffer")),
+            index_buffer: paint.index_buffer(breakdown.indicies.as_slice(), Some("Cached mesh index buffer")),
+            index_count: breakdown.indicies.len() as u32,
+            texture: breakdown.texture,
+            dirty: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Mark the mesh as changed so the next [`CachedMesh::update`] re-uploads it
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Returns true if the mesh has been invalidated and not updated since
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Re-upload the mesh from the drawable if it has been invalidated. Returns true if anything was uploaded
+    pub fn update<D>(&mut self, paint: &mut Painter, drawable: &D) -> bool
+    where
+        D: Drawable,
+        D::Pipeline: Render<Vertex = V>,
+    {
+        if !self.dirty {
+            return false;
+        }
+        let breakdown = drawable.breakdown();
+        log::info!("Updating cached mesh >> Vertices: {} || Indices: {}", breakdown.vertices.len(), breakdown.indicies.len());
+        if let Some(v) = paint.update_vertex_buffer(&breakdown.vertices, &mut self.vertex_buffer) {
+            self.vertex_buffer = v;
+        }
+        self.index_count = breakdown.indicies.len() as u32;
+        if let Some(i) = paint.update_index_buffer(breakdown.indicies, &mut self.index_buffer) {
+            self.index_buffer = i;
+        }
+        self.texture = breakdown.texture;
+        self.dirty = false;
+        true
+    }
+
+    /// The texture of the mesh, if it has one
+    pub fn texture(&self) -> Option<&Rc<Texture>> {
+        self.texture.as_ref()
+    }
+
+    /// The vertex buffer of the mesh
+    pub fn vertex_buffer(&self) -> &VertexBuffer {
+        &self.vertex_buffer
+    }
+
+    /// The index buffer of the mesh
+    pub fn index_buffer(&self) -> &IndexBuffer {
+        &self.index_buffer
+    }
+
+    /// Draw the mesh. The pipeline and any bindings it needs must already be set on the pass
+    pub fn draw<'a>(&'a self, pass: &mut RenderPass<'a>) {
+        if self.index_count == 0 {
+            return;
+        }
+        pass.set_parrot_vertex_buffer(&self.vertex_buffer);
+        pass.set_parrot_index_buffer(&self.index_buffer);
+        pass.draw_parrot_indexed_checked(&self.index_buffer, 0..self.index_count, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::Rectangle;
+    use parrot::color::Rgba;
+
+    #[test]
+    #[ignore = "needs a gpu"]
+    fn update_only_when_dirty() {
+        let instance = wgpu::Instance::new(wgpu::Backends::all());
+        let mut paint = pollster::block_on(Painter::headless(&instance, 1)).expect("No adapter");
+        let rect = Rectangle::new((0.0, 0.0, 0.0), (2.0, 2.0), Rgba::WHITE);
+        let mut mesh = CachedMesh::new(&paint, &rect);
+        assert!(!mesh.is_dirty());
+        assert!(!mesh.update(&mut paint, &rect));
+
+        mesh.invalidate();
+        assert!(mesh.is_dirty());
+        assert!(mesh.update(&mut paint, &rect));
+        assert!(!mesh.is_dirty());
+        assert!(!mesh.update(&mut paint, &rect));
+        assert!(mesh.texture().is_none());
+    }
+}
@@ -4,11 +4,32 @@ pub mod primative;
 pub mod texture;
 /// A basic textured rectangle
 pub mod sprite;
+/// Sprites animated from a sprite sheet
+pub mod animation;
+/// Packs multiple images into a single texture
+pub mod atlas;
+/// Text drawn with a font
+pub mod text;
+/// Static geometry uploaded once and reused
+pub mod mesh;
+/// A textured rectangle that scales without stretching its border
+pub mod nine_slice;
+/// Shapes drawn from a signed distance field
+pub mod sdf;
+/// Rectangles moved into place on the gpu, for drawing lots of them
+pub mod instanced;
 
 // Re-export colors
 pub use parrot::color::{Bgra8, Rgba8, Rgba};
 pub use texture::Texture;
-pub use sprite::Sprite;
+pub use sprite::{Sprite, SolidQuad};
+pub use animation::{AnimatedSprite, Playback};
+pub use atlas::{TextureAtlas, AtlasRegion, AtlasError};
+pub use text::{Text, Font, FontError};
+pub use mesh::CachedMesh;
+pub use nine_slice::{NineSlice, NineSliceError};
+pub use sdf::SdfSprite;
+pub use instanced::InstancedQuad;
 pub use primative::*;
 
 use crate::pipeline::Render;
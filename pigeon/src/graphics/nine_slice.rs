@@ -0,0 +1,198 @@
+use super::{Breakdown, Drawable, Texture};
+use crate::pipeline::quad::{QuadPipe, QuadVertex};
+use euclid::{Point3D, SideOffsets2D, Size2D, Translation3D};
+use parrot::{
+    color::Rgba,
+    transform::{ObjectSpace, ScreenSpace, WorldSpace},
+};
+use std::rc::Rc;
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum NineSliceError {
+    #[error("Border {border:?} doesn't fit inside a texture of size {size:?}")]
+    BorderTooLarge { border: SideOffsets2D<u32, ScreenSpace>, size: Size2D<u32, ScreenSpace> },
+}
+
+/// A textured rectangle split into nine parts so it can be scaled without stretching its border. The corners are drawn at their size
+/// in the texture, the edges are stretched along one axis and the centre along both. Useful for UI panels and buttons.
+/// Uses the same position and size system as [`super::Sprite`] and the [`QuadPipe`] pipeline
+pub struct NineSlice {
+    /// The centre of the rectangle
+    pub origin: Point3D<f32, WorldSpace>,
+    /// The size of the rectangle
+    pub size: Size2D<f32, ObjectSpace>,
+    /// The texture to slice
+    pub texture: Rc<Texture>,
+    /// Width of each side of the border in texture pixels
+    border: SideOffsets2D<u32, ScreenSpace>,
+    /// The color the texture is multiplied by. White leaves the texture unchanged
+    pub tint: Rgba,
+}
+
+impl NineSlice {
+    /// Create a new nine slice. Returns [`NineSliceError::BorderTooLarge`] if the border is wider or taller than the texture
+    pub fn new(
+        origin: impl Into<Point3D<f32, WorldSpace>>,
+        size: impl Into<Size2D<f32, ObjectSpace>>,
+        texture: Rc<Texture>,
+        border: SideOffsets2D<u32, ScreenSpace>,
+    ) -> Result<Self, NineSliceError> {
+        check_border(texture.size(), border)?;
+        Ok(Self {
+            origin: origin.into(),
+            size: size.into(),
+            texture,
+            border,
+            tint: Rgba::WHITE,
+        })
+    }
+
+    /// Returns the border in texture pixels
+    pub fn border(&self) -> SideOffsets2D<u32, ScreenSpace> {
+        self.border
+    }
+
+    /// Set the border. Returns [`NineSliceError::BorderTooLarge`] if it doesn't fit inside the texture
+    pub fn set_border(&mut self, border: SideOffsets2D<u32, ScreenSpace>) -> Result<(), NineSliceError> {
+        check_border(self.texture.size(), border)?;
+        self.border = border;
+        Ok(())
+    }
+
+    /// Update the texture. Returns [`NineSliceError::BorderTooLarge`] if the border doesn't fit inside it
+    pub fn update_texture(&mut self, texture: Rc<Texture>) -> Result<(), NineSliceError> {
+        check_border(texture.size(), self.border)?;
+        self.texture = texture;
+        Ok(())
+    }
+
+    /// Translate the nine slice
+    pub fn translate(&mut self, translation: Translation3D<f32, WorldSpace, WorldSpace>) {
+        self.origin = translation.transform_point3d(&self.origin);
+    }
+
+    /// Set the size
+    pub fn scale(&mut self, size: Size2D<f32, ObjectSpace>) {
+        self.size = size;
+    }
+
+    /// Set the color the texture is multiplied by
+    pub fn set_tint(&mut self, tint: Rgba) {
+        self.tint = tint;
+    }
+}
+
+impl Drawable for NineSlice {
+    type Pipeline = QuadPipe;
+
+    fn breakdown(&self) -> Breakdown<QuadVertex> {
+        Breakdown {
+            vertices: slice_vertices(self.origin, self.size, self.texture.size(), self.border, self.tint),
+            indicies: slice_indicies(),
+            texture: Some(self.texture.clone()),
+        }
+    }
+}
+
+/// Returns an error if the border is wider or taller than a texture of the given size
+fn check_border(size: Size2D<u32, ScreenSpace>, border: SideOffsets2D<u32, ScreenSpace>) -> Result<(), NineSliceError> {
+    if border.horizontal() > size.width || border.vertical() > size.height {
+        return Err(NineSliceError::BorderTooLarge { border, size });
+    }
+    Ok(())
+}
+
+/// The 4x4 grid of vertices for a nine slice, row by row from the top left
+fn slice_vertices(
+    origin: Point3D<f32, WorldSpace>,
+    size: Size2D<f32, ObjectSpace>,
+    tex_size: Size2D<u32, ScreenSpace>,
+    border: SideOffsets2D<u32, ScreenSpace>,
+    tint: Rgba,
+) -> Vec<QuadVertex> {
+    let tex_size = tex_size.to_f32();
+    let b = border;
+    let border: SideOffsets2D<f32, ScreenSpace> = SideOffsets2D::new(b.top as f32, b.right as f32, b.bottom as f32, b.left as f32);
+    // Shrink the border if the rectangle is smaller than it
+    let sx = (size.width / border.horizontal()).min(1.0);
+    let sy = (size.height / border.vertical()).min(1.0);
+
+    let left = origin.x - size.width / 2.0;
+    let right = origin.x + size.width / 2.0;
+    let top = origin.y + size.height / 2.0;
+    let bottom = origin.y - size.height / 2.0;
+    // Grid lines from left to right and top to bottom
+    let xs = [left, left + border.left * sx, right - border.right * sx, right];
+    let ys = [top, top - border.top * sy, bottom + border.bottom * sy, bottom];
+    let us = [0.0, border.left / tex_size.width, 1.0 - border.right / tex_size.width, 1.0];
+    let vs = [0.0, border.top / tex_size.height, 1.0 - border.bottom / tex_size.height, 1.0];
+
+    let tint = (tint.r, tint.g, tint.b, tint.a);
+    let mut vertices = Vec::with_capacity(16);
+    for (y, v) in ys.iter().zip(vs) {
+        for (x, u) in xs.iter().zip(us) {
+            vertices.push(QuadVertex::new_from_tuple((*x, *y, origin.z), (u, v)).with_color(tint));
+        }
+    }
+    vertices
+}
+
+/// Two triangles for each of the nine quads in the grid from [`slice_vertices`]
+fn slice_indicies() -> Vec<u16> {
+    let mut indicies = Vec::with_capacity(54);
+    for row in 0..3u16 {
+        for col in 0..3u16 {
+            let tl = row * 4 + col;
+            let (tr, bl, br) = (tl + 1, tl + 4, tl + 5);
+            indicies.extend_from_slice(&[tl, tr, br, tl, br, bl]);
+        }
+    }
+    indicies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corner_uvs() {
+        let border = SideOffsets2D::new(4, 8, 2, 4);
+        let vertices = slice_vertices(Point3D::origin(), Size2D::new(100.0, 50.0), Size2D::new(32, 16), border, Rgba::WHITE);
+        assert_eq!(vertices.len(), 16);
+        assert_eq!(slice_indicies().len(), 54);
+        assert!(slice_indicies().iter().all(|i| *i < 16));
+
+        // The outer corners cover the whole texture and the inner ones sit on the border
+        assert_eq!(vertices[0].tex_coords, [0.0, 0.0]);
+        assert_eq!(vertices[3].tex_coords, [1.0, 0.0]);
+        assert_eq!(vertices[12].tex_coords, [0.0, 1.0]);
+        assert_eq!(vertices[15].tex_coords, [1.0, 1.0]);
+        assert_eq!(vertices[5].tex_coords, [4.0 / 32.0, 4.0 / 16.0]);
+        assert_eq!(vertices[10].tex_coords, [1.0 - 8.0 / 32.0, 1.0 - 2.0 / 16.0]);
+
+        // Corners keep their size in texture pixels
+        assert_eq!(vertices[5].pos[0] - vertices[0].pos[0], 4.0);
+        assert_eq!(vertices[0].pos[1] - vertices[5].pos[1], 4.0);
+        assert_eq!(vertices[15].pos[0] - vertices[10].pos[0], 8.0);
+        assert_eq!(vertices[10].pos[1] - vertices[15].pos[1], 2.0);
+    }
+
+    #[test]
+    fn small_slices_shrink_the_border() {
+        // The border is 12 pixels across but the slice is only 6 wide
+        let border = SideOffsets2D::new(4, 8, 4, 4);
+        let vertices = slice_vertices(Point3D::origin(), Size2D::new(6.0, 100.0), Size2D::new(32, 32), border, Rgba::WHITE);
+        assert_eq!(vertices[1].pos[0] - vertices[0].pos[0], 2.0);
+        assert_eq!(vertices[3].pos[0] - vertices[2].pos[0], 4.0);
+        // The uvs don't change
+        assert_eq!(vertices[1].tex_coords[0], 4.0 / 32.0);
+    }
+
+    #[test]
+    fn border_must_fit() {
+        let size = Size2D::new(8, 8);
+        assert!(check_border(size, SideOffsets2D::new(4, 4, 4, 4)).is_ok());
+        assert!(check_border(size, SideOffsets2D::new(0, 5, 0, 4)).is_err());
+        assert!(check_border(size, SideOffsets2D::new(9, 0, 0, 0)).is_err());
+    }
+}
@@ -2,7 +2,7 @@ use parrot::{
     transform::{ObjectSpace, WorldSpace}, Rgba,
 };
 use euclid::{
-    Point3D, Size2D, Rect, Rotation3D, Translation3D, Transform3D,
+    Point3D, Size2D, Rect, Rotation3D, Translation3D, Transform3D, Vector2D,
 };
 use super::{Drawable, Breakdown};
 use crate::pipeline::{triangle::TriangleVertex, TrianglePipe};
@@ -11,7 +11,7 @@ use crate::pipeline::{triangle::TriangleVertex, TrianglePipe};
 
 /// A Basic rectangle, represented by an origin (the centre of the rectangle) and a size relative to the origin.
 /// Uses the [`TrianglePipe`] pipeline
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 pub struct Rectangle {
     /// The centre of hte rectangle
     pub origin: Point3D<f32, WorldSpace>,
@@ -109,7 +109,7 @@ impl Drawable for Rectangle {
 }
 
 /// A triangle represented by three points and an origin
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 pub struct Triangle {
     /// First point
     pub point_a: Point3D<f32, ObjectSpace>,
@@ -188,4 +188,497 @@ impl Drawable for Triangle {
             texture: None,
         }
     }
-}
\ No newline at end of file
+}
+/// Picks a segment count that keeps a curve of the given radius looking smooth
+fn default_segments(radius: f32) -> u32 {
+    ((radius.abs().sqrt() * 4.0).ceil() as u32).clamp(8, 256)
+}
+
+/// Tessellates an ellipse into a triangle fan. The rim goes counter clockwise so the triangles face forwards
+fn ellipse_fan(
+    origin: Point3D<f32, WorldSpace>,
+    radii: Size2D<f32, ObjectSpace>,
+    rotation: Rotation3D<f32, ObjectSpace, ObjectSpace>,
+    color: Rgba,
+    segments: u32,
+) -> Breakdown<TriangleVertex> {
+    if radii.width == 0.0 || radii.height == 0.0 {
+        return Breakdown::empty();
+    }
+    let segments = segments.max(3);
+    let color = (color.r, color.g, color.b, color.a);
+
+    let mut vertices = Vec::with_capacity(segments as usize + 1);
+    vertices.push(TriangleVertex::new_from_tuple(origin.to_tuple(), color));
+    for i in 0..segments {
+        let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+        let (sin, cos) = angle.sin_cos();
+        let mut vert: Point3D<f32, ObjectSpace> = Point3D::new(cos * radii.width, sin * radii.height, 0.0);
+        vert = rotation.transform_point3d(vert);
+        vert.x += origin.x;
+        vert.y += origin.y;
+        vert.z = origin.z;
+        vertices.push(TriangleVertex::new_from_tuple(vert.to_tuple(), color));
+    }
+
+    let mut indicies = Vec::with_capacity(segments as usize * 3);
+    for i in 1..=segments as u16 {
+        let next = if i == segments as u16 { 1 } else { i + 1 };
+        indicies.extend_from_slice(&[0, i, next]);
+    }
+
+    Breakdown {
+        vertices,
+        indicies,
+        texture: None,
+    }
+}
+
+/// A circle represented by an origin (its centre) and a radius. Drawn as a triangle fan with `segments` rim vertices.
+/// Uses the [`TrianglePipe`] pipeline
+#[derive(Debug, Clone, Copy)]
+pub struct Circle {
+    /// The centre of the circle
+    pub origin: Point3D<f32, WorldSpace>,
+    /// The radius of the circle
+    pub radius: f32,
+    /// The color of the circle
+    pub color: Rgba,
+    /// The amount of vertices around the edge of the circle
+    pub segments: u32,
+}
+
+impl Circle {
+    /// Create a new circle. The segment count is picked based on the radius
+    pub fn new(origin: impl Into<Point3D<f32, WorldSpace>>, radius: f32, color: impl Into<Rgba>) -> Self {
+        Self {
+            origin: origin.into(),
+            radius,
+            color: color.into(),
+            segments: default_segments(radius),
+        }
+    }
+
+    /// Set the amount of vertices around the edge of the circle. Clamped to at least 3
+    pub fn with_segments(mut self, segments: u32) -> Self {
+        self.segments = segments.max(3);
+        self
+    }
+
+    /// Translate the circle
+    pub fn translate(&mut self, translation: Translation3D<f32, WorldSpace, WorldSpace>) {
+        self.origin = translation.transform_point3d(&self.origin);
+    }
+}
+
+impl Drawable for Circle {
+    type Pipeline = TrianglePipe;
+
+    fn breakdown(&self) -> Breakdown<TriangleVertex> {
+        ellipse_fan(
+            self.origin,
+            Size2D::new(self.radius, self.radius),
+            Rotation3D::identity(),
+            self.color,
+            self.segments,
+        )
+    }
+}
+
+/// An ellipse represented by an origin (its centre) and the radius along each axis. Drawn as a triangle fan with `segments` rim vertices.
+/// Uses the [`TrianglePipe`] pipeline
+#[derive(Debug, Clone, Copy)]
+pub struct Ellipse {
+    /// The centre of the ellipse
+    pub origin: Point3D<f32, WorldSpace>,
+    /// The radius along the x and y axis
+    pub radii: Size2D<f32, ObjectSpace>,
+    /// The rotation of the ellipse
+    pub rotation: Rotation3D<f32, ObjectSpace, ObjectSpace>,
+    /// The color of the ellipse
+    pub color: Rgba,
+    /// The amount of vertices around the edge of the ellipse
+    pub segments: u32,
+}
+
+impl Ellipse {
+    /// Create a new ellipse. The segment count is picked based on the largest radius
+    pub fn new(origin: impl Into<Point3D<f32, WorldSpace>>, radii: impl Into<Size2D<f32, ObjectSpace>>, color: impl Into<Rgba>) -> Self {
+        let radii = radii.into();
+        Self {
+            origin: origin.into(),
+            radii,
+            rotation: Rotation3D::identity(),
+            color: color.into(),
+            segments: default_segments(radii.width.abs().max(radii.height.abs())),
+        }
+    }
+
+    /// Set the amount of vertices around the edge of the ellipse. Clamped to at least 3
+    pub fn with_segments(mut self, segments: u32) -> Self {
+        self.segments = segments.max(3);
+        self
+    }
+
+    /// Rotate the ellipse
+    pub fn rotate(&mut self, rotation: Rotation3D<f32, ObjectSpace, ObjectSpace>) {
+        self.rotation = rotation;
+    }
+
+    /// Translate the ellipse
+    pub fn translate(&mut self, translation: Translation3D<f32, WorldSpace, WorldSpace>) {
+        self.origin = translation.transform_point3d(&self.origin);
+    }
+
+    /// Set the ellipses radii
+    pub fn scale(&mut self, radii: Size2D<f32, ObjectSpace>) {
+        self.radii = radii;
+    }
+}
+
+impl Drawable for Ellipse {
+    type Pipeline = TrianglePipe;
+
+    fn breakdown(&self) -> Breakdown<TriangleVertex> {
+        ellipse_fan(self.origin, self.radii, self.rotation, self.color, self.segments)
+    }
+}
+
+/// How the ends of a line are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineCap {
+    /// The line stops exactly at its end points
+    #[default]
+    Butt,
+    /// The line is extended past its end points by half its width
+    Square,
+}
+
+/// How the corners of a [`Polyline`] are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineJoin {
+    /// The outer edges are extended until they meet. Falls back to [`LineJoin::Bevel`] for very sharp corners
+    #[default]
+    Miter,
+    /// The outer edges are joined with a straight edge
+    Bevel,
+}
+
+/// Miters longer than this many half widths are drawn as bevels instead
+const MITER_LIMIT: f32 = 4.0;
+
+/// Builds up the vertices and indicies of lines
+struct LineBuilder {
+    vertices: Vec<TriangleVertex>,
+    indicies: Vec<u16>,
+    color: (f32, f32, f32, f32),
+}
+
+impl LineBuilder {
+    fn new(color: Rgba) -> Self {
+        Self {
+            vertices: vec![],
+            indicies: vec![],
+            color: (color.r, color.g, color.b, color.a),
+        }
+    }
+
+    fn vertex(&mut self, point: Vector2D<f32, WorldSpace>, z: f32) -> u16 {
+        let index = u16::try_from(self.vertices.len()).expect("Line has too many vertices for 16 bit indicies. Split it into smaller lines");
+        self.vertices.push(TriangleVertex::new_from_tuple((point.x, point.y, z), self.color));
+        index
+    }
+
+    /// Add a quad for the segment from `a` to `b`. Corners are added counter clockwise
+    fn segment(&mut self, a: Vector2D<f32, WorldSpace>, b: Vector2D<f32, WorldSpace>, half_width: f32, z: f32) {
+        let dir = (b - a).normalize();
+        let normal = Vector2D::new(-dir.y, dir.x) * half_width;
+        let start = self.vertex(a - normal, z);
+        self.vertex(b - normal, z);
+        self.vertex(b + normal, z);
+        self.vertex(a + normal, z);
+        self.indicies.extend_from_slice(&[start, start + 1, start + 2, start, start + 2, start + 3]);
+    }
+
+    /// Fill the gap on the outside of the corner at `point` between two segments
+    fn join(&mut self, prev: Vector2D<f32, WorldSpace>, point: Vector2D<f32, WorldSpace>, next: Vector2D<f32, WorldSpace>, half_width: f32, join: LineJoin, z: f32) {
+        let d1 = (point - prev).normalize();
+        let d2 = (next - point).normalize();
+        let cross = d1.cross(d2);
+        if cross.abs() <= f32::EPSILON {
+            return;
+        }
+        // The outer side of a left turn is on the right
+        let side = if cross > 0.0 { -1.0 } else { 1.0 };
+        let n1 = Vector2D::new(-d1.y, d1.x) * half_width * side;
+        let n2 = Vector2D::new(-d2.y, d2.x) * half_width * side;
+
+        let centre = self.vertex(point, z);
+        let a = self.vertex(point + n1, z);
+        let b = self.vertex(point + n2, z);
+        // Keep the triangles counter clockwise whichever side they're on
+        let (a, b) = if side > 0.0 { (b, a) } else { (a, b) };
+
+        let miter = (n1 + n2).normalize();
+        let cos = miter.dot(n1) / half_width;
+        if join == LineJoin::Miter && cos > 1.0 / MITER_LIMIT {
+            let tip = self.vertex(point + miter * (half_width / cos), z);
+            self.indicies.extend_from_slice(&[centre, a, tip, centre, tip, b]);
+        } else {
+            self.indicies.extend_from_slice(&[centre, a, b]);
+        }
+    }
+
+    fn finish(self) -> Breakdown<TriangleVertex> {
+        Breakdown {
+            vertices: self.vertices,
+            indicies: self.indicies,
+            texture: None,
+        }
+    }
+}
+
+/// Extends the ends of a line by half its width if it has square caps
+fn apply_caps(points: &mut [Vector2D<f32, WorldSpace>], half_width: f32, cap: LineCap) {
+    if cap == LineCap::Square && points.len() > 1 {
+        let last = points.len() - 1;
+        let start_dir = (points[1] - points[0]).normalize();
+        let end_dir = (points[last] - points[last - 1]).normalize();
+        points[0] -= start_dir * half_width;
+        points[last] += end_dir * half_width;
+    }
+}
+
+/// A straight line between two points with a width
+/// Uses the [`TrianglePipe`] pipeline
+#[derive(Debug, Clone, Copy)]
+pub struct Line {
+    /// The start of the line
+    pub start: Point3D<f32, WorldSpace>,
+    /// The end of the line
+    pub end: Point3D<f32, WorldSpace>,
+    /// The width of the line
+    pub width: f32,
+    /// The color of the line
+    pub color: Rgba,
+    /// How the ends of the line are drawn
+    pub cap: LineCap,
+}
+
+impl Line {
+    /// Create a new line with butt caps
+    pub fn new(start: impl Into<Point3D<f32, WorldSpace>>, end: impl Into<Point3D<f32, WorldSpace>>, width: f32, color: impl Into<Rgba>) -> Self {
+        Self {
+            start: start.into(),
+            end: end.into(),
+            width,
+            color: color.into(),
+            cap: LineCap::Butt,
+        }
+    }
+
+    /// Set how the ends of the line are drawn
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Translate the line
+    pub fn translate(&mut self, translation: Translation3D<f32, WorldSpace, WorldSpace>) {
+        self.start = translation.transform_point3d(&self.start);
+        self.end = translation.transform_point3d(&self.end);
+    }
+}
+
+impl Drawable for Line {
+    type Pipeline = TrianglePipe;
+
+    fn breakdown(&self) -> Breakdown<TriangleVertex> {
+        let mut points = [self.start.xy().to_vector(), self.end.xy().to_vector()];
+        if (points[1] - points[0]).square_length() <= f32::EPSILON || self.width <= 0.0 {
+            return Breakdown::empty();
+        }
+        let half_width = self.width / 2.0;
+        apply_caps(&mut points, half_width, self.cap);
+
+        let mut builder = LineBuilder::new(self.color);
+        builder.segment(points[0], points[1], half_width, self.start.z);
+        builder.finish()
+    }
+}
+
+/// A line through a list of points with a width. Each segment and corner adds vertices, so very long lines (more than about 8000 points)
+/// run out of 16 bit indicies and panic when broken down.
+/// Uses the [`TrianglePipe`] pipeline
+#[derive(Debug, Clone)]
+pub struct Polyline {
+    /// The points the line goes through
+    pub points: Vec<Point3D<f32, WorldSpace>>,
+    /// The width of the line
+    pub width: f32,
+    /// The color of the line
+    pub color: Rgba,
+    /// How the ends of the line are drawn
+    pub cap: LineCap,
+    /// How the corners of the line are drawn
+    pub join: LineJoin,
+}
+
+impl Polyline {
+    /// Create a new polyline with butt caps and mitered corners
+    pub fn new(points: Vec<Point3D<f32, WorldSpace>>, width: f32, color: impl Into<Rgba>) -> Self {
+        Self {
+            points,
+            width,
+            color: color.into(),
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+        }
+    }
+
+    /// Set how the ends of the line are drawn
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Set how the corners of the line are drawn
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    /// Translate the polyline
+    pub fn translate(&mut self, translation: Translation3D<f32, WorldSpace, WorldSpace>) {
+        for point in self.points.iter_mut() {
+            *point = translation.transform_point3d(point);
+        }
+    }
+}
+
+impl Drawable for Polyline {
+    type Pipeline = TrianglePipe;
+
+    fn breakdown(&self) -> Breakdown<TriangleVertex> {
+        if self.width <= 0.0 {
+            return Breakdown::empty();
+        }
+        // Drop any points that would make a zero length segment
+        let mut points: Vec<Vector2D<f32, WorldSpace>> = Vec::with_capacity(self.points.len());
+        for point in &self.points {
+            let point = point.xy().to_vector();
+            if points.last().is_none_or(|last| (point - *last).square_length() > f32::EPSILON) {
+                points.push(point);
+            }
+        }
+        if points.len() < 2 {
+            return Breakdown::empty();
+        }
+        let z = self.points[0].z;
+        let half_width = self.width / 2.0;
+        apply_caps(&mut points, half_width, self.cap);
+
+        let mut builder = LineBuilder::new(self.color);
+        for pair in points.windows(2) {
+            builder.segment(pair[0], pair[1], half_width, z);
+        }
+        for corner in points.windows(3) {
+            builder.join(corner[0], corner[1], corner[2], half_width, self.join, z);
+        }
+        builder.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fan_counts() {
+        for segments in [3, 8, 17, 256] {
+            let fan = ellipse_fan(Point3D::new(1.0, 2.0, 0.5), Size2D::new(10.0, 5.0), Rotation3D::identity(), Rgba::WHITE, segments);
+            assert_eq!(fan.vertices.len(), segments as usize + 1);
+            assert_eq!(fan.indicies.len(), segments as usize * 3);
+            assert!(fan.indicies.iter().all(|i| (*i as usize) < fan.vertices.len()));
+            // Every triangle starts at the centre
+            assert!(fan.indicies.chunks(3).all(|tri| tri[0] == 0));
+        }
+        // Fewer than 3 segments can't make a shape
+        let fan = ellipse_fan(Point3D::origin(), Size2D::new(1.0, 1.0), Rotation3D::identity(), Rgba::WHITE, 1);
+        assert_eq!(fan.vertices.len(), 4);
+        let empty = ellipse_fan(Point3D::origin(), Size2D::new(0.0, 1.0), Rotation3D::identity(), Rgba::WHITE, 8);
+        assert!(empty.vertices.is_empty() && empty.indicies.is_empty());
+    }
+
+    #[test]
+    fn circle_rim_is_on_radius() {
+        let circle = Circle::new((3.0, -4.0, 0.0), 20.0, Rgba::WHITE);
+        assert!((8..=256).contains(&circle.segments));
+        let fan = circle.breakdown();
+        for v in &fan.vertices[1..] {
+            let dist = ((v.pos[0] - 3.0).powi(2) + (v.pos[1] + 4.0).powi(2)).sqrt();
+            assert!((dist - 20.0).abs() < 1e-3);
+        }
+    }
+
+    fn positions(breakdown: &Breakdown<TriangleVertex>) -> Vec<(f32, f32)> {
+        breakdown.vertices.iter().map(|v| (v.pos[0], v.pos[1])).collect()
+    }
+
+    #[test]
+    fn line_quad_corners() {
+        let line = Line::new((0.0, 0.0, 0.0), (10.0, 0.0, 0.0), 2.0, Rgba::WHITE);
+        let quad = line.breakdown();
+        assert_eq!(positions(&quad), vec![(0.0, -1.0), (10.0, -1.0), (10.0, 1.0), (0.0, 1.0)]);
+        assert_eq!(quad.indicies, vec![0, 1, 2, 0, 2, 3]);
+
+        let capped = line.with_cap(LineCap::Square).breakdown();
+        assert_eq!(positions(&capped), vec![(-1.0, -1.0), (11.0, -1.0), (11.0, 1.0), (-1.0, 1.0)]);
+
+        assert!(Line::new((1.0, 1.0, 0.0), (1.0, 1.0, 0.0), 2.0, Rgba::WHITE).breakdown().vertices.is_empty());
+    }
+
+    #[test]
+    fn polyline_joins() {
+        // A left turn, so the outside of the corner is below and to the right
+        let points = vec![Point3D::new(0.0, 0.0, 0.0), Point3D::new(10.0, 0.0, 0.0), Point3D::new(10.0, 10.0, 0.0)];
+
+        let miter = Polyline::new(points.clone(), 2.0, Rgba::WHITE).breakdown();
+        // Two segments, then the centre, both outer corners and the tip of the miter
+        assert_eq!(miter.vertices.len(), 8 + 4);
+        assert_eq!(miter.indicies.len(), 12 + 6);
+        let tip = positions(&miter)[11];
+        assert!((tip.0 - 11.0).abs() < 1e-5 && (tip.1 + 1.0).abs() < 1e-5, "Miter tip at {:?}", tip);
+
+        let bevel = Polyline::new(points, 2.0, Rgba::WHITE).with_join(LineJoin::Bevel).breakdown();
+        assert_eq!(bevel.vertices.len(), 8 + 3);
+        assert_eq!(bevel.indicies.len(), 12 + 3);
+
+        // Straight corners and repeated points don't add a join
+        let straight = vec![Point3D::new(0.0, 0.0, 0.0), Point3D::new(5.0, 0.0, 0.0), Point3D::new(5.0, 0.0, 0.0), Point3D::new(10.0, 0.0, 0.0)];
+        let straight = Polyline::new(straight, 2.0, Rgba::WHITE).breakdown();
+        assert_eq!(straight.vertices.len(), 8);
+    }
+
+    #[test]
+    fn line_triangles_face_forwards() {
+        let points = vec![Point3D::new(0.0, 0.0, 0.0), Point3D::new(10.0, 0.0, 0.0), Point3D::new(10.0, 10.0, 0.0), Point3D::new(0.0, 10.0, 0.0)];
+        for join in [LineJoin::Miter, LineJoin::Bevel] {
+            let line = Polyline::new(points.clone(), 2.0, Rgba::WHITE).with_join(join).breakdown();
+            let pos = positions(&line);
+            for tri in line.indicies.chunks(3) {
+                let (a, b, c) = (pos[tri[0] as usize], pos[tri[1] as usize], pos[tri[2] as usize]);
+                let area = (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+                assert!(area >= 0.0, "{:?} is clockwise", tri);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "too many vertices")]
+    fn polyline_index_overflow() {
+        // Every corner is a miter, so each point adds 8 vertices
+        let points = (0..9000).map(|i| Point3D::new(i as f32, (i % 2) as f32, 0.0)).collect();
+        Polyline::new(points, 0.5, Rgba::WHITE).breakdown();
+    }
+}
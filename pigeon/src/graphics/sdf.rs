@@ -0,0 +1,147 @@
+use super::{sprite::quad_vertices, AtlasRegion, Breakdown, Drawable, Texture};
+use crate::pipeline::{quad::QuadVertex, sdf::{SdfPipe, SdfVertex}};
+use euclid::{Point3D, Rotation3D, Size2D, Translation3D};
+use parrot::{
+    color::Rgba,
+    transform::{ObjectSpace, WorldSpace},
+};
+use std::rc::Rc;
+
+/// A rectangle textured with a signed distance field, drawn with the [`SdfPipe`] pipeline. Unlike a [`super::Sprite`] the edges
+/// stay sharp when scaled up, which suits icons and large text. Uses the same position and size system as [`super::Sprite`]
+pub struct SdfSprite {
+    /// The centre of the sprite
+    pub origin: Point3D<f32, WorldSpace>,
+    /// The size of the sprite
+    pub size: Size2D<f32, ObjectSpace>,
+    /// The rotation of the sprite
+    pub rotation: Rotation3D<f32, ObjectSpace, ObjectSpace>,
+    /// The distance field. The distance is read from the red channel with the edge at 0.5
+    pub texture: Rc<Texture>,
+    /// The region of the texture the sprite uses
+    pub region: AtlasRegion,
+    /// The color inside the shape
+    pub color: Rgba,
+    /// The color of the outline
+    pub outline_color: Rgba,
+    /// Width of the outline in distance field units. 0 disables the outline
+    pub outline_width: f32,
+    /// How far either side of the edge is blended, in distance field units. Lower values give harder edges
+    pub smoothing: f32,
+}
+
+impl SdfSprite {
+    /// Create a new sdf sprite with no outline
+    pub fn new(
+        origin: impl Into<Point3D<f32, WorldSpace>>,
+        size: impl Into<Size2D<f32, ObjectSpace>>,
+        texture: Rc<Texture>,
+        color: Rgba,
+    ) -> Self {
+        Self {
+            origin: origin.into(),
+            size: size.into(),
+            rotation: Rotation3D::identity(),
+            texture,
+            region: AtlasRegion::FULL,
+            color,
+            outline_color: Rgba::TRANSPARENT,
+            outline_width: 0.0,
+            smoothing: 0.05,
+        }
+    }
+
+    /// Add an outline of the given color and width
+    pub fn with_outline(mut self, color: Rgba, width: f32) -> Self {
+        self.outline_color = color;
+        self.outline_width = width;
+        self
+    }
+
+    /// Set how far either side of the edge is blended
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Rotate the sprite
+    pub fn rotate(&mut self, rotation: Rotation3D<f32, ObjectSpace, ObjectSpace>) {
+        self.rotation = rotation;
+    }
+
+    /// Translate the sprite
+    pub fn translate(&mut self, translation: Translation3D<f32, WorldSpace, WorldSpace>) {
+        self.origin = translation.transform_point3d(&self.origin);
+    }
+
+    /// Set the sprites size
+    pub fn scale(&mut self, size: Size2D<f32, ObjectSpace>) {
+        self.size = size;
+    }
+}
+
+impl Drawable for SdfSprite {
+    type Pipeline = SdfPipe;
+
+    fn breakdown(&self) -> Breakdown<SdfVertex> {
+        let quad = quad_vertices(self.origin, self.size, self.rotation, self.region, Rgba::WHITE);
+        Breakdown {
+            vertices: sdf_vertices(quad, self.color, self.outline_color, self.smoothing, self.outline_width),
+            indicies: vec![0, 1, 3, 0, 3, 2],
+            texture: Some(self.texture.clone()),
+        }
+    }
+}
+
+/// Copies the colors and edge settings onto every vertex of a quad
+fn sdf_vertices(quad: Vec<QuadVertex>, color: Rgba, outline_color: Rgba, smoothing: f32, outline_width: f32) -> Vec<SdfVertex> {
+    let color = [color.r, color.g, color.b, color.a];
+    let outline_color = [outline_color.r, outline_color.g, outline_color.b, outline_color.a];
+    quad.into_iter()
+        .map(|v| SdfVertex {
+            pos: v.pos,
+            tex_coords: v.tex_coords,
+            color,
+            outline_color,
+            params: [smoothing, outline_width],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_vertices(vertices: &[SdfVertex]) {
+        assert_eq!(vertices.len(), 4);
+        let uvs: Vec<[f32; 2]> = vertices.iter().map(|v| v.tex_coords).collect();
+        assert_eq!(uvs, vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]]);
+        for v in vertices {
+            assert_eq!(v.color, [1.0, 0.5, 0.0, 1.0]);
+            assert_eq!(v.outline_color, [0.0, 0.0, 0.0, 0.5]);
+            assert_eq!(v.params, [0.1, 0.2]);
+        }
+    }
+
+    #[test]
+    fn style_is_on_every_vertex() {
+        let quad = quad_vertices(Point3D::origin(), Size2D::new(2.0, 2.0), Rotation3D::identity(), AtlasRegion::FULL, Rgba::WHITE);
+        let positions: Vec<[f32; 3]> = quad.iter().map(|v| v.pos).collect();
+        let vertices = sdf_vertices(quad, Rgba::new(1.0, 0.5, 0.0, 1.0), Rgba::new(0.0, 0.0, 0.0, 0.5), 0.1, 0.2);
+        assert_eq!(vertices.iter().map(|v| v.pos).collect::<Vec<_>>(), positions);
+        check_vertices(&vertices);
+    }
+
+    #[test]
+    #[ignore = "needs a gpu"]
+    fn breakdown() {
+        let texture = crate::graphics::texture::tests::blank_texture(Size2D::new(32, 32)).expect("No adapter");
+        let sprite = SdfSprite::new((0.0, 0.0, 0.0), (2.0, 2.0), texture.clone(), Rgba::new(1.0, 0.5, 0.0, 1.0))
+            .with_outline(Rgba::new(0.0, 0.0, 0.0, 0.5), 0.2)
+            .with_smoothing(0.1);
+        let breakdown = sprite.breakdown();
+        check_vertices(&breakdown.vertices);
+        assert_eq!(breakdown.indicies, vec![0, 1, 3, 0, 3, 2]);
+        assert!(Rc::ptr_eq(breakdown.texture.as_ref().unwrap(), &texture));
+    }
+}
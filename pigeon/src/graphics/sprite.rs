@@ -1,7 +1,10 @@
-use super::{Breakdown, Drawable, Texture};
+use super::{AtlasRegion, Breakdown, Drawable, Texture, TextureAtlas};
 use crate::pipeline::quad::{QuadPipe, QuadVertex};
-use euclid::{Point3D, Rotation3D, Size2D, Translation3D};
-use parrot::transform::{ObjectSpace, WorldSpace};
+use euclid::{Point3D, Rect, Rotation3D, Size2D, Translation3D};
+use parrot::{
+    color::Rgba,
+    transform::{ObjectSpace, ScreenSpace, WorldSpace},
+};
 use std::rc::Rc;
 
 /// Basic textured rectangle.
@@ -18,6 +21,15 @@ pub struct Sprite {
     pub rotation: Rotation3D<f32, ObjectSpace, ObjectSpace>,
     /// The texture of the sprite
     pub texture: Rc<Texture>,
+    /// The region of the texture the sprite uses. Ignored if there's a [`Sprite::source_rect`]
+    pub region: AtlasRegion,
+    /// The part of the texture to draw in pixels, with the origin at the top left. It's converted to uv coordinates
+    /// using the size of the texture when the sprite is drawn, so it stays correct if the texture changes
+    pub source_rect: Option<Rect<u32, ScreenSpace>>,
+    /// Moves the edges of the [`Sprite::source_rect`] in half a texel so neighbouring pixels don't bleed in
+    pub source_inset: bool,
+    /// The color the texture is multiplied by. White leaves the texture unchanged
+    pub tint: Rgba,
 }
 
 impl Sprite {
@@ -32,6 +44,23 @@ impl Sprite {
             size: size.into(),
             rotation: Rotation3D::identity(),
             texture,
+            region: AtlasRegion::FULL,
+            source_rect: None,
+            source_inset: false,
+            tint: Rgba::WHITE,
+        }
+    }
+
+    /// Create a new sprite from a region of a [`TextureAtlas`]. Sprites from the same atlas are drawn together
+    pub fn from_atlas(
+        origin: impl Into<Point3D<f32, WorldSpace>>,
+        size: impl Into<Size2D<f32, ObjectSpace>>,
+        atlas: &TextureAtlas,
+        region: AtlasRegion,
+    ) -> Self {
+        Self {
+            region,
+            ..Self::new(origin, size, atlas.texture.clone())
         }
     }
 
@@ -50,50 +79,200 @@ impl Sprite {
         self.size = size;
     }
 
+    /// Only draw part of the texture. `rect` is in pixels of the sprite's texture, with the origin at the top left.
+    /// Set `inset` to move the edges in half a texel so neighbouring pixels don't bleed in
+    pub fn set_source_rect(&mut self, rect: Rect<u32, ScreenSpace>, inset: bool) {
+        self.source_rect = Some(rect);
+        self.source_inset = inset;
+    }
+
+    /// Same as [`Sprite::set_source_rect`] but takes and returns the sprite
+    pub fn with_source_rect(mut self, rect: Rect<u32, ScreenSpace>, inset: bool) -> Self {
+        self.set_source_rect(rect, inset);
+        self
+    }
+
+    /// Set the color the texture is multiplied by
+    pub fn set_tint(&mut self, tint: Rgba) {
+        self.tint = tint;
+    }
+
     /// Update the texture of the sprite
     pub fn update_texture(&mut self, texture: Rc<Texture>) {
         self.texture = texture;
     }
+
+    /// The region of the texture that's drawn. This is the source rect converted to uv coordinates if there is one,
+    /// otherwise [`Sprite::region`]
+    pub fn uv_region(&self) -> AtlasRegion {
+        source_region(self.region, self.source_rect, self.source_inset, self.texture.size())
+    }
+}
+
+/// Converts a source rect in pixels to a region of a texture of the given size, falling back to `region` if there isn't one
+fn source_region(region: AtlasRegion, source_rect: Option<Rect<u32, ScreenSpace>>, inset: bool, texture_size: Size2D<u32, ScreenSpace>) -> AtlasRegion {
+    source_rect.map_or(region, |rect| AtlasRegion::from_pixels(rect, texture_size, inset))
 }
 
 impl Drawable for Sprite {
     type Pipeline = QuadPipe;
 
     fn breakdown(&self) -> Breakdown<QuadVertex> {
-        let mut tl: Point3D<f32, ObjectSpace> = Point3D::new(
-            -self.size.width / 2.0,
-            self.size.height / 2.0,
-            self.origin.z,
-        );
-        let mut tr: Point3D<f32, ObjectSpace> =
-            Point3D::new(self.size.width / 2.0, self.size.height / 2.0, self.origin.z);
-        let mut bl: Point3D<f32, ObjectSpace> = Point3D::new(
-            -self.size.width / 2.0,
-            -self.size.height / 2.0,
-            self.origin.z,
-        );
-        let mut br: Point3D<f32, ObjectSpace> = Point3D::new(
-            self.size.width / 2.0,
-            -self.size.height / 2.0,
-            self.origin.z,
-        );
-        // Rotate each of the points (this must be done in object space)
-        for vert in [&mut tl, &mut tr, &mut bl, &mut br] {
-            *vert = self.rotation.transform_point3d(*vert);
-            vert.x = vert.x + self.origin.x;
-            vert.y = vert.y + self.origin.y;
-        }
-        let vertices = vec![
-            QuadVertex::new_from_tuple(tl.to_tuple(), (0.0, 0.0)),
-            QuadVertex::new_from_tuple(tr.to_tuple(), (1.0, 0.0)),
-            QuadVertex::new_from_tuple(bl.to_tuple(), (0.0, 1.0)),
-            QuadVertex::new_from_tuple(br.to_tuple(), (1.0, 1.0)),
-        ];
+        self.breakdown_region(self.uv_region())
+    }
+}
 
+impl Sprite {
+    /// Breakdown the sprite using the given region of its texture
+    pub(crate) fn breakdown_region(&self, region: AtlasRegion) -> Breakdown<QuadVertex> {
         Breakdown {
-            vertices,
+            vertices: quad_vertices(self.origin, self.size, self.rotation, region, self.tint),
             indicies: vec![0, 1, 3, 0, 3, 2],
             texture: Some(self.texture.clone()),
         }
     }
 }
+
+/// A solid colored rectangle drawn with the [`QuadPipe`] pipeline, so it can be drawn in between sprites.
+/// Uses the same position and size system as [`Sprite`]
+pub struct SolidQuad {
+    /// The centre of the quad
+    pub origin: Point3D<f32, WorldSpace>,
+    /// The size of the quad
+    pub size: Size2D<f32, ObjectSpace>,
+    /// The rotation of the quad
+    pub rotation: Rotation3D<f32, ObjectSpace, ObjectSpace>,
+    /// The color of the quad
+    pub color: Rgba,
+}
+
+impl SolidQuad {
+    /// Create a new solid quad
+    pub fn new(
+        origin: impl Into<Point3D<f32, WorldSpace>>,
+        size: impl Into<Size2D<f32, ObjectSpace>>,
+        color: Rgba,
+    ) -> Self {
+        Self {
+            origin: origin.into(),
+            size: size.into(),
+            rotation: Rotation3D::identity(),
+            color,
+        }
+    }
+
+    /// Rotate the quad
+    pub fn rotate(&mut self, rotation: Rotation3D<f32, ObjectSpace, ObjectSpace>) {
+        self.rotation = rotation;
+    }
+
+    /// Translate the quad
+    pub fn translate(&mut self, translation: Translation3D<f32, WorldSpace, WorldSpace>) {
+        self.origin = translation.transform_point3d(&self.origin);
+    }
+}
+
+impl Drawable for SolidQuad {
+    type Pipeline = QuadPipe;
+
+    fn breakdown(&self) -> Breakdown<QuadVertex> {
+        // No texture, so the pipeline uses its white texture
+        Breakdown {
+            vertices: quad_vertices(self.origin, self.size, self.rotation, AtlasRegion::FULL, self.color),
+            indicies: vec![0, 1, 3, 0, 3, 2],
+            texture: None,
+        }
+    }
+}
+
+/// Creates the corners of a rotated quad centred on `origin`, in the order top left, top right, bottom left, bottom right
+pub(crate) fn quad_vertices(
+    origin: Point3D<f32, WorldSpace>,
+    size: Size2D<f32, ObjectSpace>,
+    rotation: Rotation3D<f32, ObjectSpace, ObjectSpace>,
+    region: AtlasRegion,
+    tint: Rgba,
+) -> Vec<QuadVertex> {
+    let mut tl: Point3D<f32, ObjectSpace> = Point3D::new(
+        -size.width / 2.0,
+        size.height / 2.0,
+        origin.z,
+    );
+    let mut tr: Point3D<f32, ObjectSpace> =
+        Point3D::new(size.width / 2.0, size.height / 2.0, origin.z);
+    let mut bl: Point3D<f32, ObjectSpace> = Point3D::new(
+        -size.width / 2.0,
+        -size.height / 2.0,
+        origin.z,
+    );
+    let mut br: Point3D<f32, ObjectSpace> = Point3D::new(
+        size.width / 2.0,
+        -size.height / 2.0,
+        origin.z,
+    );
+    // Rotate each of the points (this must be done in object space)
+    for vert in [&mut tl, &mut tr, &mut bl, &mut br] {
+        *vert = rotation.transform_point3d(*vert);
+        vert.x = vert.x + origin.x;
+        vert.y = vert.y + origin.y;
+    }
+    let (min, max) = (region.min, region.max);
+    let tint = (tint.r, tint.g, tint.b, tint.a);
+    vec![
+        QuadVertex::new_from_tuple(tl.to_tuple(), (min.0, min.1)).with_color(tint),
+        QuadVertex::new_from_tuple(tr.to_tuple(), (max.0, min.1)).with_color(tint),
+        QuadVertex::new_from_tuple(bl.to_tuple(), (min.0, max.1)).with_color(tint),
+        QuadVertex::new_from_tuple(br.to_tuple(), (max.0, max.1)).with_color(tint),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tint_is_on_every_vertex() {
+        let tint = Rgba::new(0.5, 0.25, 1.0, 0.75);
+        let vertices = quad_vertices(Point3D::origin(), Size2D::new(2.0, 2.0), Rotation3D::identity(), AtlasRegion::FULL, tint);
+        assert!(vertices.iter().all(|v| v.color == [0.5, 0.25, 1.0, 0.75]));
+
+        let quad = SolidQuad::new((0.0, 0.0, 0.0), (2.0, 2.0), tint).breakdown();
+        assert!(quad.texture.is_none());
+        assert!(quad.vertices.iter().all(|v| v.color == [0.5, 0.25, 1.0, 0.75]));
+    }
+
+    #[test]
+    fn vertices_are_white_by_default() {
+        let vertex = QuadVertex::new_from_tuple((0.0, 0.0, 0.0), (0.0, 0.0));
+        assert_eq!(vertex.color, [1.0, 1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn source_rect_uvs() {
+        let rect = Rect::new((16, 8).into(), (32, 16).into());
+        let size = Size2D::new(64, 32);
+        assert_eq!(source_region(AtlasRegion::FULL, None, true, size), AtlasRegion::FULL);
+        assert_eq!(source_region(AtlasRegion::FULL, Some(rect), false, size), AtlasRegion { min: (0.25, 0.25), max: (0.75, 0.75) });
+        // Half a texel in from each edge
+        assert_eq!(
+            source_region(AtlasRegion::FULL, Some(rect), true, size),
+            AtlasRegion { min: (16.5 / 64.0, 8.5 / 32.0), max: (47.5 / 64.0, 23.5 / 32.0) }
+        );
+        // The same rect on a texture twice the size covers half as much
+        assert_eq!(source_region(AtlasRegion::FULL, Some(rect), false, size * 2), AtlasRegion { min: (0.125, 0.125), max: (0.375, 0.375) });
+    }
+
+    #[test]
+    #[ignore = "needs a gpu"]
+    fn source_rect_follows_texture() {
+        let small = crate::graphics::texture::tests::blank_texture(Size2D::new(64, 32)).expect("No adapter");
+        let large = crate::graphics::texture::tests::blank_texture(Size2D::new(128, 64)).expect("No adapter");
+        let mut sprite = Sprite::new((0.0, 0.0, 0.0), (1.0, 1.0), small).with_source_rect(Rect::new((16, 8).into(), (32, 16).into()), false);
+        let uvs: Vec<[f32; 2]> = sprite.breakdown().vertices.iter().map(|v| v.tex_coords).collect();
+        assert_eq!(uvs, vec![[0.25, 0.25], [0.75, 0.25], [0.25, 0.75], [0.75, 0.75]]);
+
+        sprite.update_texture(large);
+        let uvs: Vec<[f32; 2]> = sprite.breakdown().vertices.iter().map(|v| v.tex_coords).collect();
+        assert_eq!(uvs, vec![[0.125, 0.125], [0.375, 0.125], [0.125, 0.375], [0.375, 0.375]]);
+    }
+}
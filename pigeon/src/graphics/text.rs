@@ -0,0 +1,194 @@
+use super::{Breakdown, Drawable};
+use crate::pipeline::text::{GlyphCache, TextPipe, TextVertex};
+use euclid::Point3D;
+use parrot::{color::Rgba, transform::WorldSpace};
+use std::{cell::RefCell, fmt, rc::Rc};
+
+/// Errors that can occur when loading a [`Font`]
+#[derive(Debug, thiserror::Error)]
+pub enum FontError {
+    #[error("Failed to parse font: {0}")]
+    Parse(&'static str),
+}
+
+/// A font loaded with [`TextPipe::load_font`]. Glyphs are rasterised into the pipeline's atlas as they are needed
+pub struct Font {
+    /// Unique id of the font within its pipeline
+    pub id: usize,
+    pub(crate) inner: fontdue::Font,
+    pub(crate) cache: Rc<RefCell<GlyphCache>>,
+}
+
+impl Font {
+    pub(crate) fn new(id: usize, bytes: &[u8], cache: Rc<RefCell<GlyphCache>>) -> Result<Self, FontError> {
+        let inner = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default()).map_err(FontError::Parse)?;
+        Ok(Self { id, inner, cache })
+    }
+
+    /// The distance between the baselines of two lines of text at the given size
+    pub fn line_height(&self, size: f32) -> f32 {
+        self.inner
+            .horizontal_line_metrics(size)
+            .map_or(size, |m| m.new_line_size)
+    }
+}
+
+impl fmt::Debug for Font {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Font").field("id", &self.id).finish()
+    }
+}
+
+/// A line (or lines) of text. Uses the [`TextPipe`] pipeline
+///
+/// Glyph positions are rounded to whole pixels so the text stays crisp when the camera isn't zoomed.
+/// Each glyph is four vertices, so a text can have at most 16384 visible glyphs
+#[derive(Debug, Clone)]
+pub struct Text {
+    /// The text to draw. New lines move down by the font's line height
+    pub content: String,
+    /// The left end of the first line's baseline
+    pub position: Point3D<f32, WorldSpace>,
+    /// The size of the text in pixels. Glyphs are cached at whole pixel sizes
+    pub size: f32,
+    /// The color of the text
+    pub color: Rgba,
+    /// The font the text is drawn with
+    pub font: Rc<Font>,
+}
+
+impl Text {
+    /// Create white text
+    pub fn new(
+        content: impl Into<String>,
+        position: impl Into<Point3D<f32, WorldSpace>>,
+        size: f32,
+        font: Rc<Font>,
+    ) -> Self {
+        Self {
+            content: content.into(),
+            position: position.into(),
+            size,
+            color: Rgba::WHITE,
+            font,
+        }
+    }
+
+    /// Set the color of the text
+    pub fn with_color(mut self, color: Rgba) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Set the text to draw
+    pub fn set_content(&mut self, content: impl Into<String>) {
+        self.content = content.into();
+    }
+}
+
+impl Drawable for Text {
+    type Pipeline = TextPipe;
+
+    fn breakdown(&self) -> Breakdown<TextVertex> {
+        let px = self.size.round().max(1.0);
+        let font = &self.font.inner;
+        let line_height = self.font.line_height(px);
+        let color = [self.color.r, self.color.g, self.color.b, self.color.a];
+        let mut cache = self.font.cache.borrow_mut();
+
+        let mut vertices = vec![];
+        let mut indicies = vec![];
+        // Pen position relative to the start of the first baseline
+        let (mut x, mut y) = (0.0, 0.0);
+        let mut prev = None;
+
+        for c in self.content.chars() {
+            if c == '\n' {
+                x = 0.0;
+                y -= line_height;
+                prev = None;
+                continue;
+            }
+            if let Some(p) = prev {
+                x += font.horizontal_kern(p, c, px).unwrap_or(0.0);
+            }
+
+            let index = font.lookup_glyph_index(c);
+            let metrics = font.metrics_indexed(index, px);
+            // Whitespace has no bitmap, so only advance the pen
+            if metrics.width > 0 && metrics.height > 0 {
+                let glyph = cache.glyph(self.font.id, font, index, px as u32);
+
+                // Snap the pen to a whole pixel then offset by the glyph's bearing
+                let left = (self.position.x + x).round() + metrics.xmin as f32;
+                let bottom = (self.position.y + y).round() + metrics.ymin as f32;
+                let (right, top) = (left + metrics.width as f32, bottom + metrics.height as f32);
+                let z = self.position.z;
+
+                let start = u16::try_from(vertices.len()).expect("Text has too many glyphs for 16 bit indicies. Split it into smaller texts");
+                vertices.extend([
+                    TextVertex::new([left, top, z], [0.0, 0.0], color, glyph),
+                    TextVertex::new([right, top, z], [1.0, 0.0], color, glyph),
+                    TextVertex::new([left, bottom, z], [0.0, 1.0], color, glyph),
+                    TextVertex::new([right, bottom, z], [1.0, 1.0], color, glyph),
+                ]);
+                indicies.extend([0, 1, 3, 0, 3, 2].iter().map(|i| i + start));
+            }
+
+            x += metrics.advance_width;
+            prev = Some(c);
+        }
+
+        Breakdown {
+            vertices,
+            indicies,
+            texture: None,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A font with a rectangular `H` and `i`, made for these tests
+    pub(crate) const TEST_FONT: &[u8] = include_bytes!("test_font.ttf");
+
+    fn font() -> Rc<Font> {
+        Rc::new(Font::new(0, TEST_FONT, Rc::new(RefCell::new(GlyphCache::new()))).unwrap())
+    }
+
+    #[test]
+    fn glyphs_dont_overlap() {
+        let text = Text::new("Hi", (10.0, 20.0, 0.0), 32.0, font());
+        let breakdown = text.breakdown();
+        assert_eq!(breakdown.vertices.len(), 8);
+        assert_eq!(breakdown.indicies, vec![0, 1, 3, 0, 3, 2, 4, 5, 7, 4, 7, 6]);
+
+        // Each quad goes top left, top right, bottom left, bottom right
+        let (h, i) = (&breakdown.vertices[..4], &breakdown.vertices[4..]);
+        assert_ne!(h[0].glyph, i[0].glyph);
+        for quad in [h, i] {
+            assert!(quad[0].pos[0] < quad[1].pos[0]);
+            assert!(quad[0].pos[1] > quad[2].pos[1]);
+            // Both glyphs sit on the baseline
+            assert_eq!(quad[2].pos[1], 20.0);
+        }
+        assert!(h[1].pos[0] <= i[0].pos[0]);
+    }
+
+    #[test]
+    fn whitespace_only_advances() {
+        let font = font();
+        let spaced = Text::new("H i", (0.0, 0.0, 0.0), 32.0, font.clone()).breakdown();
+        let packed = Text::new("Hi", (0.0, 0.0, 0.0), 32.0, font).breakdown();
+        assert_eq!(spaced.vertices.len(), 8);
+        assert!(spaced.vertices[4].pos[0] > packed.vertices[4].pos[0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "too many glyphs")]
+    fn index_overflow() {
+        Text::new("i".repeat(16385), (0.0, 0.0, 0.0), 8.0, font()).breakdown();
+    }
+}
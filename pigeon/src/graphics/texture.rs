@@ -4,6 +4,7 @@ use parrot::{
 };
 use euclid::Size2D;
 use std::rc::Rc;
+use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 /// ID generator to generate unique IDs
 fn get_id() -> usize {
@@ -11,12 +12,12 @@ fn get_id() -> usize {
     COUNTER.fetch_add(1, Ordering::Relaxed)
 }
 
-/// A texture containing its own [`Sampler`]
-#[derive(Debug)]
+/// A texture containing its own [`Sampler`]. Cloning a texture shares the underlying gpu texture.
+#[derive(Debug, Clone)]
 pub struct Texture {
     pub id: usize,
     pub sampler: Rc<Sampler>,
-    pub texture: parrot::Texture,
+    pub texture: Arc<parrot::Texture>,
     pub name: String,
 }
 
@@ -27,6 +28,11 @@ impl Texture {
     }
 
     pub fn new(texture: parrot::Texture, sampler: Rc<Sampler>, name: &str) -> Self {
+        Self::from_shared(Arc::new(texture), sampler, name)
+    }
+
+    /// Create a texture from a gpu texture that is already shared
+    pub fn from_shared(texture: Arc<parrot::Texture>, sampler: Rc<Sampler>, name: &str) -> Self {
         Self {
             id: get_id(),
             sampler,
@@ -34,4 +40,17 @@ impl Texture {
             name: name.to_string()
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Create a blank texture for tests that need one. Returns [`None`] if there's no adapter so those tests are skipped
+    pub(crate) fn blank_texture(size: Size2D<u32, ScreenSpace>) -> Option<Rc<Texture>> {
+        let instance = wgpu::Instance::new(wgpu::Backends::all());
+        let paint = pollster::block_on(parrot::Painter::headless(&instance, 1)).ok()?;
+        let texture = paint.texture(size, wgpu::TextureFormat::Rgba8UnormSrgb, wgpu::TextureUsages::TEXTURE_BINDING, Some("Test texture"), false);
+        let sampler = paint.sampler(wgpu::FilterMode::Nearest, wgpu::FilterMode::Nearest, Some("Test sampler"));
+        Some(Rc::new(Texture::new(texture, Rc::new(sampler), "Test texture")))
+    }
+}
@@ -21,7 +21,15 @@ pub mod pipeline;
 pub mod graphics;
 /// Contains code to manage pigeon
 pub mod pigeon;
+/// Contains the camera used to view the world
+pub mod camera;
 
-pub use pigeon::Pigeon;
+pub use pigeon::{Pigeon, PigeonConfig};
+pub use camera::Camera2D;
 pub use parrot::transform;
 extern crate pigeon_parrot as parrot;
+
+/// Returns the version of parrot pigeon was built against. Use this to check that your version of parrot is compatible.
+pub fn parrot_version() -> &'static str {
+    parrot::VERSION
+}
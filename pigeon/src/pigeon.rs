@@ -14,8 +14,9 @@
 
 
 use crate::{
+    camera::Camera2D,
     graphics::{Breakdown, Drawable},
-    pipeline::{QuadPipe, Render, RenderInformation, TrianglePipe},
+    pipeline::{DebugPipe, InstancedQuadPipe, QuadPipe, Render, RenderInformation, SdfPipe, TextPipe, TrianglePipe},
 };
 use euclid::{Size2D, Transform3D};
 use itertools::Itertools;
@@ -33,6 +34,66 @@ pub const OPENGL_TO_WGPU_MATRIX: Transform3D<f32, WorldSpace, WorldSpace> = Tran
     1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.5, 1.0,
 );
 
+/// The options used to setup a [`Pigeon`]. Create one with [`PigeonConfig::default`] and change what you need.
+/// The backend (vulkan, metal, dx12...) is chosen by the [`wgpu::Instance`] passed to [`Pigeon::with_config`].
+#[derive(Debug, Clone, Copy)]
+pub struct PigeonConfig {
+    /// Size of the screen
+    pub size: Size2D<f32, WorldSpace>,
+    /// Enables MSAA for values > 1
+    pub sample_count: u32,
+    /// The present mode used to configure the surface
+    pub present_mode: wgpu::PresentMode,
+    /// The texture format of the surface. If none is given the surface's preferred format is used
+    pub format: Option<wgpu::TextureFormat>,
+    /// Which adapter to prefer when there's more than one, such as a laptop with integrated and discrete gpus
+    pub power_preference: wgpu::PowerPreference,
+}
+
+impl Default for PigeonConfig {
+    fn default() -> Self {
+        Self {
+            size: Size2D::new(800.0, 600.0),
+            sample_count: 1,
+            present_mode: wgpu::PresentMode::Fifo,
+            format: None,
+            power_preference: wgpu::PowerPreference::default(),
+        }
+    }
+}
+
+impl PigeonConfig {
+    /// Set the size of the screen
+    pub fn with_size(mut self, size: impl Into<Size2D<f32, WorldSpace>>) -> Self {
+        self.size = size.into();
+        self
+    }
+
+    /// Set the sample count
+    pub fn with_sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    /// Set the present mode
+    pub fn with_present_mode(mut self, present_mode: wgpu::PresentMode) -> Self {
+        self.present_mode = present_mode;
+        self
+    }
+
+    /// Set the texture format of the surface
+    pub fn with_format(mut self, format: wgpu::TextureFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Set the power preference used to pick the adapter
+    pub fn with_power_preference(mut self, power_preference: wgpu::PowerPreference) -> Self {
+        self.power_preference = power_preference;
+        self
+    }
+}
+
 
 /// Macro to create a pigeon, the manager, and various draw functions.
 /// the pigoen struct as input.
@@ -91,6 +152,8 @@ macro_rules! pigeon {
         pub struct Pigeon {
             pub paint: Painter,
             pub screen: Size2D<f32, WorldSpace>,
+            /// The camera used to view the world
+            pub camera: Camera2D,
             pub frame_time: u128,
             $(pub $name: $pipe,
             )*
@@ -102,8 +165,17 @@ macro_rules! pigeon {
 
         impl Pigeon {
             pub fn new(surface: wgpu::Surface, instance: &wgpu::Instance, size: Size2D<f32, WorldSpace>, sample_count: u32) -> Self {
-                let paint = Painter::for_surface(surface, instance, sample_count).block_on().unwrap();
-                $(let $name = paint.pipeline::<$pipe>(Blending::default(), paint.preferred_format(), Some(&format!("{} shader", stringify!($name))));
+                Self::with_config(surface, instance, PigeonConfig::default().with_size(size).with_sample_count(sample_count))
+            }
+
+            /// Create pigeon using a [`PigeonConfig`]. The adapter is picked from the backends enabled on `instance`
+            pub fn with_config(surface: wgpu::Surface, instance: &wgpu::Instance, config: PigeonConfig) -> Self {
+                let mut paint = Painter::for_surface_with_power(surface, instance, config.sample_count, config.power_preference).block_on().unwrap();
+                // Configure the surface, using the format the surface prefers if none was given
+                let format = config.format.unwrap_or_else(|| paint.preferred_format());
+                paint.configure(config.size.cast_unit().to_u32(), config.present_mode, format).unwrap();
+                let size = config.size;
+                $(let $name = paint.pipeline::<$pipe>(Blending::default(), format, Some(&format!("{} shader", stringify!($name))));
                 )*
                 $(
                     let $cust_name = paint.custom_pipeline::<$cust_pipe, pigeon_parrot::painter::PipelineFunction>(
@@ -119,6 +191,7 @@ macro_rules! pigeon {
                 Self {
                     paint,
                     screen: size,
+                    camera: Camera2D::new(size),
                     frame_time: 0,
                     $($name,
                     )*
@@ -131,6 +204,7 @@ macro_rules! pigeon {
 
             pub fn update_size(&mut self, size: impl Into<Size2D<f32, WorldSpace>>) {
                 self.screen = size.into();
+                self.camera.viewport_size = self.screen;
             }
         }
 
@@ -160,15 +234,45 @@ macro_rules! pigeon {
                     false
                 }
             }
+
+            /// Add a graphic for any of the pipelines. Graphics for different pipelines can be mixed freely as they're all drawn
+            /// in the same pass with the same camera and depth buffer, so their z values decide what is in front
+            pub fn add<D: Drawable>(&mut self, graphic: &D)
+            where
+            D::Pipeline: ContainerSlot,
+            {
+                <D::Pipeline as ContainerSlot>::slot(self).push(graphic.breakdown());
+            }
         }
 
-        /// Sorts the container so shapes are grouped by texture to minimise texture swapping
+        /// Finds where a pipeline's breakdowns go in the [`Container`]. Used by [`Container::add`]
+        pub trait ContainerSlot: Render {
+            fn slot(cont: &mut Container) -> &mut Vec<Breakdown<<Self as Render>::Vertex>>;
+        }
+
+        $(
+            impl ContainerSlot for $pipe {
+                fn slot(cont: &mut Container) -> &mut Vec<Breakdown<<Self as Render>::Vertex>> {
+                    &mut cont.$name
+                }
+            }
+        )*
+        $(
+            impl ContainerSlot for $cust_pipe {
+                fn slot(cont: &mut Container) -> &mut Vec<Breakdown<<Self as Render>::Vertex>> {
+                    &mut cont.$cust_name
+                }
+            }
+        )*
+
+        /// Sorts the container so shapes are grouped by texture to minimise texture swapping. Empty breakdowns are dropped
+        /// so a pipeline that was only given empty breakdowns isn't rendered
         fn sort_container(mut cont: Container) -> Container {
             let st = Instant::now();
             log::debug!("Sorting container");
             // sort container contents by texture.
             $(
-                cont.$name = cont.$name.into_iter().sorted_by(|b1, b2| {
+                cont.$name = cont.$name.into_iter().filter(|b| !b.vertices.is_empty()).sorted_by(|b1, b2| {
                     if let Some(t1) = &b1.texture {
                         if let Some(t2) = &b2.texture {
                             // Some, Some
@@ -193,7 +297,7 @@ macro_rules! pigeon {
                 }).collect();
             )*
             $(
-                cont.$cust_name = cont.$cust_name.into_iter().sorted_by(|b1, b2| {
+                cont.$cust_name = cont.$cust_name.into_iter().filter(|b| !b.vertices.is_empty()).sorted_by(|b1, b2| {
                     if let Some(t1) = &b1.texture {
                         if let Some(t2) = &b2.texture {
                             // Some, Some
@@ -222,6 +326,16 @@ macro_rules! pigeon {
             cont
         }
 
+        /// Moves anything the pipelines have queued themselves into the container
+        fn take_queued(pigeon: &mut Pigeon, cont: &mut Container) {
+            $(
+                cont.$name.append(&mut pigeon.$name.take_queued());
+            )*
+            $(
+                cont.$cust_name.append(&mut pigeon.$cust_name.take_queued());
+            )*
+        }
+
         /// Used to draw you shapes in pigeon. Takes in your draw function which will fill a [`Container`] with whatever you want
         /// drawn this pass.
         pub fn draw<F>(pigeon: &mut Pigeon, draw_fn: F)
@@ -235,13 +349,13 @@ macro_rules! pigeon {
 
             // Allow the user to populate the container
             draw_fn(&mut cont);
+            take_queued(pigeon, &mut cont);
 
             // Sort container
             cont = sort_container(cont);
 
             // Generate appropriate matrix info
-            let ortho: Transform3D<f32, WorldSpace, ScreenSpace> = Transform3D::ortho(-pigeon.screen.width/2.0, pigeon.screen.width/2.0, -pigeon.screen.height/2.0, pigeon.screen.height/2.0, 50.0, -50.0);
-            let ortho = OPENGL_TO_WGPU_MATRIX.then(&ortho);
+            let ortho = pigeon.camera.transform();
             log::debug!("Transform matrix >> {:?}", ortho);
 
             let ft = Instant::now();
@@ -259,7 +373,8 @@ macro_rules! pigeon {
                         if cont.$name.len() > 0 {
                             log::info!("Rendering for pipeline >> {}", stringify!($pipe));
                             let prep: RenderInformation<<$pipe as Render>::Vertex> = (cont.$name, ortho);
-                            pigeon.paint.update_pipeline(&mut pigeon.$name, prep);
+                            let reallocated = pigeon.paint.update_pipeline(&mut pigeon.$name, prep);
+                            pigeon.$name.swap_uniforms(&pigeon.paint, reallocated);
                             pigeon.$name.render(&mut pigeon.paint, &mut pass);
                         }
                     )*
@@ -268,7 +383,8 @@ macro_rules! pigeon {
                         if cont.$cust_name.len() > 0 {
                             log::info!("Rendering for custom pipeline >> {}", stringify!($cust_pipe));
                             let prep: RenderInformation<<$cust_pipe as Render>::Vertex> = (cont.$cust_name, ortho);
-                            pigeon.paint.update_pipeline(&mut pigeon.$cust_name, prep);
+                            let reallocated = pigeon.paint.update_pipeline(&mut pigeon.$cust_name, prep);
+                            pigeon.$cust_name.swap_uniforms(&pigeon.paint, reallocated);
                             pigeon.$cust_name.render(&mut pigeon.paint, &mut pass);
                         }
                     )*
@@ -324,13 +440,13 @@ macro_rules! pigeon {
 
                 // Allow the user to populate the container
                 add_fn(&mut cont);
+                take_queued(pigeon, &mut cont);
 
                 // Sort the container
                 cont = sort_container(cont);
 
                 // Generate appropriate matrix info
-                let ortho: Transform3D<f32, WorldSpace, ScreenSpace> = Transform3D::ortho(-pigeon.screen.width/2.0, pigeon.screen.width/2.0, -pigeon.screen.height/2.0, pigeon.screen.height/2.0, 50.0, -50.0);
-                let ortho = OPENGL_TO_WGPU_MATRIX.then(&ortho);
+                let ortho = pigeon.camera.transform();
                 log::debug!("Transform matrix >> {:?}", ortho);
 
                 let ft = Instant::now();
@@ -365,7 +481,8 @@ macro_rules! pigeon {
                     if cont.$name.len() > 0 {
                         log::info!("Rendering for pipeline >> {}", stringify!($pipe));
                         let prep: RenderInformation<<$pipe as Render>::Vertex> = (cont.$name, *ortho);
-                        pigeon.paint.update_pipeline(&mut pigeon.$name, prep);
+                        let reallocated = pigeon.paint.update_pipeline(&mut pigeon.$name, prep);
+                        pigeon.$name.swap_uniforms(&pigeon.paint, reallocated);
                         pigeon.$name.render(&mut pigeon.paint, pass);
                     }
                 )*
@@ -374,7 +491,8 @@ macro_rules! pigeon {
                     if cont.$cust_name.len() > 0 {
                         log::info!("Rendering for custom pipeline >> {}", stringify!($cust_pipe));
                         let prep: RenderInformation<<$cust_pipe as Render>::Vertex> = (cont.$cust_name, ortho);
-                        pigeon.paint.update_pipeline(&mut pigeon.$cust_name, prep);
+                        let reallocated = pigeon.paint.update_pipeline(&mut pigeon.$cust_name, prep);
+                        pigeon.$cust_name.swap_uniforms(&pigeon.paint, reallocated);
                         pigeon.$cust_name.render(&mut pigeon.paint, pass);
                     }
                 )*
@@ -384,4 +502,47 @@ macro_rules! pigeon {
     };
 }
 
-pigeon!(TrianglePipe => triangle, QuadPipe => quad | |);
+pigeon!(TrianglePipe => triangle, QuadPipe => quad, TextPipe => text, SdfPipe => sdf, InstancedQuadPipe => instanced, DebugPipe => debug | |);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::{Rectangle, SolidQuad, Sprite};
+    use parrot::color::Rgba;
+
+    #[test]
+    fn add_routes_by_pipeline() {
+        let red = Rgba::new(1.0, 0.0, 0.0, 1.0);
+        let blue = Rgba::new(0.0, 0.0, 1.0, 1.0);
+        let mut cont = Container::new();
+        assert!(!cont.is_updates());
+        cont.add(&Rectangle::new((0.0, 0.0, 0.0), (2.0, 2.0), red));
+        cont.add(&SolidQuad::new((0.0, 0.0, 0.0), (2.0, 2.0), red));
+        cont.add(&Rectangle::new((0.0, 0.0, 0.0), (2.0, 2.0), blue));
+        assert!(cont.is_updates());
+
+        // Each pipeline keeps its graphics in the order they were added
+        let cont = sort_container(cont);
+        let colors: Vec<[f32; 4]> = cont.triangle.iter().map(|b| b.vertices[0].color).collect();
+        assert_eq!(colors, vec![[1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]]);
+        assert_eq!(cont.quad.len(), 1);
+        assert!(cont.text.is_empty() && cont.sdf.is_empty() && cont.instanced.is_empty() && cont.debug.is_empty());
+    }
+
+    #[test]
+    #[ignore = "needs a gpu"]
+    fn sprites_sort_after_solid_quads() {
+        let texture = crate::graphics::texture::tests::blank_texture(Size2D::new(4, 4)).expect("No adapter");
+        let mut cont = Container::new();
+        cont.add(&Sprite::new((0.0, 0.0, 0.0), (2.0, 2.0), texture.clone()));
+        cont.add(&Rectangle::new((0.0, 0.0, 0.0), (2.0, 2.0), Rgba::WHITE));
+        cont.add(&SolidQuad::new((0.0, 0.0, 0.0), (2.0, 2.0), Rgba::WHITE));
+
+        // Untextured quads are drawn first, then quads are grouped by texture
+        let cont = sort_container(cont);
+        assert_eq!(cont.triangle.len(), 1);
+        assert_eq!(cont.quad.len(), 2);
+        assert!(cont.quad[0].texture.is_none());
+        assert_eq!(cont.quad[1].texture.as_ref().map(|t| t.id), Some(texture.id));
+    }
+}
@@ -0,0 +1,242 @@
+use std::ops::Deref;
+use parrot::{VertexBuffer, IndexBuffer, pipeline::{PipelineCore, PipelineDescription, PrimitiveTopology, DepthConfig, CompareFunction, Set, Pipeline}, Plumber, binding::{Binding, BindingType}, Painter, buffers::UniformBuffer, RenderPassExtention, transform::{ScreenSpace, WorldSpace}, color::Rgba};
+use wgpu::RenderPass;
+use super::{VERTEX_INIT_SIZE, INDEX_INIT_SIZE, RenderInformation, Render, Breakdown, triangle::TriangleVertex};
+use euclid::{Point3D, Transform3D};
+
+/// An immediate mode pipeline for debugging. Queue lines and points with [`DebugPipe::line`] and [`DebugPipe::point`]
+/// and they're drawn on top of everything else in the next draw, then forgotten
+#[derive(Debug)]
+pub struct DebugPipe {
+    vertex_buffer: VertexBuffer,
+    index_buffer: IndexBuffer,
+    index_count: u32,
+    /// Lines and points waiting for the next draw
+    pub queue: DebugQueue,
+    core: PipelineCore,
+}
+
+impl Deref for DebugPipe {
+    type Target = PipelineCore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.core
+    }
+}
+
+impl DebugPipe {
+    /// Queue a line from `a` to `b`
+    pub fn line(&mut self, a: impl Into<Point3D<f32, WorldSpace>>, b: impl Into<Point3D<f32, WorldSpace>>, color: Rgba) {
+        self.queue.line(a, b, color);
+    }
+
+    /// Queue a point. It's drawn as a cross [`DebugQueue::point_size`] wide
+    pub fn point(&mut self, p: impl Into<Point3D<f32, WorldSpace>>, color: Rgba) {
+        self.queue.point(p, color);
+    }
+
+    /// Remove everything queued since the last draw
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Returns the vertices queued since the last draw
+    pub fn queued(&self) -> &[TriangleVertex] {
+        self.queue.queued()
+    }
+}
+
+/// The lines and points queued for a [`DebugPipe`]
+#[derive(Debug, Clone)]
+pub struct DebugQueue {
+    /// Vertices queued since the last draw. Every two vertices make a line
+    queued: Vec<TriangleVertex>,
+    /// The width and height of the cross drawn for a point
+    pub point_size: f32,
+}
+
+impl Default for DebugQueue {
+    fn default() -> Self {
+        Self {
+            queued: vec![],
+            point_size: 8.0,
+        }
+    }
+}
+
+impl DebugQueue {
+    /// Queue a line from `a` to `b`
+    pub fn line(&mut self, a: impl Into<Point3D<f32, WorldSpace>>, b: impl Into<Point3D<f32, WorldSpace>>, color: Rgba) {
+        let color = (color.r, color.g, color.b, color.a);
+        self.queued.push(TriangleVertex::new_from_tuple(a.into().to_tuple(), color));
+        self.queued.push(TriangleVertex::new_from_tuple(b.into().to_tuple(), color));
+    }
+
+    /// Queue a point. It's drawn as a cross [`DebugQueue::point_size`] wide
+    pub fn point(&mut self, p: impl Into<Point3D<f32, WorldSpace>>, color: Rgba) {
+        let p = p.into();
+        let half = self.point_size / 2.0;
+        self.line((p.x - half, p.y, p.z), (p.x + half, p.y, p.z), color);
+        self.line((p.x, p.y - half, p.z), (p.x, p.y + half, p.z), color);
+    }
+
+    /// Remove everything queued
+    pub fn clear(&mut self) {
+        self.queued.clear();
+    }
+
+    /// Returns the queued vertices
+    pub fn queued(&self) -> &[TriangleVertex] {
+        &self.queued
+    }
+
+    /// Empties the queue, returning everything in it as a line list
+    pub fn take(&mut self) -> Vec<Breakdown<TriangleVertex>> {
+        if self.queued.is_empty() {
+            return vec![];
+        }
+        let vertices = std::mem::take(&mut self.queued);
+        let indicies = (0..vertices.len() as u16).collect();
+        vec![Breakdown {
+            vertices,
+            indicies,
+            texture: None,
+        }]
+    }
+}
+
+impl<'a> Plumber<'a> for DebugPipe {
+    type PrepareContext = RenderInformation<TriangleVertex>;
+    type Uniforms = [[f32;4];4];
+
+    fn description() -> PipelineDescription<'a> {
+        PipelineDescription {
+            topology: PrimitiveTopology::LineList,
+            // Always pass the depth test so debug lines are drawn over everything
+            depth: DepthConfig {
+                write_enabled: false,
+                compare: CompareFunction::Always,
+                ..Default::default()
+            },
+            ..PipelineDescription::new(
+                &TriangleVertex::VERTEX_LAYOUT,
+                Some(&[
+                    Set(&[
+                        Binding {
+                            binding: BindingType::UniformBuffer,
+                            stage: wgpu::ShaderStages::VERTEX,
+                        }
+                    ], Some("Debug transform bind group"))
+                ]),
+                parrot::shader::ShaderFile::Wgsl(include_str!("./shaders/triangle.wgsl")),
+                Some("Debug pipeline")
+            )
+        }
+    }
+
+    fn setup(pipe: Pipeline, paint: &Painter) -> Self {
+        let blank_vertex: Vec<TriangleVertex> = Vec::with_capacity(VERTEX_INIT_SIZE as usize);
+        let blank_index: Vec<u16> = Vec::with_capacity(INDEX_INIT_SIZE as usize);
+        let blank_transform: Transform3D<f32, ScreenSpace, ScreenSpace> = Transform3D::identity();
+
+        let vertex_buffer = paint.vertex_buffer(blank_vertex.as_slice(), Some("Debug vertex buffer"));
+        let index_buffer = paint.index_buffer(blank_index.as_slice(), Some("Debug index buffer"));
+        let transform_buffer = paint.uniform_buffer(&[blank_transform.to_arrays()], Some("Debug transform buffer"));
+        let bind_group = paint.binding_group(&pipe.layout.b_layouts[0], &[&transform_buffer], Some("Debug transform binding group"))
+            .expect("Failed to create the debug pipeline's transform binding group");
+
+        Self {
+            vertex_buffer,
+            index_buffer,
+            index_count: 0,
+            queue: DebugQueue::default(),
+            core: PipelineCore {
+                pipeline: pipe,
+                bindings: vec![bind_group],
+                uniforms: vec![transform_buffer]
+            }
+        }
+    }
+
+    fn prepare(&'a mut self, prep: Self::PrepareContext, paint: &mut Painter) -> Vec<(&'a mut UniformBuffer, Vec<Self::Uniforms>)> {
+        let mut vertices: Vec<TriangleVertex> = vec![];
+        let mut indices: Vec<u16> = vec![];
+
+        // Combine into a big ol array.
+        for mut lines in prep.0 {
+            let start = vertices.len();
+            vertices.append(&mut lines.vertices);
+            indices.append(&mut lines.indicies.iter().map(|ind| ind + start as u16).collect());
+        }
+        self.index_count = indices.len() as u32;
+
+        // Update the vertex and index buffers
+        if let Some(v) = paint.update_vertex_buffer(&vertices, &mut self.vertex_buffer) {
+            self.vertex_buffer = v;
+        }
+        if let Some(i) = paint.update_index_buffer(indices, &mut self.index_buffer) {
+            self.index_buffer = i;
+        }
+
+        // Return info for parrot to update our uniform buffers
+        vec![(&mut self.core.uniforms[0], vec![prep.1.to_arrays()])]
+    }
+}
+
+impl Render for DebugPipe {
+    type Vertex = TriangleVertex;
+
+    fn render<'a>(&'a mut self, _paint: &mut Painter, pass: &mut RenderPass<'a>) {
+        if self.index_count == 0 {
+            return;
+        }
+        pass.set_parrot_pipeline(self);
+        pass.set_parrot_vertex_buffer(&self.vertex_buffer);
+        pass.set_parrot_index_buffer(&self.index_buffer);
+        pass.draw_parrot_indexed_checked(&self.index_buffer, 0..self.index_count, 0..1);
+    }
+
+    fn take_queued(&mut self) -> Vec<Breakdown<TriangleVertex>> {
+        self.queue.take()
+    }
+
+    fn swap_uniforms(&mut self, paint: &Painter, buffers: Vec<(usize, UniformBuffer)>) {
+        super::swap_uniforms(&mut self.core, paint, buffers, "debug");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queued_until_taken() {
+        let mut queue = DebugQueue::default();
+        queue.line((0.0, 0.0, 0.0), (1.0, 1.0, 0.0), Rgba::new(1.0, 0.0, 0.0, 1.0));
+        queue.point((5.0, 5.0, 0.0), Rgba::new(0.0, 1.0, 0.0, 1.0));
+        // A line is two vertices, a point is two lines
+        assert_eq!(queue.queued().len(), 6);
+
+        let taken = queue.take();
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].vertices.len(), 6);
+        assert_eq!(taken[0].indicies, vec![0, 1, 2, 3, 4, 5]);
+        assert!(taken[0].texture.is_none());
+        assert!(queue.queued().is_empty());
+        assert!(queue.take().is_empty());
+
+        queue.line((0.0, 0.0, 0.0), (1.0, 0.0, 0.0), Rgba::new(1.0, 1.0, 1.0, 1.0));
+        queue.clear();
+        assert!(queue.take().is_empty());
+    }
+
+    #[test]
+    fn point_is_a_cross() {
+        let mut queue = DebugQueue { point_size: 4.0, ..Default::default() };
+        let color = Rgba::new(0.0, 0.0, 1.0, 1.0);
+        queue.point((10.0, 20.0, 0.5), color);
+        let positions: Vec<[f32; 3]> = queue.queued().iter().map(|v| v.pos).collect();
+        assert_eq!(positions, vec![[8.0, 20.0, 0.5], [12.0, 20.0, 0.5], [10.0, 18.0, 0.5], [10.0, 22.0, 0.5]]);
+        assert!(queue.queued().iter().all(|v| v.color == [0.0, 0.0, 1.0, 1.0]));
+    }
+}
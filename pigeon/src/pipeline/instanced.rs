@@ -0,0 +1,246 @@
+use super::{quad::Group, Render, RenderInformation, VERTEX_INIT_SIZE};
+use crate::graphics::{AtlasRegion, Texture};
+use euclid::Transform3D;
+use parrot::{
+    binding::{Binding, BindingGroup, BindingType},
+    buffers::*,
+    painter::RenderPassExtention,
+    pipeline::{Pipeline, PipelineCore, PipelineDescription, Set},
+    sampler::Sampler,
+    transform::{ObjectSpace, ScreenSpace, WorldSpace},
+    vertex::VertexFormat,
+    color::Rgba,
+    Painter, Plumber,
+};
+use std::{collections::HashMap, ops::Deref, rc::Rc};
+use wgpu::RenderPass;
+
+/// Corners of a 1x1 quad centred on the origin, in the order top left, top right, bottom left, bottom right
+const QUAD_CORNERS: [[f32; 2]; 4] = [[-0.5, 0.5], [0.5, 0.5], [-0.5, -0.5], [0.5, -0.5]];
+const QUAD_INDICIES: [u16; 6] = [0, 1, 3, 0, 3, 2];
+
+/// Pipeline for drawing lots of quads, such as [`crate::graphics::InstancedQuad`]. Every quad shares the same static geometry and
+/// is moved into place by its model matrix in the vertex shader, so the cpu only writes one [`QuadInstance`] per quad.
+/// Each breakdown's vertices are its instances. Breakdowns without a texture are drawn with a 1x1 white texture
+#[derive(Debug)]
+pub struct InstancedQuadPipe {
+    /// The corners of the quad shared by every instance
+    pub vertex_buffer: VertexBuffer,
+    pub index_buffer: IndexBuffer,
+    pub instance_buffer: VertexBuffer,
+    /// Ranges of instances that use the same texture
+    pub groups: Vec<Group>,
+    pub texture_binds: HashMap<usize, BindingGroup>,
+    /// Texture used by breakdowns without one
+    pub white: Rc<Texture>,
+    /// Pipeline core to deref to
+    core: PipelineCore,
+}
+
+impl Deref for InstancedQuadPipe {
+    type Target = PipelineCore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.core
+    }
+}
+
+impl<'a> Plumber<'a> for InstancedQuadPipe {
+    type PrepareContext = RenderInformation<QuadInstance>;
+    type Uniforms = [[f32; 4]; 4];
+
+    fn description() -> PipelineDescription<'a> {
+        PipelineDescription {
+            instance_layout: Some(&QuadInstance::INSTANCE_LAYOUT),
+            ..PipelineDescription::new(
+                &[VertexFormat::Floatx2],
+                Some(&[
+                    Set(
+                        &[
+                            Binding {
+                                binding: BindingType::Texture {
+                                    multisampled: false,
+                                    view_dimension: wgpu::TextureViewDimension::D2,
+                                },
+                                stage: wgpu::ShaderStages::FRAGMENT,
+                            },
+                            Binding {
+                                binding: BindingType::Sampler,
+                                stage: wgpu::ShaderStages::FRAGMENT,
+                            },
+                        ],
+                        Some("Instanced quad texture bind group"),
+                    ),
+                    Set(
+                        &[Binding {
+                            binding: BindingType::UniformBuffer,
+                            stage: wgpu::ShaderStages::VERTEX,
+                        }],
+                        Some("Instanced quad transform bind group"),
+                    ),
+                ]),
+                parrot::shader::ShaderFile::Wgsl(include_str!("./shaders/instanced_quad.wgsl")),
+                Some("Instanced quad pipeline")
+            )
+        }
+    }
+
+    fn setup(pipe: Pipeline, paint: &Painter) -> Self {
+        // Allocating a bunch of capacity for the buffer to prevent resizing it 1000 times
+        let blank_instance: Vec<QuadInstance> = Vec::with_capacity(VERTEX_INIT_SIZE as usize);
+        let blank_transform: Transform3D<f32, ScreenSpace, ScreenSpace> = Transform3D::identity();
+
+        let vertex_buffer = paint.vertex_buffer(&QUAD_CORNERS, Some("Instanced quad vertex buffer"));
+        let index_buffer = paint.index_buffer(&QUAD_INDICIES, Some("Instanced quad index buffer"));
+        let instance_buffer = paint.vertex_buffer(blank_instance.as_slice(), Some("Instanced quad instance buffer"));
+        let transform_buffer = paint.uniform_buffer(&[blank_transform.to_arrays()], Some("Instanced quad transform buffer"));
+        let bind_group = paint.binding_group(
+            &pipe.layout.b_layouts[1],
+            &[&transform_buffer],
+            Some("Instanced quad transform binding group"),
+        ).expect("Failed to create the instanced quad pipeline's transform binding group");
+
+        // Same white texture as the QuadPipe, see its setup
+        let sampler = Rc::new(Sampler::nearest(&paint.device));
+        let white = Rc::new(Texture::from_shared(paint.white_texture(), sampler, "Instanced quad white texture"));
+
+        let mut quad = Self {
+            vertex_buffer,
+            index_buffer,
+            instance_buffer,
+            groups: vec![],
+            texture_binds: HashMap::new(),
+            white: white.clone(),
+            core: PipelineCore {
+                pipeline: pipe,
+                bindings: vec![bind_group],
+                uniforms: vec![transform_buffer],
+            },
+        };
+        quad.add_texture(paint, &white);
+        quad
+    }
+
+    fn prepare(
+        &'a mut self,
+        prep: Self::PrepareContext,
+        paint: &mut Painter,
+    ) -> Vec<(&'a mut UniformBuffer, Vec<Self::Uniforms>)> {
+        let mut instances: Vec<QuadInstance> = vec![];
+        let mut groups: Vec<Group> = vec![];
+
+        // Combine into a big ol array.
+        for mut quad in prep.0 {
+            // Skip empty breakdowns
+            if quad.vertices.is_empty() {
+                continue;
+            }
+            let start = instances.len() as u32;
+            instances.append(&mut quad.vertices);
+            let tex = quad.texture.unwrap_or_else(|| self.white.clone());
+            // Check if we have already bound the texture
+            if !self.texture_binds.contains_key(&tex.id) {
+                self.add_texture(paint, &tex);
+            }
+            // Extend the previous group if it uses the same texture so they're drawn together
+            match groups.last_mut() {
+                Some(group) if group.tex_id == tex.id && group.range.end == start => {
+                    group.range.end = instances.len() as u32;
+                }
+                _ => groups.push(Group {
+                    range: start..instances.len() as u32,
+                    tex_id: tex.id,
+                }),
+            }
+        }
+
+        self.groups = groups;
+
+        // Update the instance buffer. The quad itself never changes
+        if let Some(i) = paint.update_vertex_buffer(&instances, &mut self.instance_buffer) {
+            self.instance_buffer = i;
+        }
+
+        // Return info for parrot to update our uniform buffers
+        vec![(&mut self.core.uniforms[0], vec![prep.1.to_arrays()])]
+    }
+}
+
+impl Render for InstancedQuadPipe {
+    type Vertex = QuadInstance;
+
+    fn render<'a>(&'a mut self, _paint: &mut Painter, pass: &mut RenderPass<'a>) {
+        if self.groups.is_empty() {
+            return;
+        }
+        pass.set_parrot_pipeline(self);
+        pass.set_parrot_vertex_buffer(&self.vertex_buffer);
+        pass.set_parrot_instance_buffer(&self.instance_buffer);
+        pass.set_parrot_index_buffer(&self.index_buffer);
+
+        let mut prev_tex = None;
+        for g in &self.groups {
+            if prev_tex != Some(g.tex_id) {
+                pass.set_binding(
+                    self.texture_binds
+                        .get(&g.tex_id)
+                        .expect("Cannot find texture in textures map"),
+                    &[],
+                );
+                prev_tex = Some(g.tex_id);
+            }
+            pass.draw_parrot_indexed_checked(&self.index_buffer, self.index_buffer.full_range(), g.range.clone());
+        }
+    }
+
+    fn swap_uniforms(&mut self, paint: &Painter, buffers: Vec<(usize, UniformBuffer)>) {
+        super::swap_uniforms(&mut self.core, paint, buffers, "instanced quad");
+    }
+}
+
+impl InstancedQuadPipe {
+    pub fn add_texture(&mut self, paint: &Painter, tex: &Texture) {
+        let bind_group = paint.binding_group(
+            &self.core.pipeline.layout.b_layouts[0],
+            &[&*tex.texture, &*tex.sampler],
+            Some(&format!("{} instanced binding group", tex.name)),
+        ).unwrap_or_else(|e| panic!("Failed to bind texture {} to the instanced quad pipeline: {}", tex.name, e));
+        self.texture_binds.insert(tex.id, bind_group);
+    }
+
+    /// Removes all the texture bindings. Useful when resetting a scene.
+    pub fn clear_textures(&mut self) {
+        self.texture_binds.drain();
+    }
+}
+
+/// The per instance data for instanced quads
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct QuadInstance {
+    /// Rows of the model matrix, which moves a 1x1 quad centred on the origin into worldspace
+    pub model: [[f32; 4]; 4],
+    /// The top left and bottom right u-v coordinates of the texture region
+    pub region: [f32; 4],
+    /// The color the texture is multiplied by
+    pub color: [f32; 4],
+}
+
+impl QuadInstance {
+    pub fn new(model: Transform3D<f32, ObjectSpace, WorldSpace>, region: AtlasRegion, color: Rgba) -> Self {
+        Self {
+            model: model.to_arrays(),
+            region: [region.min.0, region.min.1, region.max.0, region.max.1],
+            color: [color.r, color.g, color.b, color.a],
+        }
+    }
+
+    pub const INSTANCE_LAYOUT: [VertexFormat; 6] = [
+        VertexFormat::Floatx4,
+        VertexFormat::Floatx4,
+        VertexFormat::Floatx4,
+        VertexFormat::Floatx4,
+        VertexFormat::Floatx4,
+        VertexFormat::Floatx4,
+    ];
+}
@@ -1,15 +1,23 @@
 pub mod quad;
 pub mod triangle;
+pub mod text;
+pub mod debug;
+pub mod sdf;
+pub mod instanced;
 use crate::graphics::Texture;
 use std::rc::Rc;
-use parrot::{transform::{ScreenSpace, WorldSpace}, Painter};
+use parrot::{transform::{ScreenSpace, WorldSpace}, Painter, buffers::UniformBuffer, pipeline::PipelineCore};
 use euclid::Transform3D;
 
 pub use quad::QuadPipe;
 pub use triangle::TrianglePipe;
+pub use text::TextPipe;
+pub use debug::{DebugPipe, DebugQueue};
+pub use sdf::SdfPipe;
+pub use instanced::InstancedQuadPipe;
 use wgpu::RenderPass;
 
-/// Pigeon comes with two built in pipelines [QuadPipe] and [TrianglePipe]. Otherwise you can create
+/// Pigeon comes with six built in pipelines [QuadPipe], [TrianglePipe], [TextPipe], [SdfPipe], [InstancedQuadPipe] and [DebugPipe]. Otherwise you can create
 /// your own using the [Render] trait.
 
 /// Contains the essential details needed by the pipelines to render the shape
@@ -21,6 +29,15 @@ pub struct Breakdown<T: bytemuck::Pod + bytemuck::Zeroable + Clone + Copy> {
 }
 
 impl<T: bytemuck::Pod + bytemuck::Zeroable + Clone + Copy> Breakdown<T> {
+    /// Returns a breakdown with nothing in it. Pipelines will skip over it when rendering.
+    pub fn empty() -> Self {
+        Self {
+            vertices: vec![],
+            indicies: vec![],
+            texture: None,
+        }
+    }
+
     pub fn append(self, vec: &mut Vec<Breakdown<T>>) {
         vec.push(self);
     }
@@ -31,6 +48,27 @@ pub trait Render {
     type Vertex: bytemuck::Pod + bytemuck::Zeroable + Clone + Copy;
 
     fn render<'a>(&'a mut self, paint: &mut Painter, pass: &mut RenderPass<'a>);
+
+    /// Breakdowns the pipeline has collected itself since the last draw. They're drawn along with the [`crate::pigeon::Container`]'s
+    fn take_queued(&mut self) -> Vec<Breakdown<Self::Vertex>> {
+        vec![]
+    }
+
+    /// Swap in the uniform buffers [`Painter::update_pipeline`] had to recreate, along with any binding groups holding them.
+    /// Each buffer comes with its position in the list [`parrot::Plumber::prepare`] returned. Pipelines that keep each uniform
+    /// buffer in a binding group of its own can use [`swap_uniforms`]
+    fn swap_uniforms(&mut self, paint: &Painter, buffers: Vec<(usize, UniformBuffer)>);
+}
+
+/// Swaps recreated uniform buffers into a pipeline that keeps each uniform buffer in a binding group of its own,
+/// at the same position in [`PipelineCore::bindings`]. All the built in pipelines do. `pipeline` names the pipeline if this panics
+pub fn swap_uniforms(core: &mut PipelineCore, paint: &Painter, buffers: Vec<(usize, UniformBuffer)>, pipeline: &str) {
+    for (i, buffer) in buffers {
+        let layout = &core.pipeline.layout.b_layouts[core.bindings[i].set_index as usize];
+        core.bindings[i] = paint.binding_group(layout, &[&buffer], buffer.name.as_deref())
+            .unwrap_or_else(|e| panic!("Failed to rebind uniform buffer {:?} for the {} pipeline: {}", buffer.name, pipeline, e));
+        core.uniforms[i] = buffer;
+    }
 }
 
 /// The render information passed of to the pipelines
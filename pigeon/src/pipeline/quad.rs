@@ -1,11 +1,12 @@
 use super::{Render, RenderInformation, INDEX_INIT_SIZE, VERTEX_INIT_SIZE};
-use crate::graphics::Texture;
+use crate::graphics::{CachedMesh, Texture};
 use euclid::Transform3D;
 use parrot::{
     binding::{Binding, BindingType},
     buffers::*,
     painter::RenderPassExtention,
     pipeline::{Pipeline, PipelineCore, PipelineDescription, Set},
+    sampler::Sampler,
     transform::ScreenSpace,
     vertex::VertexFormat,
     Painter, Plumber,
@@ -14,23 +15,27 @@ use pigeon_parrot::binding::BindingGroup;
 use std::{
     collections::HashMap,
     ops::{Deref, Range},
+    rc::Rc,
 };
 use wgpu::RenderPass;
 
 /// Helps [QuadPipe] know which texture to set depending on how many indicies deep it is in the buffer
 #[derive(Debug)]
 pub struct Group {
-    range: Range<u32>,
-    tex_id: usize,
+    pub(crate) range: Range<u32>,
+    pub(crate) tex_id: usize,
 }
 
-/// Pipeline for drawing textured quads. Designed to work with [`crate::graphics::sprite::Sprite`]
+/// Pipeline for drawing textured quads. Designed to work with [`crate::graphics::sprite::Sprite`].
+/// Breakdowns without a texture are drawn with a 1x1 white texture so they show up as their vertex color
 #[derive(Debug)]
 pub struct QuadPipe {
     pub vertex_buffer: VertexBuffer,
     pub index_buffer: IndexBuffer,
     pub groups: Vec<Group>,
     pub texture_binds: HashMap<usize, BindingGroup>,
+    /// Texture used by breakdowns without one
+    pub white: Rc<Texture>,
     /// Pipeline core to deref to
     core: PipelineCore,
 }
@@ -48,14 +53,15 @@ impl<'a> Plumber<'a> for QuadPipe {
     type Uniforms = [[f32; 4]; 4];
 
     fn description() -> PipelineDescription<'a> {
-        PipelineDescription {
-            vertex_layout: &QuadVertex::VERTEX_LAYOUT,
-            pipeline_layout: Some(&[
+        PipelineDescription::new(
+            &QuadVertex::VERTEX_LAYOUT,
+            Some(&[
                 Set(
                     &[
                         Binding {
                             binding: BindingType::Texture {
                                 multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
                             },
                             stage: wgpu::ShaderStages::FRAGMENT,
                         },
@@ -74,9 +80,9 @@ impl<'a> Plumber<'a> for QuadPipe {
                     Some("Quad transform bind group"),
                 ),
             ]),
-            shader: parrot::shader::ShaderFile::Wgsl(include_str!("./shaders/quad.wgsl")),
-            name: Some("Quad pipeline"),
-        }
+            parrot::shader::ShaderFile::Wgsl(include_str!("./shaders/quad.wgsl")),
+            Some("Quad pipeline")
+        )
     }
 
     fn setup(pipe: Pipeline, paint: &Painter) -> Self {
@@ -96,19 +102,26 @@ impl<'a> Plumber<'a> for QuadPipe {
             &pipe.layout.b_layouts[1],
             &[&transform_buffer],
             Some("Quad transform binding group"),
-        );
+        ).expect("Failed to create the quad pipeline's transform binding group");
 
-        Self {
+        // The painter shares the white texture between pipes. Pigeon textures keep their sampler in an Rc so each pipe makes its own
+        let sampler = Rc::new(Sampler::nearest(&paint.device));
+        let white = Rc::new(Texture::from_shared(paint.white_texture(), sampler, "Quad white texture"));
+
+        let mut quad = Self {
             vertex_buffer,
             index_buffer,
             groups: vec![],
             texture_binds: HashMap::new(),
+            white: white.clone(),
             core: PipelineCore {
                 pipeline: pipe,
                 bindings: vec![bind_group],
                 uniforms: vec![transform_buffer],
             },
-        }
+        };
+        quad.add_texture(paint, &white);
+        quad
     }
 
     fn prepare(
@@ -122,22 +135,29 @@ impl<'a> Plumber<'a> for QuadPipe {
 
         // Combine into a big ol array.
         for mut quad in prep.0 {
+            // Skip empty breakdowns
+            if quad.vertices.is_empty() {
+                continue;
+            }
             let start = vertices.len();
             vertices.append(&mut quad.vertices);
             let start2 = indices.len() as u32;
             indices.append(&mut quad.indicies.iter().map(|ind| ind + start as u16).collect());
-            if let Some(tex) = quad.texture {
-                // Check if we have already bound the texture
-                if !self.texture_binds.contains_key(&tex.id) {
-                    // Add texture to the map
-                    self.add_texture(&paint, &tex);
+            let tex = quad.texture.unwrap_or_else(|| self.white.clone());
+            // Check if we have already bound the texture
+            if !self.texture_binds.contains_key(&tex.id) {
+                // Add texture to the map
+                self.add_texture(&paint, &tex);
+            }
+            // Extend the previous group if it uses the same texture so they're drawn together
+            match groups.last_mut() {
+                Some(group) if group.tex_id == tex.id && group.range.end == start2 => {
+                    group.range.end = indices.len() as u32;
                 }
-                groups.push(Group {
+                _ => groups.push(Group {
                     range: start2..indices.len() as u32,
                     tex_id: tex.id,
-                });
-            } else {
-                panic!("Textured shape has no texture.")
+                }),
             }
         }
 
@@ -160,6 +180,10 @@ impl Render for QuadPipe {
     type Vertex = QuadVertex;
 
     fn render<'a>(&'a mut self, _paint: &mut Painter, pass: &mut RenderPass<'a>) {
+        // Nothing was prepared, so the buffers are empty
+        if self.groups.is_empty() {
+            return;
+        }
         // Set pipeline
         pass.set_parrot_pipeline(self);
 
@@ -187,21 +211,54 @@ impl Render for QuadPipe {
                     );
                     prev_tex = g.tex_id;
                 }
-                pass.draw_parrot_indexed(g.range.clone(), 0..1);
+                pass.draw_parrot_indexed_checked(&self.index_buffer, g.range.clone(), 0..1);
             }
         }
     }
+
+    fn swap_uniforms(&mut self, paint: &Painter, buffers: Vec<(usize, UniformBuffer)>) {
+        super::swap_uniforms(&mut self.core, paint, buffers, "quad");
+    }
 }
 
 impl QuadPipe {
     pub fn add_texture(&mut self, paint: &Painter, tex: &Texture) {
         let bind_group = paint.binding_group(
             &self.core.pipeline.layout.b_layouts[0],
-            &[&tex.texture, &*tex.sampler],
+            &[&*tex.texture, &*tex.sampler],
             Some(&format!("{} binding group", tex.name)),
-        );
+        ).unwrap_or_else(|e| panic!("Failed to bind texture {} to the quad pipeline: {}", tex.name, e));
         self.texture_binds.insert(tex.id, bind_group);
     }
+
+    /// Removes all the texture bindings. Useful when resetting a scene.
+    pub fn clear_textures(&mut self) {
+        self.texture_binds.drain();
+    }
+
+    /// Clears the groups used when rendering
+    pub fn clear_groups(&mut self) {
+        self.groups.clear();
+    }
+
+    /// Draw a [`CachedMesh`] with this pipeline, binding its texture or the white texture if it has none.
+    /// The texture must have been added with [`QuadPipe::add_texture`]
+    pub fn draw_cached<'a>(&'a self, mesh: &'a CachedMesh<QuadVertex>, pass: &mut RenderPass<'a>) {
+        let tex_id = mesh.texture().map_or(self.white.id, |t| t.id);
+        pass.set_parrot_pipeline(self);
+        pass.set_binding(
+            self.texture_binds
+                .get(&tex_id)
+                .expect("Cannot find texture in textures map"),
+            &[],
+        );
+        mesh.draw(pass);
+    }
+
+    /// Returns the ids of the textures currently bound
+    pub fn texture_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.texture_binds.keys().copied()
+    }
 }
 
 /// The vertex for quads
@@ -213,6 +270,8 @@ pub struct QuadVertex {
     pub pos: [f32; 3],
     /// The u-v coordinates of the vertex on the texture
     pub tex_coords: [f32; 2],
+    /// The color the texture is multiplied by
+    pub color: [f32; 4],
 }
 
 impl Default for QuadVertex {
@@ -220,6 +279,7 @@ impl Default for QuadVertex {
         Self {
             pos: [0.0, 0.0, 0.0],
             tex_coords: [0.0, 0.0],
+            color: [1.0, 1.0, 1.0, 1.0],
         }
     }
 }
@@ -236,6 +296,7 @@ impl QuadVertex {
         Self {
             pos: [x, y, z],
             tex_coords: [u, v],
+            ..Default::default()
         }
     }
 
@@ -243,8 +304,15 @@ impl QuadVertex {
         Self {
             pos: [pos.0, pos.1, pos.2],
             tex_coords: [tex.0, tex.1],
+            ..Default::default()
         }
     }
 
-    pub const VERTEX_LAYOUT: [VertexFormat; 2] = [VertexFormat::Floatx3, VertexFormat::Floatx2];
+    /// Set the color the texture is multiplied by
+    pub fn with_color(mut self, color: (f32, f32, f32, f32)) -> Self {
+        self.color = [color.0, color.1, color.2, color.3];
+        self
+    }
+
+    pub const VERTEX_LAYOUT: [VertexFormat; 3] = [VertexFormat::Floatx3, VertexFormat::Floatx2, VertexFormat::Floatx4];
 }
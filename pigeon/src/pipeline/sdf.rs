@@ -0,0 +1,224 @@
+use super::{quad::Group, Render, RenderInformation, INDEX_INIT_SIZE, VERTEX_INIT_SIZE};
+use crate::graphics::Texture;
+use euclid::Transform3D;
+use parrot::{
+    binding::{Binding, BindingGroup, BindingType},
+    buffers::*,
+    painter::RenderPassExtention,
+    pipeline::{Pipeline, PipelineCore, PipelineDescription, Set},
+    transform::ScreenSpace,
+    vertex::VertexFormat,
+    Painter, Plumber,
+};
+use std::{collections::HashMap, ops::Deref};
+use wgpu::RenderPass;
+
+/// Pipeline for drawing signed distance field textures, such as [`crate::graphics::SdfSprite`]. The distance is read from the red
+/// channel of the texture with the edge at 0.5, so the shape stays crisp at any scale. Breakdowns without a texture are skipped
+#[derive(Debug)]
+pub struct SdfPipe {
+    pub vertex_buffer: VertexBuffer,
+    pub index_buffer: IndexBuffer,
+    pub groups: Vec<Group>,
+    pub texture_binds: HashMap<usize, BindingGroup>,
+    /// Pipeline core to deref to
+    core: PipelineCore,
+}
+
+impl Deref for SdfPipe {
+    type Target = PipelineCore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.core
+    }
+}
+
+impl<'a> Plumber<'a> for SdfPipe {
+    type PrepareContext = RenderInformation<SdfVertex>;
+    type Uniforms = [[f32; 4]; 4];
+
+    fn description() -> PipelineDescription<'a> {
+        PipelineDescription::new(
+            &SdfVertex::VERTEX_LAYOUT,
+            Some(&[
+                Set(
+                    &[
+                        Binding {
+                            binding: BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                            },
+                            stage: wgpu::ShaderStages::FRAGMENT,
+                        },
+                        Binding {
+                            binding: BindingType::Sampler,
+                            stage: wgpu::ShaderStages::FRAGMENT,
+                        },
+                    ],
+                    Some("Sdf texture bind group"),
+                ),
+                Set(
+                    &[Binding {
+                        binding: BindingType::UniformBuffer,
+                        stage: wgpu::ShaderStages::VERTEX,
+                    }],
+                    Some("Sdf transform bind group"),
+                ),
+            ]),
+            parrot::shader::ShaderFile::Wgsl(include_str!("./shaders/sdf.wgsl")),
+            Some("Sdf pipeline")
+        )
+    }
+
+    fn setup(pipe: Pipeline, paint: &Painter) -> Self {
+        // Allocating a bunch of capacity for the buffers to prevent resizing them 1000 times
+        let blank_vertex: Vec<SdfVertex> = Vec::with_capacity(VERTEX_INIT_SIZE as usize);
+        let blank_index: Vec<u16> = Vec::with_capacity(INDEX_INIT_SIZE as usize);
+        let blank_transform: Transform3D<f32, ScreenSpace, ScreenSpace> = Transform3D::identity();
+
+        let vertex_buffer = paint.vertex_buffer(blank_vertex.as_slice(), Some("Sdf vertex buffer"));
+        let index_buffer = paint.index_buffer(blank_index.as_slice(), Some("Sdf index buffer"));
+        let transform_buffer = paint.uniform_buffer(&[blank_transform.to_arrays()], Some("Sdf transform buffer"));
+        let bind_group = paint.binding_group(
+            &pipe.layout.b_layouts[1],
+            &[&transform_buffer],
+            Some("Sdf transform binding group"),
+        ).expect("Failed to create the sdf pipeline's transform binding group");
+
+        Self {
+            vertex_buffer,
+            index_buffer,
+            groups: vec![],
+            texture_binds: HashMap::new(),
+            core: PipelineCore {
+                pipeline: pipe,
+                bindings: vec![bind_group],
+                uniforms: vec![transform_buffer],
+            },
+        }
+    }
+
+    fn prepare(
+        &'a mut self,
+        prep: Self::PrepareContext,
+        paint: &mut Painter,
+    ) -> Vec<(&'a mut UniformBuffer, Vec<Self::Uniforms>)> {
+        let mut vertices: Vec<SdfVertex> = vec![];
+        let mut indices: Vec<u16> = vec![];
+        let mut groups: Vec<Group> = vec![];
+
+        // Combine into a big ol array.
+        for mut sdf in prep.0 {
+            // Nothing to draw without a distance field
+            let tex = match sdf.texture {
+                Some(tex) if !sdf.vertices.is_empty() => tex,
+                _ => continue,
+            };
+            let start = vertices.len();
+            vertices.append(&mut sdf.vertices);
+            let start2 = indices.len() as u32;
+            indices.append(&mut sdf.indicies.iter().map(|ind| ind + start as u16).collect());
+            // Check if we have already bound the texture
+            if !self.texture_binds.contains_key(&tex.id) {
+                self.add_texture(paint, &tex);
+            }
+            // Extend the previous group if it uses the same texture so they're drawn together
+            match groups.last_mut() {
+                Some(group) if group.tex_id == tex.id && group.range.end == start2 => {
+                    group.range.end = indices.len() as u32;
+                }
+                _ => groups.push(Group {
+                    range: start2..indices.len() as u32,
+                    tex_id: tex.id,
+                }),
+            }
+        }
+
+        self.groups = groups;
+
+        // Update the vertex and index buffers
+        if let Some(v) = paint.update_vertex_buffer(&vertices, &mut self.vertex_buffer) {
+            self.vertex_buffer = v;
+        }
+        if let Some(i) = paint.update_index_buffer(indices, &mut self.index_buffer) {
+            self.index_buffer = i;
+        }
+
+        // Return info for parrot to update our uniform buffers
+        vec![(&mut self.core.uniforms[0], vec![prep.1.to_arrays()])]
+    }
+}
+
+impl Render for SdfPipe {
+    type Vertex = SdfVertex;
+
+    fn render<'a>(&'a mut self, _paint: &mut Painter, pass: &mut RenderPass<'a>) {
+        if self.groups.is_empty() {
+            return;
+        }
+        pass.set_parrot_pipeline(self);
+        pass.set_parrot_vertex_buffer(&self.vertex_buffer);
+        pass.set_parrot_index_buffer(&self.index_buffer);
+
+        let mut prev_tex = None;
+        for g in &self.groups {
+            if prev_tex != Some(g.tex_id) {
+                pass.set_binding(
+                    self.texture_binds
+                        .get(&g.tex_id)
+                        .expect("Cannot find texture in textures map"),
+                    &[],
+                );
+                prev_tex = Some(g.tex_id);
+            }
+            pass.draw_parrot_indexed_checked(&self.index_buffer, g.range.clone(), 0..1);
+        }
+    }
+
+    fn swap_uniforms(&mut self, paint: &Painter, buffers: Vec<(usize, UniformBuffer)>) {
+        super::swap_uniforms(&mut self.core, paint, buffers, "sdf");
+    }
+}
+
+impl SdfPipe {
+    pub fn add_texture(&mut self, paint: &Painter, tex: &Texture) {
+        let bind_group = paint.binding_group(
+            &self.core.pipeline.layout.b_layouts[0],
+            &[&*tex.texture, &*tex.sampler],
+            Some(&format!("{} sdf binding group", tex.name)),
+        ).unwrap_or_else(|e| panic!("Failed to bind texture {} to the sdf pipeline: {}", tex.name, e));
+        self.texture_binds.insert(tex.id, bind_group);
+    }
+
+    /// Removes all the texture bindings. Useful when resetting a scene.
+    pub fn clear_textures(&mut self) {
+        self.texture_binds.drain();
+    }
+}
+
+/// The vertex for signed distance fields. The colors and edge settings are per vertex rather than a uniform so sprites with
+/// different styles can still share a draw call
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SdfVertex {
+    /// Position of the vertex in worldspace
+    pub pos: [f32; 3],
+    /// The u-v coordinates of the vertex on the texture
+    pub tex_coords: [f32; 2],
+    /// The color inside the shape
+    pub color: [f32; 4],
+    /// The color of the outline
+    pub outline_color: [f32; 4],
+    /// The smoothing and the outline width, both in distance field units
+    pub params: [f32; 2],
+}
+
+impl SdfVertex {
+    pub const VERTEX_LAYOUT: [VertexFormat; 5] = [
+        VertexFormat::Floatx3,
+        VertexFormat::Floatx2,
+        VertexFormat::Floatx4,
+        VertexFormat::Floatx4,
+        VertexFormat::Floatx2,
+    ];
+}
@@ -0,0 +1,55 @@
+// Vertex shader
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+}
+@group(1) @binding(0)
+var<uniform> camera: CameraUniform;
+
+struct VertexInput {
+    // Corner of a 1x1 quad centred on the origin
+    @location(0) position: vec2<f32>,
+}
+
+struct InstanceInput {
+    // Rows of the model matrix
+    @location(1) model_0: vec4<f32>,
+    @location(2) model_1: vec4<f32>,
+    @location(3) model_2: vec4<f32>,
+    @location(4) model_3: vec4<f32>,
+    // xy is the top left u-v coordinate of the texture region and zw the bottom right
+    @location(5) region: vec4<f32>,
+    @location(6) color: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+@vertex
+fn vs_main(
+    vertex: VertexInput,
+    instance: InstanceInput,
+) -> VertexOutput {
+    let model = mat4x4<f32>(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
+    var out: VertexOutput;
+    // Map the corner to 0-1 with v pointing down, then into the texture region
+    let t = vec2<f32>(vertex.position.x + 0.5, 0.5 - vertex.position.y);
+    out.tex_coords = mix(instance.region.xy, instance.region.zw, t);
+    out.color = instance.color;
+    out.clip_position = camera.view_proj * model * vec4<f32>(vertex.position, 0.0, 1.0);
+    return out;
+}
+
+// Fragment shader
+
+@group(0) @binding(0)
+var t_diffuse: texture_2d<f32>;
+@group(0) @binding(1)
+var s_diffuse: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(t_diffuse, s_diffuse, in.tex_coords) * in.color;
+}
@@ -0,0 +1,58 @@
+// Vertex shader
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+}
+@group(1) @binding(0)
+var<uniform> camera: CameraUniform;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+    @location(2) color: vec4<f32>,
+    @location(3) outline_color: vec4<f32>,
+    // x is the smoothing, y is the outline width
+    @location(4) params: vec2<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) outline_color: vec4<f32>,
+    @location(3) params: vec2<f32>,
+}
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.tex_coords = model.tex_coords;
+    out.color = model.color;
+    out.outline_color = model.outline_color;
+    out.params = model.params;
+    out.clip_position = camera.view_proj * vec4<f32>(model.position, 1.0);
+    return out;
+}
+
+// Fragment shader
+
+@group(0) @binding(0)
+var t_sdf: texture_2d<f32>;
+@group(0) @binding(1)
+var s_sdf: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // The distance is stored in the red channel with the edge at 0.5
+    let dist = textureSample(t_sdf, s_sdf, in.tex_coords).r;
+    let smoothing = in.params.x;
+    let outline = in.params.y;
+    let fill = smoothstep(0.5 - smoothing, 0.5 + smoothing, dist);
+    if (outline <= 0.0) {
+        return vec4<f32>(in.color.rgb, in.color.a * fill);
+    }
+    let edge = smoothstep(0.5 - outline - smoothing, 0.5 - outline + smoothing, dist);
+    let color = mix(in.outline_color, in.color, fill);
+    return vec4<f32>(color.rgb, color.a * edge);
+}
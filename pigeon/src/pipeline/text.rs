@@ -0,0 +1,476 @@
+use super::{Render, RenderInformation, INDEX_INIT_SIZE, VERTEX_INIT_SIZE};
+use super::quad::QuadVertex;
+use crate::graphics::{atlas::ShelfPacker, Font, FontError};
+use euclid::{Rect, Size2D, Transform3D};
+use parrot::{
+    binding::{Binding, BindingGroup, BindingType},
+    buffers::*,
+    painter::RenderPassExtention,
+    pipeline::{Pipeline, PipelineCore, PipelineDescription, Set},
+    transform::ScreenSpace,
+    Painter, Plumber, Sampler,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ops::Deref,
+    rc::Rc,
+};
+use wgpu::RenderPass;
+
+/// The size of the glyph atlas when first created
+pub const GLYPH_ATLAS_INIT_SIZE: u32 = 256;
+/// The largest the glyph atlas can grow to. Glyphs that haven't been used recently are evicted once it is full
+pub const GLYPH_ATLAS_MAX_SIZE: u32 = 2048;
+
+/// The vertex for text. Each glyph is a quad of four vertices. The texture coordinates are worked out by [`TextPipe`]
+/// once the glyph has a spot in the atlas
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct TextVertex {
+    /// Position of the vertex in worldspace
+    pub pos: [f32; 3],
+    /// Which corner of the glyph the vertex is, from (0, 0) at the top left to (1, 1) at the bottom right
+    pub corner: [f32; 2],
+    /// The color of the text
+    pub color: [f32; 4],
+    /// The glyph's slot in the glyph cache
+    pub glyph: u32,
+}
+
+impl TextVertex {
+    pub fn new(pos: [f32; 3], corner: [f32; 2], color: [f32; 4], glyph: u32) -> Self {
+        Self { pos, corner, color, glyph }
+    }
+}
+
+/// Identifies a rasterised glyph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    font: usize,
+    index: u16,
+    px: u32,
+}
+
+#[derive(Debug)]
+struct CachedGlyph {
+    key: GlyphKey,
+    size: Size2D<u32, ScreenSpace>,
+    /// Coverage of each pixel, one byte per pixel
+    bitmap: Vec<u8>,
+    /// Where the glyph is in the atlas. None until it has been packed
+    rect: Option<Rect<u32, ScreenSpace>>,
+    /// The last frame the glyph was used in
+    last_used: u64,
+}
+
+/// Keeps track of which glyphs are in the atlas and where. Shared between a [`TextPipe`] and its fonts
+#[derive(Debug)]
+pub struct GlyphCache {
+    slots: Vec<Option<CachedGlyph>>,
+    free: Vec<u32>,
+    lookup: HashMap<GlyphKey, u32>,
+    packer: ShelfPacker,
+    size: Size2D<u32, ScreenSpace>,
+    /// The largest the atlas can grow to before glyphs are evicted
+    max_size: u32,
+    /// Slots packed since the atlas was last updated
+    uploads: Vec<u32>,
+    frame: u64,
+}
+
+impl GlyphCache {
+    pub(crate) fn new() -> Self {
+        let size = Size2D::new(GLYPH_ATLAS_INIT_SIZE, GLYPH_ATLAS_INIT_SIZE);
+        Self {
+            slots: vec![],
+            free: vec![],
+            lookup: HashMap::new(),
+            packer: ShelfPacker::new(size),
+            size,
+            max_size: GLYPH_ATLAS_MAX_SIZE,
+            uploads: vec![],
+            frame: 0,
+        }
+    }
+
+    /// Returns the slot of a glyph, rasterising it if it isn't cached
+    pub(crate) fn glyph(&mut self, font_id: usize, font: &fontdue::Font, index: u16, px: u32) -> u32 {
+        let key = GlyphKey { font: font_id, index, px };
+        if let Some(&slot) = self.lookup.get(&key) {
+            if let Some(glyph) = &mut self.slots[slot as usize] {
+                glyph.last_used = self.frame;
+            }
+            return slot;
+        }
+
+        let (metrics, bitmap) = font.rasterize_indexed(index, px as f32);
+        let glyph = CachedGlyph {
+            key,
+            size: Size2D::new(metrics.width as u32, metrics.height as u32),
+            bitmap,
+            rect: None,
+            last_used: self.frame,
+        };
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot as usize] = Some(glyph);
+                slot
+            }
+            None => {
+                self.slots.push(Some(glyph));
+                self.slots.len() as u32 - 1
+            }
+        };
+        self.lookup.insert(key, slot);
+        slot
+    }
+
+    /// Find a spot in the atlas for every glyph that doesn't have one, growing the atlas or evicting glyphs
+    /// that weren't used this frame if it's full. Returns true if the atlas was rebuilt, in which case every glyph must be uploaded again
+    fn pack(&mut self) -> bool {
+        let mut rebuilt = false;
+        while !self.pack_pending() {
+            if self.size.width < self.max_size {
+                self.size *= 2;
+                log::info!("Growing glyph atlas >> Size: {:?}", self.size);
+            } else if !self.evict() {
+                log::error!("Glyph atlas is full of glyphs used this frame. Some glyphs won't be drawn");
+                break;
+            }
+
+            // Start packing again from scratch
+            rebuilt = true;
+            self.packer = ShelfPacker::new(self.size);
+            for glyph in self.slots.iter_mut().flatten() {
+                glyph.rect = None;
+            }
+        }
+
+        if rebuilt {
+            self.uploads = self.slots
+                .iter()
+                .enumerate()
+                .filter(|(_, g)| g.as_ref().is_some_and(|g| g.rect.is_some()))
+                .map(|(i, _)| i as u32)
+                .collect();
+        }
+        self.frame += 1;
+        rebuilt
+    }
+
+    /// Packs the glyphs without a spot. Returns false if the atlas is full
+    fn pack_pending(&mut self) -> bool {
+        // Packing the tallest glyphs first wastes less space
+        let mut pending: Vec<usize> = self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| g.as_ref().is_some_and(|g| g.rect.is_none()))
+            .map(|(i, _)| i)
+            .collect();
+        pending.sort_by_key(|i| std::cmp::Reverse(self.slots[*i].as_ref().map_or(0, |g| g.size.height)));
+
+        for i in pending {
+            if let Some(glyph) = &mut self.slots[i] {
+                // Leave a pixel between glyphs so they don't bleed into each other when sampled
+                match self.packer.pack(glyph.size + Size2D::new(1, 1)) {
+                    Ok(origin) => {
+                        glyph.rect = Some(Rect::new(origin, glyph.size));
+                        self.uploads.push(i as u32);
+                    }
+                    Err(_) => return false,
+                }
+            }
+        }
+        true
+    }
+
+    /// Removes glyphs that weren't used this frame. Returns false if there were none
+    fn evict(&mut self) -> bool {
+        let mut evicted = false;
+        for (i, slot) in self.slots.iter_mut().enumerate() {
+            if slot.as_ref().is_some_and(|g| g.last_used < self.frame) {
+                if let Some(glyph) = slot.take() {
+                    self.lookup.remove(&glyph.key);
+                    self.free.push(i as u32);
+                    evicted = true;
+                }
+            }
+        }
+        log::info!("Evicted glyphs from the glyph atlas >> Evicted: {}", evicted);
+        evicted
+    }
+
+    /// Returns the uv coordinates of a glyph's corner, if the glyph is in the atlas
+    fn uv(&self, slot: u32, corner: [f32; 2]) -> Option<(f32, f32)> {
+        let rect = self.slots.get(slot as usize)?.as_ref()?.rect?;
+        Some((
+            (rect.min_x() as f32 + corner[0] * rect.width() as f32) / self.size.width as f32,
+            (rect.min_y() as f32 + corner[1] * rect.height() as f32) / self.size.height as f32,
+        ))
+    }
+}
+
+/// Pipeline for drawing text. Designed to work with [`crate::graphics::Text`]. Glyphs from every font loaded by the pipeline share one atlas
+#[derive(Debug)]
+pub struct TextPipe {
+    pub vertex_buffer: VertexBuffer,
+    pub index_buffer: IndexBuffer,
+    /// The number of indicies to draw
+    pub index_count: u32,
+    /// The glyph atlas. Each pixel is the glyph's coverage
+    pub atlas: parrot::Texture,
+    atlas_bind: BindingGroup,
+    sampler: Sampler,
+    cache: Rc<RefCell<GlyphCache>>,
+    fonts: usize,
+    /// Pipeline core to deref to
+    core: PipelineCore,
+}
+
+impl Deref for TextPipe {
+    type Target = PipelineCore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.core
+    }
+}
+
+impl<'a> Plumber<'a> for TextPipe {
+    type PrepareContext = RenderInformation<TextVertex>;
+    type Uniforms = [[f32; 4]; 4];
+
+    fn description() -> PipelineDescription<'a> {
+        PipelineDescription::new(
+            &QuadVertex::VERTEX_LAYOUT,
+            Some(&[
+                Set(
+                    &[
+                        Binding {
+                            binding: BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                            },
+                            stage: wgpu::ShaderStages::FRAGMENT,
+                        },
+                        Binding {
+                            binding: BindingType::Sampler,
+                            stage: wgpu::ShaderStages::FRAGMENT,
+                        },
+                    ],
+                    Some("Glyph atlas bind group"),
+                ),
+                Set(
+                    &[Binding {
+                        binding: BindingType::UniformBuffer,
+                        stage: wgpu::ShaderStages::VERTEX,
+                    }],
+                    Some("Text transform bind group"),
+                ),
+            ]),
+            parrot::shader::ShaderFile::Wgsl(include_str!("./shaders/text.wgsl")),
+            Some("Text pipeline")
+        )
+    }
+
+    fn setup(pipe: Pipeline, paint: &Painter) -> Self {
+        // Allocating a bunch of capacity for the buffers to prevent resizing them 1000 times
+        let blank_vertex: Vec<QuadVertex> = Vec::with_capacity(VERTEX_INIT_SIZE as usize);
+        let blank_index: Vec<u16> = Vec::with_capacity(INDEX_INIT_SIZE as usize);
+        let blank_transform: Transform3D<f32, ScreenSpace, ScreenSpace> = Transform3D::identity();
+
+        let vertex_buffer = paint.vertex_buffer(blank_vertex.as_slice(), Some("Text vertex buffer"));
+        let index_buffer = paint.index_buffer(blank_index.as_slice(), Some("Text index buffer"));
+        let transform_buffer = paint.uniform_buffer(
+            &[blank_transform.to_arrays()],
+            Some("Text transform buffer"),
+        );
+        let bind_group = paint.binding_group(
+            &pipe.layout.b_layouts[1],
+            &[&transform_buffer],
+            Some("Text transform binding group"),
+        ).expect("Failed to create the text pipeline's transform binding group");
+
+        let cache = GlyphCache::new();
+        let sampler = paint.sampler(wgpu::FilterMode::Linear, wgpu::FilterMode::Linear, Some("Glyph sampler"));
+        let atlas = Self::create_atlas(paint, cache.size);
+        let atlas_bind = paint.binding_group(
+            &pipe.layout.b_layouts[0],
+            &[&atlas, &sampler],
+            Some("Glyph atlas binding group"),
+        ).expect("Failed to bind the glyph atlas to the text pipeline");
+
+        Self {
+            vertex_buffer,
+            index_buffer,
+            index_count: 0,
+            atlas,
+            atlas_bind,
+            sampler,
+            cache: Rc::new(RefCell::new(cache)),
+            fonts: 0,
+            core: PipelineCore {
+                pipeline: pipe,
+                bindings: vec![bind_group],
+                uniforms: vec![transform_buffer],
+            },
+        }
+    }
+
+    fn prepare(
+        &'a mut self,
+        prep: Self::PrepareContext,
+        paint: &mut Painter,
+    ) -> Vec<(&'a mut UniformBuffer, Vec<Self::Uniforms>)> {
+        let rebuilt = self.cache.borrow_mut().pack();
+        self.update_atlas(paint, rebuilt);
+
+        let cache = self.cache.borrow();
+        let mut vertices: Vec<QuadVertex> = vec![];
+        let mut indices: Vec<u16> = vec![];
+
+        for text in prep.0 {
+            // Each glyph is a quad of four vertices
+            for quad in text.vertices.chunks_exact(4) {
+                let uvs: Option<Vec<(f32, f32)>> = quad.iter().map(|v| cache.uv(v.glyph, v.corner)).collect();
+                // Skip glyphs that didn't fit in the atlas
+                let Some(uvs) = uvs else { continue };
+
+                let start = u16::try_from(vertices.len()).expect("Too much text for 16 bit indicies. Split it into smaller texts");
+                for (v, uv) in quad.iter().zip(uvs) {
+                    vertices.push(
+                        QuadVertex::new(v.pos[0], v.pos[1], v.pos[2], uv.0, uv.1)
+                            .with_color((v.color[0], v.color[1], v.color[2], v.color[3])),
+                    );
+                }
+                indices.extend([0, 1, 3, 0, 3, 2].iter().map(|i| i + start));
+            }
+        }
+        drop(cache);
+
+        self.index_count = indices.len() as u32;
+
+        // Update the vertex and index buffers
+        if let Some(v) = paint.update_vertex_buffer(&vertices, &mut self.vertex_buffer) {
+            self.vertex_buffer = v;
+        }
+        if let Some(i) = paint.update_index_buffer(indices, &mut self.index_buffer) {
+            self.index_buffer = i;
+        }
+
+        // Return info for parrot to update our uniform buffers
+        vec![(&mut self.core.uniforms[0], vec![prep.1.to_arrays()])]
+    }
+}
+
+impl Render for TextPipe {
+    type Vertex = TextVertex;
+
+    fn render<'a>(&'a mut self, _paint: &mut Painter, pass: &mut RenderPass<'a>) {
+        if self.index_count == 0 {
+            return;
+        }
+        pass.set_parrot_pipeline(self);
+        pass.set_binding(&self.atlas_bind, &[]);
+        pass.set_parrot_vertex_buffer(&self.vertex_buffer);
+        pass.set_parrot_index_buffer(&self.index_buffer);
+        pass.draw_parrot_indexed(0..self.index_count, 0..1);
+    }
+
+    fn swap_uniforms(&mut self, paint: &Painter, buffers: Vec<(usize, UniformBuffer)>) {
+        super::swap_uniforms(&mut self.core, paint, buffers, "text");
+    }
+}
+
+impl TextPipe {
+    /// Load a font from the bytes of a ttf or otf file
+    pub fn load_font(&mut self, bytes: &[u8]) -> Result<Rc<Font>, FontError> {
+        log::info!("Loading font >> Id: {}", self.fonts);
+        let font = Font::new(self.fonts, bytes, self.cache.clone())?;
+        self.fonts += 1;
+        Ok(Rc::new(font))
+    }
+
+    fn create_atlas(paint: &Painter, size: Size2D<u32, ScreenSpace>) -> parrot::Texture {
+        paint.texture(
+            size,
+            wgpu::TextureFormat::R8Unorm,
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            Some("Glyph atlas"),
+            false,
+        )
+    }
+
+    /// Upload newly packed glyphs, recreating the atlas texture if it has grown
+    fn update_atlas(&mut self, paint: &Painter, rebuilt: bool) {
+        let mut cache = self.cache.borrow_mut();
+        if rebuilt && self.atlas.size != cache.size {
+            self.atlas = Self::create_atlas(paint, cache.size);
+            self.atlas_bind = paint.binding_group(
+                &self.core.pipeline.layout.b_layouts[0],
+                &[&self.atlas, &self.sampler],
+                Some("Glyph atlas binding group"),
+            ).expect("Failed to rebind the resized glyph atlas to the text pipeline");
+        }
+
+        for slot in std::mem::take(&mut cache.uploads) {
+            if let Some(CachedGlyph { rect: Some(rect), bitmap, .. }) = &cache.slots[slot as usize] {
+                parrot::Texture::transfer(&self.atlas, bitmap, *rect, &paint.device);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::text::tests::TEST_FONT;
+
+    fn font() -> fontdue::Font {
+        fontdue::Font::from_bytes(TEST_FONT, fontdue::FontSettings::default()).unwrap()
+    }
+
+    #[test]
+    fn atlas_grows() {
+        let font = font();
+        let mut cache = GlyphCache::new();
+        let h = cache.glyph(0, &font, font.lookup_glyph_index('H'), 16);
+        assert!(!cache.pack());
+        assert_eq!(cache.uploads, vec![h]);
+
+        // Two of these don't fit in the first atlas
+        cache.uploads.clear();
+        let big = cache.glyph(0, &font, font.lookup_glyph_index('H'), 300);
+        let bigger = cache.glyph(0, &font, font.lookup_glyph_index('H'), 301);
+        assert!(cache.pack());
+        assert_eq!(cache.size, Size2D::new(GLYPH_ATLAS_INIT_SIZE * 2, GLYPH_ATLAS_INIT_SIZE * 2));
+        // Everything is uploaded again into the new atlas
+        let mut uploads = cache.uploads.clone();
+        uploads.sort();
+        assert_eq!(uploads, vec![h, big, bigger]);
+        assert!([h, big, bigger].iter().all(|slot| cache.uv(*slot, [1.0, 1.0]).is_some()));
+    }
+
+    #[test]
+    fn unused_glyphs_are_evicted() {
+        let font = font();
+        let mut cache = GlyphCache::new();
+        cache.max_size = GLYPH_ATLAS_INIT_SIZE;
+        let old = cache.glyph(0, &font, font.lookup_glyph_index('H'), 300);
+        assert!(!cache.pack());
+
+        // Next frame the old glyph isn't used and the new one doesn't fit beside it
+        let new = cache.glyph(0, &font, font.lookup_glyph_index('H'), 301);
+        assert!(cache.pack());
+        assert_eq!(cache.size.width, GLYPH_ATLAS_INIT_SIZE);
+        assert!(cache.uv(old, [0.0, 0.0]).is_none());
+        assert!(cache.uv(new, [0.0, 0.0]).is_some());
+        assert_eq!(cache.lookup.len(), 1);
+
+        // The evicted glyph is rasterised again into its old slot when it's used
+        let again = cache.glyph(0, &font, font.lookup_glyph_index('H'), 300);
+        assert_eq!(again, old);
+        assert_eq!(cache.lookup.len(), 2);
+        assert!(cache.uv(again, [0.0, 0.0]).is_none());
+    }
+}
@@ -26,9 +26,9 @@ impl<'a> Plumber<'a> for TrianglePipe {
     type Uniforms = [[f32;4];4];
 
     fn description() -> PipelineDescription<'a> {
-        PipelineDescription {
-            vertex_layout: &TriangleVertex::VERTEX_LAYOUT,
-            pipeline_layout: Some(&[
+        PipelineDescription::new(
+            &TriangleVertex::VERTEX_LAYOUT,
+            Some(&[
                 Set(&[
                     Binding {
                         binding: BindingType::UniformBuffer,
@@ -36,9 +36,9 @@ impl<'a> Plumber<'a> for TrianglePipe {
                     }
                 ], Some("Triangle transform bind group"))
             ]),
-            shader: parrot::shader::ShaderFile::Wgsl(include_str!("./shaders/triangle.wgsl")),
-            name: Some("Triangle pipeline"),
-        }
+            parrot::shader::ShaderFile::Wgsl(include_str!("./shaders/triangle.wgsl")),
+            Some("Triangle pipeline")
+        )
     }
 
     fn setup(pipe: Pipeline, paint: &Painter) -> Self {
@@ -50,7 +50,8 @@ impl<'a> Plumber<'a> for TrianglePipe {
         let vertex_buffer = paint.vertex_buffer(blank_vertex.as_slice(), Some("Triangle vertex buffer"));
         let index_buffer = paint.index_buffer(blank_index.as_slice(), Some("Triangle index buffer"));
         let transform_buffer = paint.uniform_buffer(&[blank_transform.to_arrays()], Some("Triangle transform buffer"));
-        let bind_group = paint.binding_group(&pipe.layout.b_layouts[0], &[&transform_buffer], Some("Triangle transform binding group"));
+        let bind_group = paint.binding_group(&pipe.layout.b_layouts[0], &[&transform_buffer], Some("Triangle transform binding group"))
+            .expect("Failed to create the triangle pipeline's transform binding group");
 
         Self {
             vertex_buffer,
@@ -69,6 +70,10 @@ impl<'a> Plumber<'a> for TrianglePipe {
 
         // Combine into a big ol array.
         for mut tri in prep.0 {
+            // Skip empty breakdowns
+            if tri.vertices.is_empty() {
+                continue;
+            }
             let start = vertices.len();
             vertices.append(&mut tri.vertices);
             indices.append(&mut tri.indicies.iter().map(|ind| ind + start as u16).collect());
@@ -91,6 +96,10 @@ impl Render for TrianglePipe {
     type Vertex = TriangleVertex;
 
     fn render<'a>(&'a mut self, _paint: &mut Painter, pass: &mut RenderPass<'a>) {
+        // Nothing was prepared, so the buffers are empty
+        if self.vertex_buffer.len == 0 {
+            return;
+        }
         // Set pipeline
         pass.set_parrot_pipeline(self);
 
@@ -100,7 +109,11 @@ impl Render for TrianglePipe {
         
 
         // Draw
-        pass.draw_parrot_indexed(0..self.index_buffer.size, 0..1);
+        pass.draw_parrot_indexed_checked(&self.index_buffer, self.index_buffer.full_range(), 0..1);
+    }
+
+    fn swap_uniforms(&mut self, paint: &Painter, buffers: Vec<(usize, UniformBuffer)>) {
+        super::swap_uniforms(&mut self.core, paint, buffers, "triangle");
     }
 }
 